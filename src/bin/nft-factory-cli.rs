@@ -0,0 +1,1815 @@
+//! Admin CLI for operating drops: derives PDAs, builds and sends transactions
+//! and prints decoded program state.
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use mpl_token_metadata::{
+    accounts::{CollectionAuthorityRecord, MasterEdition, Metadata, TokenRecord},
+    types::Creator,
+};
+use nft_factory::{client::*, instruction, state::*};
+use serde_json::json;
+use solana_rpc_client::rpc_client::RpcClient;
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    compute_budget::ComputeBudgetInstruction,
+    instruction::Instruction,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    signature::{read_keypair_file, Keypair, Signer},
+    system_instruction,
+    transaction::Transaction,
+};
+use spl_associated_token_account::get_associated_token_address_with_program_id;
+use std::{error::Error, str::FromStr};
+
+type CliResult<T = ()> = Result<T, Box<dyn Error>>;
+
+/// Metaplex CPIs in mint paths need more than the default compute budget.
+const MINT_COMPUTE_UNITS: u32 = 400_000;
+/// Compute limit of a whole transaction.
+const MAX_COMPUTE_UNITS: u32 = 1_400_000;
+
+#[derive(Parser)]
+#[command(name = "nft-factory-cli", about = "Operate nft_factory drops")]
+struct Cli {
+    /// RPC endpoint, defaults to a local test validator
+    #[arg(long, global = true, default_value = "http://127.0.0.1:8899")]
+    url: String,
+    /// Fee payer and signer keypair
+    #[arg(long, global = true, default_value = "~/.config/solana/id.json")]
+    keypair: String,
+    /// Program id of the deployed nft_factory
+    #[arg(long, global = true, default_value_t = nft_factory::id())]
+    program_id: Pubkey,
+    /// Print the serialized instructions instead of sending them
+    #[arg(long, global = true)]
+    dry_run: bool,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Create or update the global config
+    Configure {
+        #[arg(long)]
+        authority: Pubkey,
+        /// Marketplace fee in basis points of every sale after royalties
+        #[arg(long, default_value_t = 0)]
+        platform_fee: u16,
+        /// Account receiving the marketplace fee, defaults to the authority
+        #[arg(long)]
+        fee_recipient: Option<Pubkey>,
+    },
+    /// Mint a collection NFT and approve its pda_creator as collection authority
+    CreateCollection {
+        #[command(flatten)]
+        metadata: MetadataArgs,
+        #[arg(long, default_value_t = 0)]
+        fee: u16,
+        #[arg(long, value_enum, default_value_t = TokenProgram::SplToken)]
+        token_program: TokenProgram,
+    },
+    /// Register a collection for sale
+    AddCollection {
+        #[arg(long)]
+        collection_mint: Pubkey,
+        #[arg(long)]
+        admin: Pubkey,
+        #[command(flatten)]
+        metadata: MetadataArgs,
+        #[arg(long, default_value_t = 0)]
+        fee: u16,
+        /// Creator as ADDRESS:SHARE, repeat for each creator
+        #[arg(long = "creator", value_parser = parse_creator)]
+        creators: Vec<Creator>,
+        #[arg(long, value_enum, default_value_t = TokenProgram::SplToken)]
+        token_program: TokenProgram,
+        #[arg(long)]
+        programmable: bool,
+        #[arg(long)]
+        rule_set: Option<Pubkey>,
+        /// Key that must co-sign every mint
+        #[arg(long)]
+        gatekeeper: Option<Pubkey>,
+        /// Lamports charged instead of failing a mint in the wrong phase, with a
+        /// used gate item or without the required tokens
+        #[arg(long)]
+        bot_tax: Option<u64>,
+        /// Items held back from the sale for admin mints
+        #[arg(long, default_value_t = 0)]
+        reserve: u64,
+        /// Most items the collection mints
+        #[arg(long, default_value_t = MAX_COLLECTION_SUPPLY)]
+        supply_cap: u64,
+    },
+    /// Change fields of a registered collection, unset options are kept
+    UpdateCollection {
+        #[arg(long)]
+        collection_mint: Pubkey,
+        #[arg(long)]
+        name: Option<String>,
+        #[arg(long)]
+        symbol: Option<String>,
+        #[arg(long)]
+        uri: Option<String>,
+        #[arg(long)]
+        fee: Option<u16>,
+        /// Creator as ADDRESS:SHARE, repeat for each creator, replaces all
+        /// creators
+        #[arg(long = "creator", value_parser = parse_creator)]
+        creators: Vec<Creator>,
+        #[arg(long)]
+        admin: Option<Pubkey>,
+        /// New supply cap
+        #[arg(long)]
+        supply_cap: Option<u64>,
+    },
+    /// Set the price, start time and payee of a collection sale
+    AddPromotion {
+        #[arg(long)]
+        collection_mint: Pubkey,
+        /// Price in lamports, the start price of dutch auctions
+        #[arg(long)]
+        price: u64,
+        /// Unix timestamp the sale opens at
+        #[arg(long)]
+        start_ts: u64,
+        /// Account receiving the sale proceeds
+        #[arg(long)]
+        charge: Pubkey,
+        /// Hold proceeds in the collection treasury until the sale is finalized
+        #[arg(long)]
+        escrow: bool,
+        #[command(flatten)]
+        dutch: DutchArgs,
+        #[command(flatten)]
+        gate: GateArgs,
+        #[command(flatten)]
+        token: TokenRequirementArgs,
+    },
+    /// Buy one item of a collection
+    Mint {
+        #[arg(long)]
+        collection_mint: Pubkey,
+        /// Item of the gate collection held by the signer, required during a
+        /// gated phase
+        #[arg(long)]
+        gate_mint: Option<Pubkey>,
+        /// Gatekeeper keypair co-signing the mint, required when the
+        /// collection has a gatekeeper
+        #[arg(long)]
+        gatekeeper_keypair: Option<String>,
+        /// Items to mint in one transaction
+        #[arg(
+            long,
+            default_value_t = 1,
+            value_parser = clap::value_parser!(u8).range(1..=MAX_MINT_COUNT as i64)
+        )]
+        count: u8,
+    },
+    /// Create a fungible token with its vault and metadata
+    CreateToken {
+        #[command(flatten)]
+        metadata: MetadataArgs,
+        #[arg(long, default_value_t = 9)]
+        decimals: u8,
+        #[arg(long, value_enum, default_value_t = TokenProgram::SplToken)]
+        token_program: TokenProgram,
+        /// Token-2022 only: point the mint at its metadata account
+        #[arg(long)]
+        metadata_pointer: bool,
+        /// Token-2022 only: transfer fee in basis points
+        #[arg(long)]
+        transfer_fee_bps: Option<u16>,
+        /// Token-2022 only: maximum transfer fee in base units
+        #[arg(long, default_value_t = u64::MAX)]
+        maximum_fee: u64,
+        /// Token-2022 only: make the token non-transferable
+        #[arg(long)]
+        non_transferable: bool,
+    },
+    /// Pay the escrowed proceeds of a sale to its charge account
+    FinalizeSale {
+        #[arg(long)]
+        collection_mint: Pubkey,
+    },
+    /// Claim back what was paid above a dutch auction's clearing price
+    ClaimRebate {
+        #[arg(long)]
+        collection_mint: Pubkey,
+        #[arg(long)]
+        mint: Pubkey,
+    },
+    /// Cancel an escrowed sale and open refunds
+    CancelSale {
+        #[arg(long)]
+        collection_mint: Pubkey,
+    },
+    /// Burn an item of a cancelled sale and get its price back
+    Refund {
+        #[arg(long)]
+        collection_mint: Pubkey,
+        #[arg(long)]
+        mint: Pubkey,
+    },
+    /// Auction a single item of a collection
+    CreateAuction {
+        #[arg(long)]
+        collection_mint: Pubkey,
+        /// Lot number, unique within the collection
+        #[arg(long)]
+        id: u64,
+        #[arg(long)]
+        name: String,
+        #[arg(long)]
+        uri: String,
+        /// Lowest accepted bid in lamports
+        #[arg(long)]
+        reserve_price: u64,
+        #[arg(long)]
+        start_ts: u64,
+        #[arg(long)]
+        end_ts: u64,
+        /// Seconds a late bid pushes the end back to
+        #[arg(long, default_value_t = 300)]
+        extension: u64,
+        /// Lamports a bid must beat the highest one by
+        #[arg(long)]
+        min_increment: u64,
+        /// Account receiving the winning bid
+        #[arg(long)]
+        charge: Pubkey,
+    },
+    /// Bid on an auction
+    Bid {
+        #[arg(long)]
+        collection_mint: Pubkey,
+        #[arg(long)]
+        id: u64,
+        /// Bid in lamports
+        #[arg(long)]
+        amount: u64,
+    },
+    /// Mint an ended auction's item to its winner and pay out the bid
+    SettleAuction {
+        #[arg(long)]
+        collection_mint: Pubkey,
+        #[arg(long)]
+        id: u64,
+    },
+    /// Verify the signer as creator on items of a collection it is a creator of
+    SignMetadata {
+        #[arg(long)]
+        collection_mint: Pubkey,
+        /// Item to verify, repeat for each item
+        #[arg(long = "mint", required = true)]
+        mints: Vec<Pubkey>,
+    },
+    /// Update an item's metadata or hand its update authority to another key
+    UpdateItem {
+        #[arg(long)]
+        mint: Pubkey,
+        #[arg(long)]
+        uri: Option<String>,
+        /// Mark the item's primary sale as happened
+        #[arg(long)]
+        primary_sale_happened: bool,
+        /// New update authority, the factory can't update the item afterwards
+        #[arg(long)]
+        update_authority: Option<Pubkey>,
+    },
+    /// Mint a reserved item to a wallet without payment
+    AdminMint {
+        #[arg(long)]
+        collection_mint: Pubkey,
+        #[arg(long)]
+        recipient: Pubkey,
+    },
+    /// List an item held by the signer for sale
+    List {
+        #[arg(long)]
+        mint: Pubkey,
+        /// Asking price in lamports
+        #[arg(long)]
+        price: u64,
+    },
+    /// Take a listed item back
+    Delist {
+        #[arg(long)]
+        mint: Pubkey,
+    },
+    /// Buy a listed item at its current price
+    Buy {
+        #[arg(long)]
+        mint: Pubkey,
+    },
+    /// Offer a price for any item of a collection, or change the offer
+    Offer {
+        #[arg(long)]
+        collection_mint: Pubkey,
+        /// Offered price in lamports
+        #[arg(long)]
+        price: u64,
+    },
+    /// Withdraw an offer on a collection
+    CancelOffer {
+        #[arg(long)]
+        collection_mint: Pubkey,
+    },
+    /// Sell an item to a bidder's collection offer
+    AcceptOffer {
+        #[arg(long)]
+        mint: Pubkey,
+        #[arg(long)]
+        bidder: Pubkey,
+    },
+    /// Open the staking pool of a collection
+    CreatePool {
+        #[arg(long)]
+        collection_mint: Pubkey,
+        /// Token created by create-token that rewards are paid in
+        #[arg(long)]
+        reward_mint: Pubkey,
+        /// Reward in base units per staked item per second
+        #[arg(long)]
+        reward_rate: u64,
+    },
+    /// Fund a staking pool from the signer's reward tokens
+    FundPool {
+        #[arg(long)]
+        collection_mint: Pubkey,
+        /// Amount in base units
+        #[arg(long)]
+        amount: u64,
+    },
+    /// Close a staking pool with nothing staked, withdrawing its balance to
+    /// the signer
+    ClosePool {
+        #[arg(long)]
+        collection_mint: Pubkey,
+    },
+    /// Revoke the factory's collection authority once the sale, the reserve
+    /// and the auctions are done
+    GraduateCollection {
+        #[arg(long)]
+        collection_mint: Pubkey,
+    },
+    /// Stake an item held by the signer
+    Stake {
+        #[arg(long)]
+        mint: Pubkey,
+    },
+    /// Unstake an item, claiming its rewards
+    Unstake {
+        #[arg(long)]
+        mint: Pubkey,
+    },
+    /// Claim the rewards of a staked item
+    ClaimRewards {
+        #[arg(long)]
+        mint: Pubkey,
+    },
+    /// Create a burn-to-redeem recipe
+    CreateRecipe {
+        #[arg(long)]
+        id: u64,
+        /// Items to burn per redemption, repeatable
+        #[arg(long = "input", value_parser = parse_recipe_input, required = true)]
+        inputs: Vec<RecipeInput>,
+        /// Collection minting the redeemed item, a receipt is written without
+        #[arg(long)]
+        output_collection: Option<Pubkey>,
+    },
+    /// Burn items held by the signer to redeem a recipe
+    Redeem {
+        #[arg(long)]
+        id: u64,
+        /// Items to burn in recipe order, repeatable
+        #[arg(long = "burn", required = true)]
+        burns: Vec<Pubkey>,
+    },
+    /// End a collection's sale and reclaim the promotion rent
+    ClosePromotion {
+        #[arg(long)]
+        collection_mint: Pubkey,
+        /// Account receiving the rent, defaults to the signer
+        #[arg(long)]
+        recipient: Option<Pubkey>,
+    },
+    /// Reclaim the rent of a collection whose promotion is closed
+    CloseCollection {
+        #[arg(long)]
+        collection_mint: Pubkey,
+        /// Account receiving the rent, defaults to the signer
+        #[arg(long)]
+        recipient: Option<Pubkey>,
+    },
+    /// Clear a buyer's mint record, returning its rent to the buyer
+    CloseUserRecord {
+        #[arg(long)]
+        user: Pubkey,
+    },
+    /// Print decoded program state
+    Show {
+        /// Account to decode, every program account when omitted
+        address: Option<Pubkey>,
+        /// Wallet whose user record to recognise besides the signer's,
+        /// repeatable
+        #[arg(long = "user")]
+        users: Vec<Pubkey>,
+    },
+}
+
+#[derive(Args)]
+struct MetadataArgs {
+    #[arg(long)]
+    name: String,
+    #[arg(long)]
+    symbol: String,
+    #[arg(long)]
+    uri: String,
+}
+
+/// Dutch auction pricing, enabled by setting a floor price
+#[derive(Args)]
+struct DutchArgs {
+    /// Lowest price of the auction in lamports
+    #[arg(long = "dutch-floor", requires_all = ["interval", "step"])]
+    floor_price: Option<u64>,
+    /// Seconds between price drops
+    #[arg(long = "dutch-interval")]
+    interval: Option<u64>,
+    /// Lamports taken off the price at every drop
+    #[arg(long = "dutch-step")]
+    step: Option<u64>,
+    /// Keep payments above the clearing price in escrow for buyers to claim
+    #[arg(long, requires = "floor_price")]
+    rebate: bool,
+}
+
+impl DutchArgs {
+    fn auction(&self, start_price: u64) -> Option<DutchAuction> {
+        Some(DutchAuction {
+            start_price,
+            floor_price: self.floor_price?,
+            interval: self.interval?,
+            step: self.step?,
+            rebate: self.rebate,
+        })
+    }
+}
+
+/// Fungible token condition, enabled by setting a token to hold or burn
+#[derive(Args)]
+struct TokenRequirementArgs {
+    /// Token the buyer must hold
+    #[arg(long, requires = "token_amount", conflicts_with = "burn_token")]
+    hold_token: Option<Pubkey>,
+    /// Token created by create-token the buyer burns instead of paying
+    #[arg(long, requires = "token_amount")]
+    burn_token: Option<Pubkey>,
+    /// Amount to hold or burn in base units
+    #[arg(long)]
+    token_amount: Option<u64>,
+}
+
+impl TokenRequirementArgs {
+    fn requirement(&self) -> Option<TokenRequirement> {
+        let amount = self.token_amount?;
+        match (self.hold_token, self.burn_token) {
+            (Some(mint), _) => Some(TokenRequirement::Hold { mint, amount }),
+            (_, Some(mint)) => Some(TokenRequirement::Burn { mint, amount }),
+            _ => None,
+        }
+    }
+}
+
+/// Holders-only phase before the public sale, enabled by setting a gate
+/// collection
+#[derive(Args)]
+struct GateArgs {
+    /// Collection mint whose holders may mint during the phase
+    #[arg(long, requires = "gate_start_ts")]
+    gate_collection: Option<Pubkey>,
+    /// Phase number, gate items can mint once per phase
+    #[arg(long, default_value_t = 0)]
+    gate_phase: u64,
+    /// Unix timestamp the gated phase opens at
+    #[arg(long)]
+    gate_start_ts: Option<u64>,
+    /// Discounted price in lamports during the gated phase
+    #[arg(long)]
+    gate_price: Option<u64>,
+}
+
+impl GateArgs {
+    fn gate(&self) -> Option<TokenGate> {
+        Some(TokenGate {
+            collection: self.gate_collection?,
+            phase: self.gate_phase,
+            start_ts: self.gate_start_ts?,
+            price: self.gate_price,
+        })
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum TokenProgram {
+    SplToken,
+    Token2022,
+}
+
+impl TokenProgram {
+    fn id(self) -> Pubkey {
+        match self {
+            TokenProgram::SplToken => spl_token::id(),
+            TokenProgram::Token2022 => spl_token_2022::id(),
+        }
+    }
+}
+
+fn parse_creator(s: &str) -> Result<Creator, String> {
+    let (address, share) = s
+        .split_once(':')
+        .ok_or_else(|| format!("expected ADDRESS:SHARE, got {}", s))?;
+    Ok(Creator {
+        address: Pubkey::from_str(address).map_err(|e| e.to_string())?,
+        verified: false,
+        share: share.parse().map_err(|e| format!("invalid share: {}", e))?,
+    })
+}
+
+fn parse_recipe_input(s: &str) -> Result<RecipeInput, String> {
+    let (collection, quantity) = s
+        .split_once(':')
+        .ok_or_else(|| format!("expected COLLECTION_MINT:QUANTITY, got {}", s))?;
+    Ok(RecipeInput {
+        collection: Pubkey::from_str(collection).map_err(|e| e.to_string())?,
+        quantity: quantity
+            .parse()
+            .map_err(|e| format!("invalid quantity: {}", e))?,
+    })
+}
+
+struct Context {
+    rpc: RpcClient,
+    payer: Keypair,
+    program_id: Pubkey,
+    dry_run: bool,
+}
+
+impl Context {
+    fn fetch<T>(&self, address: &Pubkey) -> CliResult<T>
+    where
+        T: borsh::BorshDeserialize,
+    {
+        let data = self.rpc.get_account_data(address)?;
+        Ok(nft_factory::utils::try_from_slice_unchecked(&data)?)
+    }
+
+    /// Send the instructions in one transaction, or print them on dry runs.
+    fn execute(&self, instructions: Vec<Instruction>, extra_signers: &[&Keypair]) -> CliResult {
+        if self.dry_run {
+            for ix in instructions.iter() {
+                let accounts: Vec<_> = ix
+                    .accounts
+                    .iter()
+                    .map(|meta| {
+                        json!({
+                            "pubkey": meta.pubkey.to_string(),
+                            "is_signer": meta.is_signer,
+                            "is_writable": meta.is_writable,
+                        })
+                    })
+                    .collect();
+                let printed = json!({
+                    "program_id": ix.program_id.to_string(),
+                    "accounts": accounts,
+                    "data": STANDARD.encode(&ix.data),
+                });
+                println!("{}", serde_json::to_string_pretty(&printed)?);
+            }
+            return Ok(());
+        }
+
+        let mut signers: Vec<&Keypair> = vec![&self.payer];
+        signers.extend_from_slice(extra_signers);
+        let blockhash = self.rpc.get_latest_blockhash()?;
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&self.payer.pubkey()),
+            &signers,
+            blockhash,
+        );
+        let signature = self.rpc.send_and_confirm_transaction(&tx)?;
+        println!("signature: {}", signature);
+        Ok(())
+    }
+
+    /// Instructions creating a fresh mint, with the payer as mint authority
+    /// and one token in the owner's associated token account, as classic NFTs
+    /// expect before their metadata is created.
+    fn create_nft_mint(
+        &self,
+        mint: &Keypair,
+        owner: &Pubkey,
+        token_program: &Pubkey,
+    ) -> CliResult<(Pubkey, Vec<Instruction>)> {
+        let payer = self.payer.pubkey();
+        let lamports = self
+            .rpc
+            .get_minimum_balance_for_rent_exemption(spl_token_2022::state::Mint::LEN)?;
+        let token_account =
+            get_associated_token_address_with_program_id(owner, &mint.pubkey(), token_program);
+        let instructions = vec![
+            system_instruction::create_account(
+                &payer,
+                &mint.pubkey(),
+                lamports,
+                spl_token_2022::state::Mint::LEN as u64,
+                token_program,
+            ),
+            spl_token_2022::instruction::initialize_mint2(
+                token_program,
+                &mint.pubkey(),
+                &payer,
+                Some(&payer),
+                0,
+            )?,
+            spl_associated_token_account::instruction::create_associated_token_account(
+                &payer,
+                owner,
+                &mint.pubkey(),
+                token_program,
+            ),
+            spl_token_2022::instruction::mint_to(
+                token_program,
+                &mint.pubkey(),
+                &token_account,
+                &payer,
+                &[],
+                1,
+            )?,
+        ];
+        Ok((token_account, instructions))
+    }
+}
+
+fn main() -> CliResult {
+    let cli = Cli::parse();
+    let keypair_path = match cli.keypair.strip_prefix("~/") {
+        Some(rest) => format!("{}/{}", std::env::var("HOME")?, rest),
+        None => cli.keypair.clone(),
+    };
+    let ctx = Context {
+        rpc: RpcClient::new_with_commitment(cli.url.clone(), CommitmentConfig::confirmed()),
+        payer: read_keypair_file(&keypair_path)
+            .map_err(|e| format!("failed to read keypair {}: {}", keypair_path, e))?,
+        program_id: cli.program_id,
+        dry_run: cli.dry_run,
+    };
+    let program_id = ctx.program_id;
+    let payer = ctx.payer.pubkey();
+
+    match cli.command {
+        Command::Configure {
+            authority,
+            platform_fee,
+            fee_recipient,
+        } => {
+            let config = config_address(&program_id);
+            println!("config: {}", config);
+            let ix = instruction::configure(
+                &program_id,
+                &payer,
+                &config,
+                ConfigureArgs {
+                    authority,
+                    platform_fee,
+                    fee_recipient: fee_recipient.unwrap_or(authority),
+                },
+            )?;
+            ctx.execute(vec![ix], &[])
+        }
+        Command::CreateCollection {
+            metadata,
+            fee,
+            token_program,
+        } => {
+            let mint = Keypair::new();
+            let token_program = token_program.id();
+            let pda_creator = pda_creator_address(&program_id, &mint.pubkey());
+            let (_, mut instructions) = ctx.create_nft_mint(&mint, &payer, &token_program)?;
+            println!("collection mint: {}", mint.pubkey());
+            println!("pda creator: {}", pda_creator);
+            instructions.push(ComputeBudgetInstruction::set_compute_unit_limit(
+                MINT_COMPUTE_UNITS,
+            ));
+            instructions.push(instruction::create_collection(
+                &program_id,
+                &payer,
+                &mint.pubkey(),
+                &pda_creator,
+                &CollectionAuthorityRecord::find_pda(&mint.pubkey(), &pda_creator).0,
+                &Metadata::find_pda(&mint.pubkey()).0,
+                &MasterEdition::find_pda(&mint.pubkey()).0,
+                &mpl_token_metadata::ID,
+                &token_program,
+                CreateCollectionArgs {
+                    fee,
+                    name: metadata.name,
+                    symbol: metadata.symbol,
+                    uri: metadata.uri,
+                },
+            )?);
+            ctx.execute(instructions, &[&mint])
+        }
+        Command::AddCollection {
+            collection_mint,
+            admin,
+            metadata,
+            fee,
+            creators,
+            token_program,
+            programmable,
+            rule_set,
+            gatekeeper,
+            bot_tax,
+            reserve,
+            supply_cap,
+        } => {
+            let collection_info = collection_address(&program_id, &collection_mint);
+            let pda_creator = pda_creator_address(&program_id, &collection_mint);
+            println!("collection info: {}", collection_info);
+            let ix = instruction::add_collection(
+                &program_id,
+                &payer,
+                &config_address(&program_id),
+                &collection_mint,
+                &pda_creator,
+                &collection_info,
+                AddCollectionArgs {
+                    max_supply: 0,
+                    collection_mint,
+                    admin,
+                    pda_creator,
+                    creators,
+                    fee,
+                    name: metadata.name,
+                    symbol: metadata.symbol,
+                    uri: metadata.uri,
+                    ts: 0,
+                    token_program: token_program.id(),
+                    programmable,
+                    rule_set,
+                    gatekeeper,
+                    bot_tax,
+                    reserve,
+                    reserve_minted: 0,
+                    supply_cap,
+                    listings: 0,
+                    auctions: 0,
+                    version: LAYOUT_VERSION,
+                },
+            )?;
+            ctx.execute(vec![ix], &[])
+        }
+        Command::UpdateCollection {
+            collection_mint,
+            name,
+            symbol,
+            uri,
+            fee,
+            creators,
+            admin,
+            supply_cap,
+        } => {
+            let ix = instruction::update_collection(
+                &program_id,
+                &payer,
+                &config_address(&program_id),
+                &collection_mint,
+                &collection_address(&program_id, &collection_mint),
+                UpdateCollectionArgs {
+                    name,
+                    symbol,
+                    uri,
+                    fee,
+                    creators: (!creators.is_empty()).then_some(creators),
+                    admin,
+                    supply_cap,
+                },
+            )?;
+            ctx.execute(vec![ix], &[])
+        }
+        Command::AddPromotion {
+            collection_mint,
+            price,
+            start_ts,
+            charge,
+            escrow,
+            dutch,
+            gate,
+            token,
+        } => {
+            let collection_info = collection_address(&program_id, &collection_mint);
+            let promotion_info = promotion_address(&program_id, &collection_mint);
+            println!("promotion info: {}", promotion_info);
+            let ix = instruction::add_promotion(
+                &program_id,
+                &payer,
+                &config_address(&program_id),
+                &collection_mint,
+                &collection_info,
+                &promotion_info,
+                &treasury_address(&program_id, &collection_mint),
+                AddPromotionArgs {
+                    sale_price: price,
+                    public_start_ts: start_ts,
+                    collection: collection_mint,
+                    char_addr: charge,
+                    escrow,
+                    dutch: dutch.auction(price),
+                    gate: gate.gate(),
+                    token: token.requirement(),
+                    version: LAYOUT_VERSION,
+                },
+            )?;
+            ctx.execute(vec![ix], &[])
+        }
+        Command::Mint {
+            collection_mint,
+            gate_mint,
+            gatekeeper_keypair,
+            count,
+        } => {
+            let collection_info = collection_address(&program_id, &collection_mint);
+            let promotion_info = promotion_address(&program_id, &collection_mint);
+            let collection_data: CollectionData = ctx.fetch(&collection_info)?;
+            let promotion_data: PromotionData = ctx.fetch(&promotion_info)?;
+            let token_program = collection_data.token_program;
+            let pda_creator = pda_creator_address(&program_id, &collection_mint);
+
+            let mint = Keypair::new();
+            println!("mint: {}", mint.pubkey());
+            let mut instructions = vec![ComputeBudgetInstruction::set_compute_unit_limit(
+                (MINT_COMPUTE_UNITS * count as u32).min(MAX_COMPUTE_UNITS),
+            )];
+            let token_account = if collection_data.programmable {
+                // the metadata program creates and mints pNFTs itself
+                get_associated_token_address_with_program_id(&payer, &mint.pubkey(), &token_program)
+            } else {
+                let (token_account, create) = ctx.create_nft_mint(&mint, &payer, &token_program)?;
+                instructions.extend(create);
+                token_account
+            };
+
+            let metadata = Metadata::find_pda(&mint.pubkey()).0;
+            let edition = MasterEdition::find_pda(&mint.pubkey()).0;
+            let collection_metadata = Metadata::find_pda(&collection_mint).0;
+            let collection_edition = MasterEdition::find_pda(&collection_mint).0;
+            let authority_record =
+                CollectionAuthorityRecord::find_pda(&collection_mint, &pda_creator).0;
+            let user_info = user_address(&program_id, &payer);
+            let ix = if collection_data.programmable {
+                instruction::mint_programmable(
+                    &program_id,
+                    &payer,
+                    &pda_creator,
+                    &mint.pubkey(),
+                    &token_account,
+                    &metadata,
+                    &edition,
+                    &collection_mint,
+                    &collection_metadata,
+                    &collection_edition,
+                    &authority_record,
+                    &promotion_info,
+                    &collection_info,
+                    &promotion_data.char_addr,
+                    &user_info,
+                    &mpl_token_metadata::ID,
+                    &token_program,
+                    &TokenRecord::find_pda(&mint.pubkey(), &token_account).0,
+                    &collection_data.rule_set.unwrap_or(mpl_token_metadata::ID),
+                )?
+            } else {
+                instruction::mint(
+                    &program_id,
+                    &payer,
+                    &pda_creator,
+                    &mint.pubkey(),
+                    &token_account,
+                    &metadata,
+                    &edition,
+                    &collection_mint,
+                    &collection_metadata,
+                    &collection_edition,
+                    &authority_record,
+                    &promotion_info,
+                    &collection_info,
+                    &promotion_data.char_addr,
+                    &user_info,
+                    &mpl_token_metadata::ID,
+                    &token_program,
+                )?
+            };
+            let ix = if promotion_data.escrow {
+                instruction::with_escrow(
+                    ix,
+                    &treasury_address(&program_id, &collection_mint),
+                    &receipt_address(&program_id, &mint.pubkey()),
+                )
+            } else {
+                ix
+            };
+            let ix = match gate_mint {
+                Some(gate_mint) => {
+                    let gate_token_program = ctx.rpc.get_account(&gate_mint)?.owner;
+                    instruction::with_gate(
+                        &program_id,
+                        ix,
+                        &collection_mint,
+                        &gate_mint,
+                        &get_associated_token_address_with_program_id(
+                            &payer,
+                            &gate_mint,
+                            &gate_token_program,
+                        ),
+                    )
+                }
+                None => ix,
+            };
+            let ix = match &promotion_data.token {
+                Some(requirement) => {
+                    let token_mint = requirement.mint();
+                    let token_program = ctx.rpc.get_account(token_mint)?.owner;
+                    instruction::with_token_requirement(
+                        ix,
+                        token_mint,
+                        &get_associated_token_address_with_program_id(
+                            &payer,
+                            token_mint,
+                            &token_program,
+                        ),
+                        &token_program,
+                    )
+                }
+                None => ix,
+            };
+            let gatekeeper = match collection_data.gatekeeper {
+                Some(gatekeeper) => {
+                    let path =
+                        gatekeeper_keypair.ok_or("collection requires --gatekeeper-keypair")?;
+                    let keypair = read_keypair_file(&path)
+                        .map_err(|e| format!("failed to read keypair {}: {}", path, e))?;
+                    if keypair.pubkey() != gatekeeper {
+                        return Err(format!("gatekeeper must be {}", gatekeeper).into());
+                    }
+                    Some(keypair)
+                }
+                None => None,
+            };
+            let ix = match &gatekeeper {
+                Some(gatekeeper) => instruction::with_gatekeeper(ix, &gatekeeper.pubkey()),
+                None => ix,
+            };
+            let extra_mints: Vec<Keypair> = (1..count).map(|_| Keypair::new()).collect();
+            let mut extra_items = vec![];
+            for extra_mint in extra_mints.iter() {
+                println!("mint: {}", extra_mint.pubkey());
+                let token_account = if collection_data.programmable {
+                    get_associated_token_address_with_program_id(
+                        &payer,
+                        &extra_mint.pubkey(),
+                        &token_program,
+                    )
+                } else {
+                    let (token_account, create) =
+                        ctx.create_nft_mint(extra_mint, &payer, &token_program)?;
+                    instructions.extend(create);
+                    token_account
+                };
+                extra_items.push(instruction::ExtraMintItem {
+                    mint: extra_mint.pubkey(),
+                    token_account,
+                    token_record: collection_data
+                        .programmable
+                        .then(|| TokenRecord::find_pda(&extra_mint.pubkey(), &token_account).0),
+                    receipt: promotion_data
+                        .escrow
+                        .then(|| receipt_address(&program_id, &extra_mint.pubkey())),
+                });
+            }
+            let ix = if extra_items.is_empty() {
+                ix
+            } else {
+                instruction::with_extra_items(ix, &extra_items)
+            };
+            instructions.push(ix);
+            let mut signers = vec![&mint];
+            signers.extend(extra_mints.iter());
+            signers.extend(gatekeeper.as_ref());
+            ctx.execute(instructions, &signers)
+        }
+        Command::CreateToken {
+            metadata,
+            decimals,
+            token_program,
+            metadata_pointer,
+            transfer_fee_bps,
+            maximum_fee,
+            non_transferable,
+        } => {
+            let mut extensions = vec![];
+            if metadata_pointer {
+                extensions.push(TokenExtension::MetadataPointer);
+            }
+            if let Some(basis_points) = transfer_fee_bps {
+                extensions.push(TokenExtension::TransferFee {
+                    basis_points,
+                    maximum_fee,
+                });
+            }
+            if non_transferable {
+                extensions.push(TokenExtension::NonTransferable);
+            }
+
+            let mint = Keypair::new();
+            let mint_key = mint.pubkey();
+            let token_info = token_info_address(&program_id, &mint_key);
+            let mint_vault = mint_vault_address(&program_id, &mint_key);
+            let mint_auth = mint_auth_address(&program_id, &mint_key);
+            println!("mint: {}", mint_key);
+            println!("token info: {}", token_info);
+            println!("mint vault: {}", mint_vault);
+            let ix = instruction::create_token(
+                &program_id,
+                &payer,
+                &token_info,
+                &mint_key,
+                &mint_vault,
+                &mint_auth,
+                &Metadata::find_pda(&mint_key).0,
+                &mpl_token_metadata::ID,
+                &token_program.id(),
+                CreateTokenArgs {
+                    name: metadata.name,
+                    symbol: metadata.symbol,
+                    uri: metadata.uri,
+                    decimals,
+                    extensions,
+                },
+            )?;
+            ctx.execute(vec![ix], &[&mint])
+        }
+        Command::FinalizeSale { collection_mint } => {
+            let promotion_info = promotion_address(&program_id, &collection_mint);
+            let promotion_data: PromotionData = ctx.fetch(&promotion_info)?;
+            let ix = instruction::finalize_sale(
+                &program_id,
+                &payer,
+                &config_address(&program_id),
+                &collection_mint,
+                &collection_address(&program_id, &collection_mint),
+                &promotion_info,
+                &treasury_address(&program_id, &collection_mint),
+                &promotion_data.char_addr,
+            )?;
+            ctx.execute(vec![ix], &[])
+        }
+        Command::ClaimRebate {
+            collection_mint,
+            mint,
+        } => {
+            let ix = instruction::claim_rebate(
+                &program_id,
+                &payer,
+                &collection_mint,
+                &treasury_address(&program_id, &collection_mint),
+                &receipt_address(&program_id, &mint),
+                &mint,
+            )?;
+            ctx.execute(vec![ix], &[])
+        }
+        Command::CancelSale { collection_mint } => {
+            let ix = instruction::cancel_sale(
+                &program_id,
+                &payer,
+                &config_address(&program_id),
+                &collection_mint,
+                &collection_address(&program_id, &collection_mint),
+                &treasury_address(&program_id, &collection_mint),
+            )?;
+            ctx.execute(vec![ix], &[])
+        }
+        Command::Refund {
+            collection_mint,
+            mint,
+        } => {
+            let collection_data: CollectionData =
+                ctx.fetch(&collection_address(&program_id, &collection_mint))?;
+            let token_account = get_associated_token_address_with_program_id(
+                &payer,
+                &mint,
+                &collection_data.token_program,
+            );
+            let token_record = collection_data
+                .programmable
+                .then(|| TokenRecord::find_pda(&mint, &token_account).0);
+            let ix = instruction::refund(
+                &program_id,
+                &payer,
+                &collection_mint,
+                &treasury_address(&program_id, &collection_mint),
+                &receipt_address(&program_id, &mint),
+                &mint,
+                &token_account,
+                &Metadata::find_pda(&mint).0,
+                &MasterEdition::find_pda(&mint).0,
+                &Metadata::find_pda(&collection_mint).0,
+                token_record.as_ref(),
+                &mpl_token_metadata::ID,
+                &collection_data.token_program,
+            )?;
+            ctx.execute(vec![ix], &[])
+        }
+        Command::CreateAuction {
+            collection_mint,
+            id,
+            name,
+            uri,
+            reserve_price,
+            start_ts,
+            end_ts,
+            extension,
+            min_increment,
+            charge,
+        } => {
+            let auction_info = auction_address(&program_id, &collection_mint, id);
+            println!("auction: {}", auction_info);
+            let ix = instruction::create_auction(
+                &program_id,
+                &payer,
+                &config_address(&program_id),
+                &collection_mint,
+                &collection_address(&program_id, &collection_mint),
+                &auction_info,
+                CreateAuctionArgs {
+                    id,
+                    name,
+                    uri,
+                    reserve_price,
+                    start_ts,
+                    end_ts,
+                    extension,
+                    min_increment,
+                    char_addr: charge,
+                },
+            )?;
+            ctx.execute(vec![ix], &[])
+        }
+        Command::Bid {
+            collection_mint,
+            id,
+            amount,
+        } => {
+            let auction_info = auction_address(&program_id, &collection_mint, id);
+            let auction_data: AuctionData = ctx.fetch(&auction_info)?;
+            let ix = instruction::place_bid(
+                &program_id,
+                &payer,
+                &collection_mint,
+                &auction_info,
+                &auction_data.highest_bidder.unwrap_or(payer),
+                PlaceBidArgs { amount },
+            )?;
+            ctx.execute(vec![ix], &[])
+        }
+        Command::SettleAuction {
+            collection_mint,
+            id,
+        } => {
+            let auction_info = auction_address(&program_id, &collection_mint, id);
+            let auction_data: AuctionData = ctx.fetch(&auction_info)?;
+            let collection_info = collection_address(&program_id, &collection_mint);
+            let collection_data: CollectionData = ctx.fetch(&collection_info)?;
+            let token_program = collection_data.token_program;
+            let pda_creator = pda_creator_address(&program_id, &collection_mint);
+            let winner = auction_data.highest_bidder.unwrap_or(payer);
+
+            let mint = Keypair::new();
+            let mut instructions = vec![ComputeBudgetInstruction::set_compute_unit_limit(
+                MINT_COMPUTE_UNITS,
+            )];
+            let token_account =
+                if collection_data.programmable || auction_data.highest_bidder.is_none() {
+                    get_associated_token_address_with_program_id(
+                        &winner,
+                        &mint.pubkey(),
+                        &token_program,
+                    )
+                } else {
+                    println!("mint: {}", mint.pubkey());
+                    let (token_account, create) =
+                        ctx.create_nft_mint(&mint, &winner, &token_program)?;
+                    instructions.extend(create);
+                    token_account
+                };
+            let mut ix = instruction::settle_auction(
+                &program_id,
+                &payer,
+                &winner,
+                &pda_creator,
+                &mint.pubkey(),
+                &token_account,
+                &Metadata::find_pda(&mint.pubkey()).0,
+                &MasterEdition::find_pda(&mint.pubkey()).0,
+                &collection_mint,
+                &Metadata::find_pda(&collection_mint).0,
+                &MasterEdition::find_pda(&collection_mint).0,
+                &CollectionAuthorityRecord::find_pda(&collection_mint, &pda_creator).0,
+                &collection_info,
+                &auction_info,
+                &auction_data.char_addr,
+                &auction_data.creator,
+                &mpl_token_metadata::ID,
+                &token_program,
+            )?;
+            if collection_data.programmable {
+                ix.accounts.extend(instruction::programmable_accounts(
+                    &TokenRecord::find_pda(&mint.pubkey(), &token_account).0,
+                    &collection_data.rule_set.unwrap_or(mpl_token_metadata::ID),
+                ));
+            }
+            instructions.push(ix);
+            ctx.execute(instructions, &[&mint])
+        }
+        Command::SignMetadata {
+            collection_mint,
+            mints,
+        } => {
+            let ix = instruction::sign_metadata(
+                &program_id,
+                &payer,
+                &collection_mint,
+                &collection_address(&program_id, &collection_mint),
+                &mints,
+            )?;
+            ctx.execute(vec![ix], &[])
+        }
+        Command::UpdateItem {
+            mint,
+            uri,
+            primary_sale_happened,
+            update_authority,
+        } => {
+            let metadata = Metadata::find_pda(&mint).0;
+            let metadata_data = Metadata::safe_deserialize(&ctx.rpc.get_account_data(&metadata)?)?;
+            let collection_mint = metadata_data
+                .collection
+                .ok_or("item is not part of a collection")?
+                .key;
+            let ix = instruction::update_item(
+                &program_id,
+                &payer,
+                &config_address(&program_id),
+                &collection_mint,
+                &collection_address(&program_id, &collection_mint),
+                &pda_creator_address(&program_id, &collection_mint),
+                &mint,
+                UpdateItemArgs {
+                    uri,
+                    primary_sale_happened: primary_sale_happened.then_some(true),
+                    update_authority,
+                },
+            )?;
+            ctx.execute(vec![ix], &[])
+        }
+        Command::AdminMint {
+            collection_mint,
+            recipient,
+        } => {
+            let collection_info = collection_address(&program_id, &collection_mint);
+            let collection_data: CollectionData = ctx.fetch(&collection_info)?;
+            let token_program = collection_data.token_program;
+            let pda_creator = pda_creator_address(&program_id, &collection_mint);
+
+            let mint = Keypair::new();
+            println!("mint: {}", mint.pubkey());
+            let mut instructions = vec![ComputeBudgetInstruction::set_compute_unit_limit(
+                MINT_COMPUTE_UNITS,
+            )];
+            let token_account = if collection_data.programmable {
+                get_associated_token_address_with_program_id(
+                    &recipient,
+                    &mint.pubkey(),
+                    &token_program,
+                )
+            } else {
+                let (token_account, create) =
+                    ctx.create_nft_mint(&mint, &recipient, &token_program)?;
+                instructions.extend(create);
+                token_account
+            };
+            let mut ix = instruction::admin_mint(
+                &program_id,
+                &payer,
+                &config_address(&program_id),
+                &pda_creator,
+                &mint.pubkey(),
+                &token_account,
+                &Metadata::find_pda(&mint.pubkey()).0,
+                &MasterEdition::find_pda(&mint.pubkey()).0,
+                &collection_mint,
+                &Metadata::find_pda(&collection_mint).0,
+                &MasterEdition::find_pda(&collection_mint).0,
+                &CollectionAuthorityRecord::find_pda(&collection_mint, &pda_creator).0,
+                &collection_info,
+                &mpl_token_metadata::ID,
+                &token_program,
+                AdminMintArgs { recipient },
+            )?;
+            if collection_data.programmable {
+                ix.accounts.extend(instruction::programmable_accounts(
+                    &TokenRecord::find_pda(&mint.pubkey(), &token_account).0,
+                    &collection_data.rule_set.unwrap_or(mpl_token_metadata::ID),
+                ));
+            }
+            instructions.push(ix);
+            ctx.execute(instructions, &[&mint])
+        }
+        Command::List { mint, price } => {
+            let token_program = ctx.rpc.get_account(&mint)?.owner;
+            let metadata = Metadata::find_pda(&mint).0;
+            let metadata_data = Metadata::safe_deserialize(&ctx.rpc.get_account_data(&metadata)?)?;
+            let collection_mint = metadata_data
+                .collection
+                .ok_or("item is not part of a collection")?
+                .key;
+            let ix = instruction::list(
+                &program_id,
+                &payer,
+                &mint,
+                &get_associated_token_address_with_program_id(&payer, &mint, &token_program),
+                &metadata,
+                &collection_address(&program_id, &collection_mint),
+                &listing_address(&program_id, &mint),
+                &listing_vault_address(&program_id, &mint),
+                &token_program,
+                ListArgs { price },
+            )?;
+            ctx.execute(vec![ix], &[])
+        }
+        Command::Delist { mint } => {
+            let token_program = ctx.rpc.get_account(&mint)?.owner;
+            let listing_info = listing_address(&program_id, &mint);
+            let listing_data: ListingData = ctx.fetch(&listing_info)?;
+            let ix = instruction::delist(
+                &program_id,
+                &payer,
+                &mint,
+                &get_associated_token_address_with_program_id(&payer, &mint, &token_program),
+                &listing_info,
+                &listing_vault_address(&program_id, &mint),
+                &collection_address(&program_id, &listing_data.collection),
+                &token_program,
+            )?;
+            ctx.execute(vec![ix], &[])
+        }
+        Command::Buy { mint } => {
+            let token_program = ctx.rpc.get_account(&mint)?.owner;
+            let listing_info = listing_address(&program_id, &mint);
+            let listing_data: ListingData = ctx.fetch(&listing_info)?;
+            let collection_info = collection_address(&program_id, &listing_data.collection);
+            let collection_data: CollectionData = ctx.fetch(&collection_info)?;
+            let config = config_address(&program_id);
+            let config_data: ConfigureData = ctx.fetch(&config)?;
+            let creators: Vec<Pubkey> = collection_data
+                .creators
+                .iter()
+                .map(|creator| creator.address)
+                .collect();
+            let token_account =
+                get_associated_token_address_with_program_id(&payer, &mint, &token_program);
+            let create_token_account =
+                spl_associated_token_account::instruction::create_associated_token_account_idempotent(
+                    &payer,
+                    &payer,
+                    &mint,
+                    &token_program,
+                );
+            let ix = instruction::buy(
+                &program_id,
+                &payer,
+                &listing_data.seller,
+                &mint,
+                &token_account,
+                &listing_info,
+                &listing_vault_address(&program_id, &mint),
+                &collection_info,
+                &config,
+                &config_data.fee_recipient,
+                &token_program,
+                &creators,
+                BuyArgs {
+                    price: listing_data.price,
+                },
+            )?;
+            ctx.execute(vec![create_token_account, ix], &[])
+        }
+        Command::Offer {
+            collection_mint,
+            price,
+        } => {
+            let ix = instruction::place_collection_offer(
+                &program_id,
+                &payer,
+                &collection_mint,
+                &collection_address(&program_id, &collection_mint),
+                &offer_address(&program_id, &collection_mint, &payer),
+                OfferArgs { price },
+            )?;
+            ctx.execute(vec![ix], &[])
+        }
+        Command::CancelOffer { collection_mint } => {
+            let ix = instruction::cancel_collection_offer(
+                &program_id,
+                &payer,
+                &collection_mint,
+                &offer_address(&program_id, &collection_mint, &payer),
+            )?;
+            ctx.execute(vec![ix], &[])
+        }
+        Command::AcceptOffer { mint, bidder } => {
+            let token_program = ctx.rpc.get_account(&mint)?.owner;
+            let metadata = Metadata::find_pda(&mint).0;
+            let metadata_data = Metadata::safe_deserialize(&ctx.rpc.get_account_data(&metadata)?)?;
+            let collection_mint = metadata_data
+                .collection
+                .ok_or("item is not part of a collection")?
+                .key;
+            let collection_info = collection_address(&program_id, &collection_mint);
+            let collection_data: CollectionData = ctx.fetch(&collection_info)?;
+            let offer_info = offer_address(&program_id, &collection_mint, &bidder);
+            let offer_data: OfferData = ctx.fetch(&offer_info)?;
+            let config = config_address(&program_id);
+            let config_data: ConfigureData = ctx.fetch(&config)?;
+            let creators: Vec<Pubkey> = collection_data
+                .creators
+                .iter()
+                .map(|creator| creator.address)
+                .collect();
+            let bidder_token_account =
+                get_associated_token_address_with_program_id(&bidder, &mint, &token_program);
+            let create_token_account =
+                spl_associated_token_account::instruction::create_associated_token_account_idempotent(
+                    &payer,
+                    &bidder,
+                    &mint,
+                    &token_program,
+                );
+            let ix = instruction::accept_offer(
+                &program_id,
+                &payer,
+                &bidder,
+                &mint,
+                &get_associated_token_address_with_program_id(&payer, &mint, &token_program),
+                &bidder_token_account,
+                &metadata,
+                &collection_info,
+                &offer_info,
+                &config,
+                &config_data.fee_recipient,
+                &token_program,
+                &creators,
+                AcceptOfferArgs {
+                    price: offer_data.price,
+                },
+            )?;
+            ctx.execute(vec![create_token_account, ix], &[])
+        }
+        Command::CreatePool {
+            collection_mint,
+            reward_mint,
+            reward_rate,
+        } => {
+            let ix = instruction::create_pool(
+                &program_id,
+                &payer,
+                &config_address(&program_id),
+                &collection_mint,
+                &collection_address(&program_id, &collection_mint),
+                &pool_address(&program_id, &collection_mint),
+                &reward_mint,
+                &token_info_address(&program_id, &reward_mint),
+                CreatePoolArgs { reward_rate },
+            )?;
+            ctx.execute(vec![ix], &[])
+        }
+        Command::FundPool {
+            collection_mint,
+            amount,
+        } => {
+            let pool_info = pool_address(&program_id, &collection_mint);
+            let pool_data: PoolData = ctx.fetch(&pool_info)?;
+            let reward_mint = pool_data.reward_mint;
+            let token_program = ctx.rpc.get_account(&reward_mint)?.owner;
+            let ix = instruction::fund_pool(
+                &program_id,
+                &payer,
+                &pool_info,
+                &reward_mint,
+                &get_associated_token_address_with_program_id(&payer, &reward_mint, &token_program),
+                &mint_vault_address(&program_id, &reward_mint),
+                &token_program,
+                FundPoolArgs { amount },
+            )?;
+            ctx.execute(vec![ix], &[])
+        }
+        Command::ClosePool { collection_mint } => {
+            let pool_info = pool_address(&program_id, &collection_mint);
+            let pool_data: PoolData = ctx.fetch(&pool_info)?;
+            let reward_mint = pool_data.reward_mint;
+            let token_program = ctx.rpc.get_account(&reward_mint)?.owner;
+            let create_destination =
+                spl_associated_token_account::instruction::create_associated_token_account_idempotent(
+                    &payer,
+                    &payer,
+                    &reward_mint,
+                    &token_program,
+                );
+            let ix = instruction::close_pool(
+                &program_id,
+                &payer,
+                &config_address(&program_id),
+                &collection_mint,
+                &collection_address(&program_id, &collection_mint),
+                &pool_info,
+                &reward_mint,
+                &mint_vault_address(&program_id, &reward_mint),
+                &mint_auth_address(&program_id, &reward_mint),
+                &get_associated_token_address_with_program_id(&payer, &reward_mint, &token_program),
+                &token_program,
+            )?;
+            ctx.execute(vec![create_destination, ix], &[])
+        }
+        Command::GraduateCollection { collection_mint } => {
+            let pda_creator = pda_creator_address(&program_id, &collection_mint);
+            let ix = instruction::graduate_collection(
+                &program_id,
+                &payer,
+                &config_address(&program_id),
+                &collection_mint,
+                &collection_address(&program_id, &collection_mint),
+                &pda_creator,
+                &CollectionAuthorityRecord::find_pda(&collection_mint, &pda_creator).0,
+            )?;
+            ctx.execute(vec![ix], &[])
+        }
+        Command::Stake { mint } => {
+            let token_program = ctx.rpc.get_account(&mint)?.owner;
+            let metadata = Metadata::find_pda(&mint).0;
+            let metadata_data = Metadata::safe_deserialize(&ctx.rpc.get_account_data(&metadata)?)?;
+            let collection_mint = metadata_data
+                .collection
+                .ok_or("item is not part of a collection")?
+                .key;
+            let ix = instruction::stake(
+                &program_id,
+                &payer,
+                &mint,
+                &get_associated_token_address_with_program_id(&payer, &mint, &token_program),
+                &metadata,
+                &MasterEdition::find_pda(&mint).0,
+                &collection_address(&program_id, &collection_mint),
+                &pool_address(&program_id, &collection_mint),
+                &stake_address(&program_id, &mint),
+                &token_program,
+            )?;
+            ctx.execute(vec![ix], &[])
+        }
+        command @ (Command::Unstake { mint } | Command::ClaimRewards { mint }) => {
+            let stake_info = stake_address(&program_id, &mint);
+            let stake_data: StakeData = ctx.fetch(&stake_info)?;
+            let pool_info = pool_address(&program_id, &stake_data.collection);
+            let pool_data: PoolData = ctx.fetch(&pool_info)?;
+            let reward_mint = pool_data.reward_mint;
+            let reward_token_program = ctx.rpc.get_account(&reward_mint)?.owner;
+            let create_reward_account =
+                spl_associated_token_account::instruction::create_associated_token_account_idempotent(
+                    &payer,
+                    &payer,
+                    &reward_mint,
+                    &reward_token_program,
+                );
+            let reward_account = get_associated_token_address_with_program_id(
+                &payer,
+                &reward_mint,
+                &reward_token_program,
+            );
+            let ix = if matches!(command, Command::Unstake { .. }) {
+                let token_program = ctx.rpc.get_account(&mint)?.owner;
+                instruction::unstake(
+                    &program_id,
+                    &payer,
+                    &mint,
+                    &get_associated_token_address_with_program_id(&payer, &mint, &token_program),
+                    &MasterEdition::find_pda(&mint).0,
+                    &pool_info,
+                    &stake_info,
+                    &reward_mint,
+                    &mint_vault_address(&program_id, &reward_mint),
+                    &mint_auth_address(&program_id, &reward_mint),
+                    &reward_account,
+                    &token_program,
+                    &reward_token_program,
+                )?
+            } else {
+                instruction::claim_rewards(
+                    &program_id,
+                    &payer,
+                    &mint,
+                    &pool_info,
+                    &stake_info,
+                    &reward_mint,
+                    &mint_vault_address(&program_id, &reward_mint),
+                    &mint_auth_address(&program_id, &reward_mint),
+                    &reward_account,
+                    &reward_token_program,
+                )?
+            };
+            ctx.execute(vec![create_reward_account, ix], &[])
+        }
+        Command::CreateRecipe {
+            id,
+            inputs,
+            output_collection,
+        } => {
+            let recipe_info = recipe_address(&program_id, id);
+            println!("recipe: {}", recipe_info);
+            let output = match output_collection {
+                Some(collection_mint) => RecipeOutput::Collection(collection_mint),
+                None => RecipeOutput::Receipt,
+            };
+            let ix = instruction::create_recipe(
+                &program_id,
+                &payer,
+                &config_address(&program_id),
+                &recipe_info,
+                CreateRecipeArgs { id, inputs, output },
+            )?;
+            ctx.execute(vec![ix], &[])
+        }
+        Command::Redeem { id, burns } => {
+            let recipe_info = recipe_address(&program_id, id);
+            let recipe_data: RecipeData = ctx.fetch(&recipe_info)?;
+            let mut inputs = vec![];
+            for mint in burns {
+                let metadata = Metadata::find_pda(&mint).0;
+                let metadata_data =
+                    Metadata::safe_deserialize(&ctx.rpc.get_account_data(&metadata)?)?;
+                let collection_mint = metadata_data
+                    .collection
+                    .ok_or("item is not part of a collection")?
+                    .key;
+                inputs.push(instruction::RedeemInput {
+                    mint,
+                    token_account: get_associated_token_address_with_program_id(
+                        &payer,
+                        &mint,
+                        &spl_token::id(),
+                    ),
+                    collection_mint,
+                });
+            }
+
+            match recipe_data.output {
+                RecipeOutput::Receipt => {
+                    let redemption_info =
+                        redemption_address(&program_id, &recipe_info, recipe_data.redeemed);
+                    println!("redemption: {}", redemption_info);
+                    let ix = instruction::redeem_for_receipt(
+                        &program_id,
+                        &payer,
+                        &recipe_info,
+                        &redemption_info,
+                        &inputs,
+                    )?;
+                    ctx.execute(vec![ix], &[])
+                }
+                RecipeOutput::Collection(collection_mint) => {
+                    let collection_info = collection_address(&program_id, &collection_mint);
+                    let collection_data: CollectionData = ctx.fetch(&collection_info)?;
+                    let token_program = collection_data.token_program;
+                    let pda_creator = pda_creator_address(&program_id, &collection_mint);
+
+                    let mint = Keypair::new();
+                    println!("mint: {}", mint.pubkey());
+                    let mut instructions = vec![ComputeBudgetInstruction::set_compute_unit_limit(
+                        MINT_COMPUTE_UNITS,
+                    )];
+                    let token_account = if collection_data.programmable {
+                        get_associated_token_address_with_program_id(
+                            &payer,
+                            &mint.pubkey(),
+                            &token_program,
+                        )
+                    } else {
+                        let (token_account, create) =
+                            ctx.create_nft_mint(&mint, &payer, &token_program)?;
+                        instructions.extend(create);
+                        token_account
+                    };
+                    let mut ix = instruction::redeem(
+                        &program_id,
+                        &payer,
+                        &recipe_info,
+                        &pda_creator,
+                        &mint.pubkey(),
+                        &token_account,
+                        &Metadata::find_pda(&mint.pubkey()).0,
+                        &MasterEdition::find_pda(&mint.pubkey()).0,
+                        &collection_mint,
+                        &Metadata::find_pda(&collection_mint).0,
+                        &MasterEdition::find_pda(&collection_mint).0,
+                        &CollectionAuthorityRecord::find_pda(&collection_mint, &pda_creator).0,
+                        &collection_info,
+                        &token_program,
+                        &inputs,
+                    )?;
+                    if collection_data.programmable {
+                        ix.accounts.extend(instruction::programmable_accounts(
+                            &TokenRecord::find_pda(&mint.pubkey(), &token_account).0,
+                            &collection_data.rule_set.unwrap_or(mpl_token_metadata::ID),
+                        ));
+                    }
+                    instructions.push(ix);
+                    ctx.execute(instructions, &[&mint])
+                }
+            }
+        }
+        Command::ClosePromotion {
+            collection_mint,
+            recipient,
+        } => {
+            let ix = instruction::close_promotion(
+                &program_id,
+                &payer,
+                &config_address(&program_id),
+                &collection_mint,
+                &collection_address(&program_id, &collection_mint),
+                &promotion_address(&program_id, &collection_mint),
+                &treasury_address(&program_id, &collection_mint),
+                &recipient.unwrap_or(payer),
+            )?;
+            ctx.execute(vec![ix], &[])
+        }
+        Command::CloseCollection {
+            collection_mint,
+            recipient,
+        } => {
+            let ix = instruction::close_collection(
+                &program_id,
+                &payer,
+                &config_address(&program_id),
+                &collection_mint,
+                &collection_address(&program_id, &collection_mint),
+                &promotion_address(&program_id, &collection_mint),
+                &treasury_address(&program_id, &collection_mint),
+                &pool_address(&program_id, &collection_mint),
+                &recipient.unwrap_or(payer),
+            )?;
+            ctx.execute(vec![ix], &[])
+        }
+        Command::CloseUserRecord { user } => {
+            let ix = instruction::close_user_record(
+                &program_id,
+                &payer,
+                &config_address(&program_id),
+                &user,
+                &user_address(&program_id, &user),
+            )?;
+            ctx.execute(vec![ix], &[])
+        }
+        Command::Show { address, mut users } => {
+            users.push(payer);
+            let accounts: Vec<(Pubkey, Vec<u8>)> = match address {
+                Some(address) => vec![(address, ctx.rpc.get_account_data(&address)?)],
+                None => ctx
+                    .rpc
+                    .get_program_accounts(&program_id)?
+                    .into_iter()
+                    .map(|(address, account)| (address, account.data))
+                    .collect(),
+            };
+            let written = export_json_lines(&program_id, &accounts, &users, std::io::stdout())?;
+            if written < accounts.len() {
+                eprintln!("{} unrecognised accounts", accounts.len() - written);
+            }
+            Ok(())
+        }
+    }
+}
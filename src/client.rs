@@ -0,0 +1,620 @@
+//! Off-chain helpers for indexers: classify raw program accounts into typed
+//! state and export snapshots as JSON lines.
+
+use borsh::BorshDeserialize;
+use serde_json::{json, Value};
+use solana_program::{program_error::ProgramError, pubkey::Pubkey};
+use std::io::{self, Write};
+
+use crate::{state::*, utils::try_from_slice_unchecked};
+
+/// A program account decoded into its state type.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ProgramAccount {
+    Configure(ConfigureData),
+    Collection(CollectionData),
+    Promotion(PromotionData),
+    Token(TokenData),
+    User(UserData),
+    EditionDrop(EditionDropData),
+    Treasury(TreasuryData),
+    Receipt(ReceiptData),
+    Auction(AuctionData),
+    Listing(ListingData),
+    Offer(OfferData),
+    Pool(PoolData),
+    Stake(StakeData),
+    Recipe(RecipeData),
+    Redemption(RedemptionData),
+    GateUse(GateUseData),
+}
+
+pub fn config_address(program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[program_id.as_ref(), "config".as_bytes()], program_id).0
+}
+
+pub fn collection_address(program_id: &Pubkey, collection_mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            program_id.as_ref(),
+            collection_mint.as_ref(),
+            "collection".as_bytes(),
+        ],
+        program_id,
+    )
+    .0
+}
+
+pub fn promotion_address(program_id: &Pubkey, collection_mint: &Pubkey) -> Pubkey {
+    let collection_info = collection_address(program_id, collection_mint);
+    Pubkey::find_program_address(&[program_id.as_ref(), collection_info.as_ref()], program_id).0
+}
+
+pub fn pda_creator_address(program_id: &Pubkey, collection_mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            program_id.as_ref(),
+            collection_mint.as_ref(),
+            "pda_creator".as_bytes(),
+        ],
+        program_id,
+    )
+    .0
+}
+
+pub fn user_address(program_id: &Pubkey, user: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[program_id.as_ref(), user.as_ref(), "user_info".as_bytes()],
+        program_id,
+    )
+    .0
+}
+
+pub fn token_info_address(program_id: &Pubkey, mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[program_id.as_ref(), mint.as_ref(), "token_info".as_bytes()],
+        program_id,
+    )
+    .0
+}
+
+pub fn edition_drop_address(program_id: &Pubkey, master_mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            program_id.as_ref(),
+            master_mint.as_ref(),
+            "edition_drop".as_bytes(),
+        ],
+        program_id,
+    )
+    .0
+}
+
+pub fn treasury_address(program_id: &Pubkey, collection_mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            program_id.as_ref(),
+            collection_mint.as_ref(),
+            "treasury".as_bytes(),
+        ],
+        program_id,
+    )
+    .0
+}
+
+pub fn receipt_address(program_id: &Pubkey, mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[program_id.as_ref(), mint.as_ref(), "receipt".as_bytes()],
+        program_id,
+    )
+    .0
+}
+
+pub fn auction_address(program_id: &Pubkey, collection_mint: &Pubkey, id: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            program_id.as_ref(),
+            collection_mint.as_ref(),
+            &id.to_le_bytes(),
+            "auction".as_bytes(),
+        ],
+        program_id,
+    )
+    .0
+}
+
+pub fn listing_address(program_id: &Pubkey, mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[program_id.as_ref(), mint.as_ref(), "listing".as_bytes()],
+        program_id,
+    )
+    .0
+}
+
+pub fn listing_vault_address(program_id: &Pubkey, mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            program_id.as_ref(),
+            mint.as_ref(),
+            "listing_vault".as_bytes(),
+        ],
+        program_id,
+    )
+    .0
+}
+
+pub fn offer_address(program_id: &Pubkey, collection_mint: &Pubkey, bidder: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            program_id.as_ref(),
+            collection_mint.as_ref(),
+            bidder.as_ref(),
+            "offer".as_bytes(),
+        ],
+        program_id,
+    )
+    .0
+}
+
+pub fn pool_address(program_id: &Pubkey, collection_mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            program_id.as_ref(),
+            collection_mint.as_ref(),
+            "pool".as_bytes(),
+        ],
+        program_id,
+    )
+    .0
+}
+
+pub fn stake_address(program_id: &Pubkey, mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[program_id.as_ref(), mint.as_ref(), "stake".as_bytes()],
+        program_id,
+    )
+    .0
+}
+
+pub fn mint_vault_address(program_id: &Pubkey, mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[program_id.as_ref(), mint.as_ref(), "mint_vault".as_bytes()],
+        program_id,
+    )
+    .0
+}
+
+pub fn mint_auth_address(program_id: &Pubkey, mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[program_id.as_ref(), mint.as_ref(), "mint_auth".as_bytes()],
+        program_id,
+    )
+    .0
+}
+
+pub fn recipe_address(program_id: &Pubkey, id: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[program_id.as_ref(), &id.to_le_bytes(), "recipe".as_bytes()],
+        program_id,
+    )
+    .0
+}
+
+pub fn redemption_address(program_id: &Pubkey, recipe: &Pubkey, index: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            program_id.as_ref(),
+            recipe.as_ref(),
+            &index.to_le_bytes(),
+            "redemption".as_bytes(),
+        ],
+        program_id,
+    )
+    .0
+}
+
+pub fn gate_use_address(
+    program_id: &Pubkey,
+    collection_mint: &Pubkey,
+    gate_mint: &Pubkey,
+) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            program_id.as_ref(),
+            collection_mint.as_ref(),
+            gate_mint.as_ref(),
+            "gate".as_bytes(),
+        ],
+        program_id,
+    )
+    .0
+}
+
+fn decode<T: BorshDeserialize>(data: &[u8], len: usize) -> Option<T> {
+    if data.len() != len {
+        return None;
+    }
+    try_from_slice_unchecked(data).ok()
+}
+
+/// Decode an account still in an unversioned layout, migrated to the current
+/// one the way the program reads it.
+fn decode_v0<T>(
+    data: &[u8],
+    len: usize,
+    from_v0: fn(&[u8]) -> Result<T, ProgramError>,
+) -> Option<T> {
+    if data.len() != len {
+        return None;
+    }
+    from_v0(data).ok()
+}
+
+/// Classify the data of a program account. Candidate types are picked by data
+/// length and confirmed by re-deriving the account address from the decoded
+/// seeds, so a stray account of the right size is not misread and types that
+/// share a length are told apart. Configs, collections, promotions and
+/// tokens not migrated yet are decoded from their old layout. User records don't store the wallet they
+/// are derived from, so they are only recognised for the wallets in `users`.
+pub fn classify(
+    program_id: &Pubkey,
+    address: &Pubkey,
+    data: &[u8],
+    users: &[Pubkey],
+) -> Option<ProgramAccount> {
+    let confirm =
+        |expected: Pubkey, account: ProgramAccount| (expected == *address).then_some(account);
+    None.or_else(|| {
+        let config: ConfigureData = decode(data, ConfigureData::LEN)?;
+        confirm(
+            config_address(program_id),
+            ProgramAccount::Configure(config),
+        )
+    })
+    .or_else(|| {
+        let collection: CollectionData = decode(data, CollectionData::LEN)?;
+        confirm(
+            collection_address(program_id, &collection.collection_mint),
+            ProgramAccount::Collection(collection),
+        )
+    })
+    .or_else(|| {
+        let promotion: PromotionData = decode(data, PromotionData::LEN)?;
+        confirm(
+            promotion_address(program_id, &promotion.collection),
+            ProgramAccount::Promotion(promotion),
+        )
+    })
+    .or_else(|| {
+        let token: TokenData = decode(data, TokenData::LEN)?;
+        confirm(
+            token_info_address(program_id, &token.mint),
+            ProgramAccount::Token(token),
+        )
+    })
+    .or_else(|| {
+        let drop: EditionDropData = decode(data, EditionDropData::LEN)?;
+        confirm(
+            edition_drop_address(program_id, &drop.master_mint),
+            ProgramAccount::EditionDrop(drop),
+        )
+    })
+    .or_else(|| {
+        let treasury: TreasuryData = decode(data, TreasuryData::LEN)?;
+        confirm(
+            treasury_address(program_id, &treasury.collection),
+            ProgramAccount::Treasury(treasury),
+        )
+    })
+    .or_else(|| {
+        let receipt: ReceiptData = decode(data, ReceiptData::LEN)?;
+        confirm(
+            receipt_address(program_id, &receipt.mint),
+            ProgramAccount::Receipt(receipt),
+        )
+    })
+    .or_else(|| {
+        let auction: AuctionData = decode(data, AuctionData::LEN)?;
+        confirm(
+            auction_address(program_id, &auction.collection, auction.id),
+            ProgramAccount::Auction(auction),
+        )
+    })
+    .or_else(|| {
+        let listing: ListingData = decode(data, ListingData::LEN)?;
+        confirm(
+            listing_address(program_id, &listing.mint),
+            ProgramAccount::Listing(listing),
+        )
+    })
+    .or_else(|| {
+        let offer: OfferData = decode(data, OfferData::LEN)?;
+        confirm(
+            offer_address(program_id, &offer.collection, &offer.bidder),
+            ProgramAccount::Offer(offer),
+        )
+    })
+    .or_else(|| {
+        let pool: PoolData = decode(data, PoolData::LEN)?;
+        confirm(
+            pool_address(program_id, &pool.collection),
+            ProgramAccount::Pool(pool),
+        )
+    })
+    .or_else(|| {
+        let stake: StakeData = decode(data, StakeData::LEN)?;
+        confirm(
+            stake_address(program_id, &stake.mint),
+            ProgramAccount::Stake(stake),
+        )
+    })
+    .or_else(|| {
+        let recipe: RecipeData = decode(data, RecipeData::LEN)?;
+        confirm(
+            recipe_address(program_id, recipe.id),
+            ProgramAccount::Recipe(recipe),
+        )
+    })
+    .or_else(|| {
+        let redemption: RedemptionData = decode(data, RedemptionData::LEN)?;
+        confirm(
+            redemption_address(program_id, &redemption.recipe, redemption.index),
+            ProgramAccount::Redemption(redemption),
+        )
+    })
+    .or_else(|| {
+        let gate_use: GateUseData = decode(data, GateUseData::LEN)?;
+        confirm(
+            gate_use_address(program_id, &gate_use.collection, &gate_use.gate_mint),
+            ProgramAccount::GateUse(gate_use),
+        )
+    })
+    .or_else(|| {
+        let config = decode_v0(data, ConfigureData::LEN_V0, ConfigureData::from_v0)?;
+        confirm(
+            config_address(program_id),
+            ProgramAccount::Configure(config),
+        )
+    })
+    .or_else(|| {
+        let collection = decode_v0(data, CollectionData::LEN_V0, CollectionData::from_v0)?;
+        confirm(
+            collection_address(program_id, &collection.collection_mint),
+            ProgramAccount::Collection(collection),
+        )
+    })
+    .or_else(|| {
+        let promotion = decode_v0(data, PromotionData::LEN_V0, PromotionData::from_v0)?;
+        confirm(
+            promotion_address(program_id, &promotion.collection),
+            ProgramAccount::Promotion(promotion),
+        )
+    })
+    .or_else(|| {
+        let token = decode_v0(data, TokenData::LEN_V0, TokenData::from_v0)?;
+        confirm(
+            token_info_address(program_id, &token.mint),
+            ProgramAccount::Token(token),
+        )
+    })
+    .or_else(|| {
+        let user: UserData = decode(data, UserData::LEN)?;
+        users
+            .iter()
+            .any(|wallet| user_address(program_id, wallet) == *address)
+            .then_some(ProgramAccount::User(user))
+    })
+}
+
+impl ProgramAccount {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ProgramAccount::Configure(_) => "configure",
+            ProgramAccount::Collection(_) => "collection",
+            ProgramAccount::Promotion(_) => "promotion",
+            ProgramAccount::Token(_) => "token",
+            ProgramAccount::User(_) => "user",
+            ProgramAccount::EditionDrop(_) => "edition_drop",
+            ProgramAccount::Treasury(_) => "treasury",
+            ProgramAccount::Receipt(_) => "receipt",
+            ProgramAccount::Auction(_) => "auction",
+            ProgramAccount::Listing(_) => "listing",
+            ProgramAccount::Offer(_) => "offer",
+            ProgramAccount::Pool(_) => "pool",
+            ProgramAccount::Stake(_) => "stake",
+            ProgramAccount::Recipe(_) => "recipe",
+            ProgramAccount::Redemption(_) => "redemption",
+            ProgramAccount::GateUse(_) => "gate_use",
+        }
+    }
+
+    pub fn to_json(&self) -> Value {
+        match self {
+            ProgramAccount::Configure(config) => json!({
+                "authority": config.authority.to_string(),
+                "platform_fee": config.platform_fee,
+                "fee_recipient": config.fee_recipient.to_string(),
+            }),
+            ProgramAccount::Collection(collection) => json!({
+                "collection_mint": collection.collection_mint.to_string(),
+                "admin": collection.admin.to_string(),
+                "pda_creator": collection.pda_creator.to_string(),
+                "creators": collection.creators.iter().map(|creator| json!({
+                    "address": creator.address.to_string(),
+                    "verified": creator.verified,
+                    "share": creator.share,
+                })).collect::<Vec<Value>>(),
+                "fee": collection.fee,
+                "name": collection.name,
+                "symbol": collection.symbol,
+                "uri": collection.uri,
+                "minted": collection.max_supply,
+                "ts": collection.ts,
+                "token_program": collection.token_program.to_string(),
+                "programmable": collection.programmable,
+                "rule_set": collection.rule_set.map(|rule_set| rule_set.to_string()),
+                "gatekeeper": collection.gatekeeper.map(|gatekeeper| gatekeeper.to_string()),
+                "bot_tax": collection.bot_tax,
+                "reserve": collection.reserve,
+                "reserve_minted": collection.reserve_minted,
+                "supply_cap": collection.supply_cap,
+                "listings": collection.listings,
+                "auctions": collection.auctions,
+            }),
+            ProgramAccount::Promotion(promotion) => json!({
+                "collection": promotion.collection.to_string(),
+                "sale_price": promotion.sale_price,
+                "public_start_ts": promotion.public_start_ts,
+                "char_addr": promotion.char_addr.to_string(),
+                "escrow": promotion.escrow,
+                "dutch": promotion.dutch.as_ref().map(|dutch| json!({
+                    "start_price": dutch.start_price,
+                    "floor_price": dutch.floor_price,
+                    "interval": dutch.interval,
+                    "step": dutch.step,
+                    "rebate": dutch.rebate,
+                })),
+                "gate": promotion.gate.as_ref().map(|gate| json!({
+                    "collection": gate.collection.to_string(),
+                    "phase": gate.phase,
+                    "start_ts": gate.start_ts,
+                    "price": gate.price,
+                })),
+                "token": promotion.token.as_ref().map(|token| json!({
+                    "kind": match token {
+                        TokenRequirement::Hold { .. } => "hold",
+                        TokenRequirement::Burn { .. } => "burn",
+                    },
+                    "mint": token.mint().to_string(),
+                    "amount": token.amount(),
+                })),
+            }),
+            ProgramAccount::Token(token) => json!({
+                "mint": token.mint.to_string(),
+                "creator": token.creator.to_string(),
+                "name": token.name,
+                "symbol": token.symbol,
+                "decimals": token.decimals,
+                "supply": token.supply,
+                "token_program": token.token_program.to_string(),
+            }),
+            ProgramAccount::User(user) => json!({
+                "minted": user.minted,
+                "shots": user.shots,
+            }),
+            ProgramAccount::EditionDrop(drop) => json!({
+                "collection": drop.collection.to_string(),
+                "master_mint": drop.master_mint.to_string(),
+                "max_supply": drop.max_supply,
+                "minted": drop.minted,
+            }),
+            ProgramAccount::Treasury(treasury) => json!({
+                "collection": treasury.collection.to_string(),
+                "status": format!("{:?}", treasury.status),
+                "total": treasury.total,
+                "sold": treasury.sold,
+                "clearing_price": treasury.clearing_price,
+                "rebate": treasury.rebate,
+            }),
+            ProgramAccount::Receipt(receipt) => json!({
+                "collection": receipt.collection.to_string(),
+                "mint": receipt.mint.to_string(),
+                "buyer": receipt.buyer.to_string(),
+                "price": receipt.price,
+            }),
+            ProgramAccount::Auction(auction) => json!({
+                "collection": auction.collection.to_string(),
+                "id": auction.id,
+                "name": auction.name,
+                "uri": auction.uri,
+                "reserve_price": auction.reserve_price,
+                "start_ts": auction.start_ts,
+                "end_ts": auction.end_ts,
+                "extension": auction.extension,
+                "min_increment": auction.min_increment,
+                "char_addr": auction.char_addr.to_string(),
+                "highest_bidder": auction.highest_bidder.map(|bidder| bidder.to_string()),
+                "highest_bid": auction.highest_bid,
+                "creator": auction.creator.to_string(),
+            }),
+            ProgramAccount::Listing(listing) => json!({
+                "seller": listing.seller.to_string(),
+                "mint": listing.mint.to_string(),
+                "collection": listing.collection.to_string(),
+                "price": listing.price,
+            }),
+            ProgramAccount::Offer(offer) => json!({
+                "collection": offer.collection.to_string(),
+                "bidder": offer.bidder.to_string(),
+                "price": offer.price,
+            }),
+            ProgramAccount::Pool(pool) => json!({
+                "collection": pool.collection.to_string(),
+                "reward_mint": pool.reward_mint.to_string(),
+                "reward_rate": pool.reward_rate,
+                "staked": pool.staked,
+                "balance": pool.balance,
+            }),
+            ProgramAccount::Stake(stake) => json!({
+                "collection": stake.collection.to_string(),
+                "mint": stake.mint.to_string(),
+                "owner": stake.owner.to_string(),
+                "staked_at": stake.staked_at,
+                "last_claim_ts": stake.last_claim_ts,
+            }),
+            ProgramAccount::Recipe(recipe) => json!({
+                "id": recipe.id,
+                "inputs": recipe
+                    .inputs
+                    .iter()
+                    .map(|input| json!({
+                        "collection": input.collection.to_string(),
+                        "quantity": input.quantity,
+                    }))
+                    .collect::<Vec<_>>(),
+                "output": match recipe.output {
+                    RecipeOutput::Collection(collection) => json!({
+                        "collection": collection.to_string(),
+                    }),
+                    RecipeOutput::Receipt => json!("receipt"),
+                },
+                "redeemed": recipe.redeemed,
+            }),
+            ProgramAccount::Redemption(redemption) => json!({
+                "recipe": redemption.recipe.to_string(),
+                "index": redemption.index,
+                "redeemer": redemption.redeemer.to_string(),
+                "ts": redemption.ts,
+            }),
+            ProgramAccount::GateUse(gate_use) => json!({
+                "collection": gate_use.collection.to_string(),
+                "gate_mint": gate_use.gate_mint.to_string(),
+                "phase": gate_use.phase,
+            }),
+        }
+    }
+}
+
+/// Write one JSON object per recognised account, as returned by
+/// `getProgramAccounts`. Unrecognised accounts, and user records of wallets
+/// missing from `users`, are skipped; returns the number of lines written.
+pub fn export_json_lines<W: Write>(
+    program_id: &Pubkey,
+    accounts: &[(Pubkey, Vec<u8>)],
+    users: &[Pubkey],
+    mut out: W,
+) -> io::Result<usize> {
+    let mut written = 0;
+    for (address, data) in accounts.iter() {
+        if let Some(account) = classify(program_id, address, data, users) {
+            let line = json!({
+                "address": address.to_string(),
+                "type": account.kind(),
+                "data": account.to_json(),
+            });
+            writeln!(out, "{}", line)?;
+            written += 1;
+        }
+    }
+    Ok(written)
+}
@@ -1,15 +1,15 @@
-use solana_program::program_error::ProgramError;
+use num_derive::FromPrimitive;
+use num_traits::FromPrimitive;
+use solana_program::{
+    decode_error::DecodeError,
+    msg,
+    program_error::{PrintProgramError, ProgramError},
+};
 use thiserror::Error;
 
-#[macro_export]
-macro_rules! ferror {
-    ($s:expr) => {{
-        msg!("ferror {}", $s);
-        Err(ProgramError::Custom(0xfe as u32))
-    }};
-}
-
-#[derive(Error, Debug, Copy, Clone)]
+/// Program errors. Codes are part of the program interface and never change,
+/// new variants take the next free code.
+#[derive(Error, Debug, Copy, Clone, PartialEq, Eq, FromPrimitive)]
 pub enum AppError {
     #[error("Invalid signer")]
     InvalidSigner = 0xfa01,
@@ -41,14 +41,171 @@ pub enum AppError {
     #[error("Checked calculate failed")]
     CheckedCalculateFailed = 0xfa0a,
 
-    #[error("Invalid eq pubkey")]
-    InvalidEqPubkey0 = 0xfa18,
+    #[error("Invalid token program")]
+    InvalidTokenProgram = 0xfa0b,
 
-    #[error("Invalid eq pubkey")]
-    InvalidEqPubkey1 = 0xfa19,
+    #[error("Invalid authority")]
+    InvalidAuthority = 0xfa0c,
 
-    #[error("Invalid eq pubkey")]
-    InvalidEqPubkey2 = 0xfa1a,
+    #[error("Sale not open")]
+    SaleNotOpen = 0xfa0d,
+
+    #[error("Sold out")]
+    SoldOut = 0xfa0e,
+
+    #[error("Mint rate exceeded")]
+    MintRateExceeded = 0xfa0f,
+
+    #[error("Token extensions require token-2022")]
+    ExtensionsRequireToken2022 = 0xfa10,
+
+    #[error("Programmable NFTs require spl-token")]
+    ProgrammableRequiresSplToken = 0xfa11,
+
+    #[error("Rule set requires programmable NFTs")]
+    RuleSetRequiresProgrammable = 0xfa12,
+
+    #[error("Edition drops require classic NFTs")]
+    EditionDropRequiresClassic = 0xfa13,
+
+    #[error("Master token must be held by the pda creator")]
+    InvalidMasterToken = 0xfa14,
+
+    #[error("Sale still active")]
+    SaleActive = 0xfa15,
+
+    #[error("Sale ended")]
+    SaleEnded = 0xfa16,
+
+    #[error("Escrowed sales only support collection mints")]
+    EscrowUnsupported = 0xfa17,
+
+    #[error("Invalid rent sysvar")]
+    InvalidRentSysvar = 0xfa18,
+
+    #[error("Invalid system program")]
+    InvalidSystemProgram = 0xfa19,
+
+    #[error("Invalid charge account")]
+    InvalidChargeAccount = 0xfa1a,
+
+    #[error("Sale not cancelled")]
+    SaleNotCancelled = 0xfa1b,
+
+    #[error("Rebate unavailable")]
+    RebateUnavailable = 0xfa1c,
+
+    #[error("Invalid dutch auction")]
+    InvalidDutchAuction = 0xfa1d,
+
+    #[error("Rebates require an escrowed sale")]
+    RebateRequiresEscrow = 0xfa1e,
+
+    #[error("Invalid auction")]
+    InvalidAuction = 0xfa1f,
+
+    #[error("Auction not live")]
+    AuctionNotLive = 0xfa20,
+
+    #[error("Bid too low")]
+    BidTooLow = 0xfa21,
+
+    #[error("Auction not ended")]
+    AuctionNotEnded = 0xfa22,
+
+    #[error("Auction already settled")]
+    AuctionSettled = 0xfa23,
+
+    #[error("Invalid token account")]
+    InvalidTokenAccount = 0xfa24,
+
+    #[error("Invalid fee")]
+    InvalidFee = 0xfa25,
+
+    #[error("Not an item of a factory collection")]
+    NotCollectionItem = 0xfa26,
+
+    #[error("Programmable NFTs are not supported")]
+    ProgrammableUnsupported = 0xfa27,
+
+    #[error("Price mismatch")]
+    PriceMismatch = 0xfa28,
+
+    #[error("Invalid reward rate")]
+    InvalidRewardRate = 0xfa29,
+
+    #[error("Invalid recipe")]
+    InvalidRecipe = 0xfa2a,
+
+    #[error("Invalid token gate")]
+    InvalidGate = 0xfa2b,
+
+    #[error("Gate item already used in this phase")]
+    GateUsed = 0xfa2c,
+
+    #[error("Invalid token requirement")]
+    InvalidTokenRequirement = 0xfa2d,
+
+    #[error("Insufficient token balance")]
+    InsufficientTokens = 0xfa2e,
+
+    #[error("Invalid gatekeeper")]
+    InvalidGatekeeper = 0xfa2f,
+
+    #[error("Invalid mint count")]
+    InvalidMintCount = 0xfa30,
+
+    #[error("Invalid reserve")]
+    InvalidReserve = 0xfa31,
+
+    #[error("Reserve exhausted")]
+    ReserveExhausted = 0xfa32,
+
+    #[error("Invalid supply cap")]
+    InvalidSupplyCap = 0xfa33,
+
+    #[error("Creator shares must sum to 100")]
+    InvalidCreatorShares = 0xfa34,
+
+    #[error("Too many creators")]
+    TooManyCreators = 0xfa35,
+
+    #[error("Duplicate creator")]
+    DuplicateCreator = 0xfa36,
+
+    #[error("Name too long")]
+    NameTooLong = 0xfa37,
+
+    #[error("Symbol too long")]
+    SymbolTooLong = 0xfa38,
+
+    #[error("Uri too long")]
+    UriTooLong = 0xfa39,
+
+    #[error("Pool has staked items")]
+    PoolHasStakes = 0xfa3a,
+
+    #[error("Collection has an open treasury, pool, listing or auction")]
+    CollectionInUse = 0xfa3b,
+
+    #[error("Escrow and dutch auction can't change while the treasury is open")]
+    TreasuryOpen = 0xfa3c,
+}
+
+impl AppError {
+    /// Decode a custom error code returned by the program.
+    pub fn from_code(code: u32) -> Option<AppError> {
+        AppError::from_u32(code)
+    }
+
+    /// Decode the program error of a failed instruction, `None` when it is not
+    /// one of ours.
+    pub fn from_program_error(err: &ProgramError) -> Option<AppError> {
+        match err {
+            ProgramError::Custom(code) => AppError::from_code(*code),
+            _ => None,
+        }
+    }
 }
 
 impl From<AppError> for ProgramError {
@@ -56,3 +213,18 @@ impl From<AppError> for ProgramError {
         ProgramError::Custom(err as u32)
     }
 }
+
+impl<T> DecodeError<T> for AppError {
+    fn type_of() -> &'static str {
+        "AppError"
+    }
+}
+
+impl PrintProgramError for AppError {
+    fn print<E>(&self)
+    where
+        E: 'static + std::error::Error + DecodeError<E> + PrintProgramError + FromPrimitive,
+    {
+        msg!("Error: {}", self);
+    }
+}
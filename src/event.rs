@@ -0,0 +1,294 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{log::sol_log_data, pubkey::Pubkey};
+
+use crate::state::{
+    DutchAuction, TokenGate, TokenRequirement, UpdateCollectionArgs, UpdateItemArgs,
+};
+
+/// First data field of every event, tells our events apart from other
+/// `Program data:` lines. Anyone can log it, only `parse_logs` checks which
+/// program did.
+pub const EVENT_TAG: [u8; 8] = *b"nftfctev";
+
+const PROGRAM_DATA: &str = "Program data: ";
+
+/// Events emitted by the processors through `sol_log_data`, each encoded as
+/// two data fields: `EVENT_TAG` and the borsh serialized event.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq)]
+pub enum AppEvent {
+    Configured {
+        authority: Pubkey,
+        platform_fee: u16,
+        fee_recipient: Pubkey,
+    },
+    CollectionCreated {
+        collection_mint: Pubkey,
+        creator: Pubkey,
+    },
+    CollectionAdded {
+        collection: Pubkey,
+        admin: Pubkey,
+        token_program: Pubkey,
+        programmable: bool,
+    },
+    PromotionUpdated {
+        collection: Pubkey,
+        sale_price: u64,
+        public_start_ts: u64,
+        char_addr: Pubkey,
+        escrow: bool,
+        dutch: Option<DutchAuction>,
+        gate: Option<TokenGate>,
+        token: Option<TokenRequirement>,
+    },
+    Minted {
+        collection: Pubkey,
+        mint: Pubkey,
+        buyer: Pubkey,
+        price: u64,
+        index: u64,
+    },
+    TokenCreated {
+        mint: Pubkey,
+        creator: Pubkey,
+        decimals: u8,
+        token_program: Pubkey,
+    },
+    CollectionSizeSet {
+        collection: Pubkey,
+        size: u64,
+    },
+    EditionDropCreated {
+        collection: Pubkey,
+        master_mint: Pubkey,
+        max_supply: Option<u64>,
+    },
+    EditionMinted {
+        collection: Pubkey,
+        master_mint: Pubkey,
+        mint: Pubkey,
+        buyer: Pubkey,
+        price: u64,
+        edition: u64,
+    },
+    PromotionClosed {
+        collection: Pubkey,
+        recipient: Pubkey,
+    },
+    CollectionClosed {
+        collection: Pubkey,
+        recipient: Pubkey,
+    },
+    UserRecordClosed {
+        user: Pubkey,
+        recipient: Pubkey,
+    },
+    SaleFinalized {
+        collection: Pubkey,
+        amount: u64,
+    },
+    SaleCancelled {
+        collection: Pubkey,
+    },
+    Refunded {
+        collection: Pubkey,
+        mint: Pubkey,
+        buyer: Pubkey,
+        amount: u64,
+    },
+    RebateClaimed {
+        collection: Pubkey,
+        mint: Pubkey,
+        buyer: Pubkey,
+        amount: u64,
+    },
+    AuctionCreated {
+        collection: Pubkey,
+        auction: Pubkey,
+        reserve_price: u64,
+        end_ts: u64,
+    },
+    BidPlaced {
+        auction: Pubkey,
+        bidder: Pubkey,
+        amount: u64,
+        end_ts: u64,
+    },
+    AuctionSettled {
+        collection: Pubkey,
+        auction: Pubkey,
+        mint: Option<Pubkey>,
+        winner: Option<Pubkey>,
+        price: u64,
+    },
+    Listed {
+        collection: Pubkey,
+        mint: Pubkey,
+        seller: Pubkey,
+        price: u64,
+    },
+    Delisted {
+        collection: Pubkey,
+        mint: Pubkey,
+        seller: Pubkey,
+    },
+    Sold {
+        collection: Pubkey,
+        mint: Pubkey,
+        seller: Pubkey,
+        buyer: Pubkey,
+        price: u64,
+        royalties: u64,
+        platform_fee: u64,
+    },
+    OfferPlaced {
+        collection: Pubkey,
+        bidder: Pubkey,
+        price: u64,
+    },
+    OfferCancelled {
+        collection: Pubkey,
+        bidder: Pubkey,
+    },
+    OfferAccepted {
+        collection: Pubkey,
+        mint: Pubkey,
+        seller: Pubkey,
+        bidder: Pubkey,
+        price: u64,
+        royalties: u64,
+        platform_fee: u64,
+    },
+    PoolCreated {
+        collection: Pubkey,
+        reward_mint: Pubkey,
+        reward_rate: u64,
+    },
+    PoolFunded {
+        collection: Pubkey,
+        funder: Pubkey,
+        amount: u64,
+    },
+    Staked {
+        collection: Pubkey,
+        mint: Pubkey,
+        owner: Pubkey,
+    },
+    /// `forfeited` rewards accrued above the pool balance
+    Unstaked {
+        collection: Pubkey,
+        mint: Pubkey,
+        owner: Pubkey,
+        forfeited: u64,
+    },
+    RewardsClaimed {
+        collection: Pubkey,
+        mint: Pubkey,
+        owner: Pubkey,
+        amount: u64,
+    },
+    RecipeCreated {
+        recipe: Pubkey,
+        id: u64,
+    },
+    Redeemed {
+        recipe: Pubkey,
+        redeemer: Pubkey,
+        burned: Vec<Pubkey>,
+        mint: Option<Pubkey>,
+        redemption: Option<Pubkey>,
+    },
+    /// a mint that was not allowed charged the bot tax instead of failing,
+    /// `error` is the `AppError` code it would have failed with
+    BotTaxed {
+        collection: Pubkey,
+        payer: Pubkey,
+        tax: u64,
+        error: u32,
+    },
+    AdminMinted {
+        collection: Pubkey,
+        mint: Pubkey,
+        recipient: Pubkey,
+        index: u64,
+    },
+    /// `changes` holds the fields that were set
+    CollectionUpdated {
+        collection: Pubkey,
+        authority: Pubkey,
+        changes: UpdateCollectionArgs,
+    },
+    /// `mints` lists the items newly verified, skipped items are left out
+    CreatorVerified {
+        collection: Pubkey,
+        creator: Pubkey,
+        mints: Vec<Pubkey>,
+    },
+    /// `changes` holds the fields that were set
+    ItemUpdated {
+        collection: Pubkey,
+        mint: Pubkey,
+        authority: Pubkey,
+        changes: UpdateItemArgs,
+    },
+    /// `withdrawn` is the unspent balance sent back to the authority
+    PoolClosed {
+        collection: Pubkey,
+        authority: Pubkey,
+        withdrawn: u64,
+    },
+    /// the factory gave up its collection authority
+    CollectionGraduated {
+        collection: Pubkey,
+        authority: Pubkey,
+    },
+}
+
+impl AppEvent {
+    pub fn emit(&self) {
+        sol_log_data(&[&EVENT_TAG, &self.try_to_vec().unwrap()]);
+    }
+
+    /// Decode the data fields of one `sol_log_data` call.
+    pub fn decode(fields: &[&[u8]]) -> Option<AppEvent> {
+        match fields {
+            [tag, data] if *tag == EVENT_TAG => AppEvent::try_from_slice(data).ok(),
+            _ => None,
+        }
+    }
+
+    /// Decode a `Program data: ...` log line, `None` for any other line.
+    pub fn from_log(line: &str) -> Option<AppEvent> {
+        let encoded = line.strip_prefix(PROGRAM_DATA)?;
+        let fields = encoded
+            .split(' ')
+            .map(|field| STANDARD.decode(field).ok())
+            .collect::<Option<Vec<Vec<u8>>>>()?;
+        let fields: Vec<&[u8]> = fields.iter().map(|field| field.as_slice()).collect();
+        AppEvent::decode(&fields)
+    }
+
+    /// Every event in the log messages of a transaction, in emission order.
+    /// Only lines logged while this program is executing are read, so other
+    /// programs of the transaction can't forge events.
+    pub fn parse_logs<S: AsRef<str>>(logs: &[S]) -> Vec<AppEvent> {
+        let program_id = crate::id().to_string();
+        let mut invoked: Vec<&str> = vec![];
+        let mut events = vec![];
+        for line in logs.iter().map(|line| line.as_ref()) {
+            let mut words = line.split(' ');
+            match (words.next(), words.next(), words.next()) {
+                (Some("Program"), Some(id), Some("invoke")) => invoked.push(id),
+                (Some("Program"), Some(_), Some("success" | "failed:")) => {
+                    invoked.pop();
+                }
+                _ if invoked.last() == Some(&program_id.as_str()) => {
+                    events.extend(AppEvent::from_log(line));
+                }
+                _ => {}
+            }
+        }
+        events
+    }
+}
@@ -4,10 +4,12 @@ use solana_program::{
     program_error::ProgramError,
     pubkey::Pubkey,
     system_program,
-    sysvar::rent,
+    sysvar::{self, rent},
 };
 
-use crate::state::*;
+use mpl_token_metadata::accounts::{MasterEdition, Metadata};
+
+use crate::{state::*, utils::MPL_TOKEN_AUTH_RULES_ID};
 
 #[repr(C)]
 #[derive(Clone, Debug, BorshSerialize, BorshDeserialize, PartialEq)]
@@ -18,7 +20,40 @@ pub enum AppInstruction {
     AddCollection(AddCollectionArgs),
     Mint,
     AddPromotion(AddPromotionArgs),
-    CreateToken(CreateTokenArgs)
+    CreateToken(CreateTokenArgs),
+    SetCollectionSize,
+    CreateEditionDrop(CreateEditionDropArgs),
+    MintEdition,
+    ClosePromotion,
+    CloseCollection,
+    CloseUserRecord,
+    FinalizeSale,
+    CancelSale,
+    Refund,
+    ClaimRebate,
+    CreateAuction(CreateAuctionArgs),
+    PlaceBid(PlaceBidArgs),
+    SettleAuction,
+    List(ListArgs),
+    Delist,
+    Buy(BuyArgs),
+    PlaceCollectionOffer(OfferArgs),
+    CancelCollectionOffer,
+    AcceptOffer(AcceptOfferArgs),
+    CreatePool(CreatePoolArgs),
+    FundPool(FundPoolArgs),
+    Stake,
+    Unstake,
+    ClaimRewards,
+    CreateRecipe(CreateRecipeArgs),
+    Redeem,
+    MintMany(MintManyArgs),
+    AdminMint(AdminMintArgs),
+    UpdateCollection(UpdateCollectionArgs),
+    SignMetadata,
+    UpdateItem(UpdateItemArgs),
+    ClosePool,
+    GraduateCollection,
 }
 
 pub fn configure(
@@ -69,7 +104,9 @@ pub fn create_collection(
     Ok(Instruction {
         program_id: *program_id,
         accounts,
-        data: AppInstruction::CreateAndApproveCollection(args).try_to_vec().unwrap(),
+        data: AppInstruction::CreateAndApproveCollection(args)
+            .try_to_vec()
+            .unwrap(),
     })
 }
 
@@ -135,17 +172,26 @@ pub fn add_promotion(
     collection_mint: &Pubkey,
     collection_info: &Pubkey,
     promotion_info: &Pubkey,
+    treasury_info: &Pubkey,
     args: AddPromotionArgs,
 ) -> Result<Instruction, ProgramError> {
-    let accounts = vec![
+    let mut accounts = vec![
         AccountMeta::new(*signer, true),
         AccountMeta::new(*config, false),
         AccountMeta::new(*collection_mint, false),
         AccountMeta::new(*collection_info, false),
         AccountMeta::new(*promotion_info, false),
+        AccountMeta::new_readonly(*treasury_info, false),
         AccountMeta::new_readonly(rent::id(), false),
         AccountMeta::new_readonly(system_program::id(), false),
     ];
+    if let Some(TokenRequirement::Burn { mint, .. }) = &args.token {
+        let (token_info, _) = Pubkey::find_program_address(
+            &[program_id.as_ref(), mint.as_ref(), "token_info".as_bytes()],
+            program_id,
+        );
+        accounts.push(AccountMeta::new_readonly(token_info, false));
+    }
 
     Ok(Instruction {
         program_id: *program_id,
@@ -166,12 +212,12 @@ pub fn mint(
     collection_metadata: &Pubkey,
     collection_master_edition_account: &Pubkey,
     collection_authority_record: &Pubkey,
-    promotion_info: &Pubkey,    
+    promotion_info: &Pubkey,
     collection_info: &Pubkey,
     charge_info: &Pubkey,
     user_info: &Pubkey,
     metadata_program_info: &Pubkey,
-    token_program_info: &Pubkey,   
+    token_program_info: &Pubkey,
 ) -> Result<Instruction, ProgramError> {
     let accounts = vec![
         AccountMeta::new(*siger, true),
@@ -184,16 +230,16 @@ pub fn mint(
         AccountMeta::new(*collection_metadata, false),
         AccountMeta::new(*collection_master_edition_account, false),
         AccountMeta::new(*collection_authority_record, false),
-        AccountMeta::new(*promotion_info, false),        
+        AccountMeta::new(*promotion_info, false),
         AccountMeta::new(*collection_info, false),
         AccountMeta::new(*charge_info, false),
         AccountMeta::new(*user_info, false),
         AccountMeta::new_readonly(*metadata_program_info, false),
-        AccountMeta::new_readonly(*token_program_info, false),    
-        AccountMeta::new_readonly(rent::id(), false),  
+        AccountMeta::new_readonly(*token_program_info, false),
+        AccountMeta::new_readonly(rent::id(), false),
         AccountMeta::new_readonly(system_program::id(), false),
     ];
-    
+
     Ok(Instruction {
         program_id: *program_id,
         accounts,
@@ -201,26 +247,191 @@ pub fn mint(
     })
 }
 
+/// Same as [`mint`] for collections minting programmable NFTs. `rule_set`
+/// must be the collection's rule set, or any account when it has none.
+pub fn mint_programmable(
+    program_id: &Pubkey,
+    siger: &Pubkey,
+    pda_creator_info: &Pubkey,
+    mint_info: &Pubkey,
+    token_account: &Pubkey,
+    metadata_info: &Pubkey,
+    edition_info: &Pubkey,
+    collection_mint: &Pubkey,
+    collection_metadata: &Pubkey,
+    collection_master_edition_account: &Pubkey,
+    collection_authority_record: &Pubkey,
+    promotion_info: &Pubkey,
+    collection_info: &Pubkey,
+    charge_info: &Pubkey,
+    user_info: &Pubkey,
+    metadata_program_info: &Pubkey,
+    token_program_info: &Pubkey,
+    token_record: &Pubkey,
+    rule_set: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let mut ix = mint(
+        program_id,
+        siger,
+        pda_creator_info,
+        mint_info,
+        token_account,
+        metadata_info,
+        edition_info,
+        collection_mint,
+        collection_metadata,
+        collection_master_edition_account,
+        collection_authority_record,
+        promotion_info,
+        collection_info,
+        charge_info,
+        user_info,
+        metadata_program_info,
+        token_program_info,
+    )?;
+    ix.accounts
+        .extend(programmable_accounts(token_record, rule_set));
+
+    Ok(ix)
+}
+
+/// Accounts appended to instructions minting into a programmable collection.
+pub fn programmable_accounts(token_record: &Pubkey, rule_set: &Pubkey) -> Vec<AccountMeta> {
+    vec![
+        AccountMeta::new_readonly(sysvar::instructions::id(), false),
+        AccountMeta::new(*token_record, false),
+        AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+        AccountMeta::new_readonly(MPL_TOKEN_AUTH_RULES_ID, false),
+        AccountMeta::new_readonly(*rule_set, false),
+    ]
+}
+
+/// Append the accounts a mint needs when the collection's promotion escrows
+/// its proceeds, to an instruction built by [`mint`] or [`mint_programmable`].
+pub fn with_escrow(mut ix: Instruction, treasury: &Pubkey, receipt: &Pubkey) -> Instruction {
+    ix.accounts.extend([
+        AccountMeta::new(*treasury, false),
+        AccountMeta::new(*receipt, false),
+    ]);
+    ix
+}
+
+/// Further item of a `MintMany`, see [`with_extra_items`].
+pub struct ExtraMintItem {
+    pub mint: Pubkey,
+    pub token_account: Pubkey,
+    /// programmable collections only
+    pub token_record: Option<Pubkey>,
+    /// escrowed sales only, the receipt of `mint`
+    pub receipt: Option<Pubkey>,
+}
+
+/// Turn a `mint` or `mint_programmable` instruction into `MintMany`, minting
+/// `items` on top of its own item. Apply this last, the items go after every
+/// other account. At most `MAX_MINT_COUNT - 1` extra items.
+pub fn with_extra_items(mut ix: Instruction, items: &[ExtraMintItem]) -> Instruction {
+    for item in items {
+        ix.accounts.extend([
+            AccountMeta::new(item.mint, true),
+            AccountMeta::new(item.token_account, false),
+            AccountMeta::new(Metadata::find_pda(&item.mint).0, false),
+            AccountMeta::new(MasterEdition::find_pda(&item.mint).0, false),
+        ]);
+        if let Some(token_record) = item.token_record {
+            ix.accounts.push(AccountMeta::new(token_record, false));
+        }
+        if let Some(receipt) = item.receipt {
+            ix.accounts.push(AccountMeta::new(receipt, false));
+        }
+    }
+    ix.data = AppInstruction::MintMany(MintManyArgs {
+        count: items.len() as u8 + 1,
+    })
+    .try_to_vec()
+    .unwrap();
+    ix
+}
+
+/// Number of accounts `mint` and `mint_programmable` start with.
+const MINT_FIXED_ACCOUNTS: usize = 18;
+
+/// Add the gatekeeper co-signer a mint needs when the collection has one. It
+/// goes right after the fixed mint accounts, so apply this to the `mint` or
+/// `mint_programmable` instruction in any order with the other helpers.
+pub fn with_gatekeeper(mut ix: Instruction, gatekeeper: &Pubkey) -> Instruction {
+    ix.accounts.insert(
+        MINT_FIXED_ACCOUNTS,
+        AccountMeta::new_readonly(*gatekeeper, true),
+    );
+    ix
+}
+
+/// Append the gate item a mint uses during a promotion's gated phase, after
+/// the escrow accounts if any. `gate_token_account` holds `gate_mint` for the
+/// signer.
+pub fn with_gate(
+    program_id: &Pubkey,
+    mut ix: Instruction,
+    collection_mint: &Pubkey,
+    gate_mint: &Pubkey,
+    gate_token_account: &Pubkey,
+) -> Instruction {
+    let (gate_use, _) = Pubkey::find_program_address(
+        &[
+            program_id.as_ref(),
+            collection_mint.as_ref(),
+            gate_mint.as_ref(),
+            "gate".as_bytes(),
+        ],
+        program_id,
+    );
+    ix.accounts.extend([
+        AccountMeta::new_readonly(*gate_mint, false),
+        AccountMeta::new_readonly(*gate_token_account, false),
+        AccountMeta::new_readonly(Metadata::find_pda(gate_mint).0, false),
+        AccountMeta::new(gate_use, false),
+    ]);
+    ix
+}
+
+/// Append the token accounts a mint needs when the promotion has a token
+/// requirement, after the gate accounts if any. `token_account` holds the
+/// required token for the signer.
+pub fn with_token_requirement(
+    mut ix: Instruction,
+    token_mint: &Pubkey,
+    token_account: &Pubkey,
+    token_program: &Pubkey,
+) -> Instruction {
+    ix.accounts.extend([
+        AccountMeta::new(*token_mint, false),
+        AccountMeta::new(*token_account, false),
+        AccountMeta::new_readonly(*token_program, false),
+    ]);
+    ix
+}
+
 pub fn create_token(
     program_id: &Pubkey,
     siger: &Pubkey,
     config_info: &Pubkey,
     mint: &Pubkey,
     mint_vault: &Pubkey,
-    mint_auth:  &Pubkey,
+    mint_auth: &Pubkey,
     metadata_key: &Pubkey,
     metadata_program: &Pubkey,
+    token_program: &Pubkey,
     args: CreateTokenArgs,
 ) -> Result<Instruction, ProgramError> {
     let accounts = vec![
         AccountMeta::new(*siger, true),
         AccountMeta::new(*config_info, false),
-        AccountMeta::new(*mint, false),
+        AccountMeta::new(*mint, true),
         AccountMeta::new(*mint_vault, false),
         AccountMeta::new(*mint_auth, false),
         AccountMeta::new(*metadata_key, false),
         AccountMeta::new_readonly(*metadata_program, false),
-        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(*token_program, false),
         AccountMeta::new_readonly(rent::id(), false),
         AccountMeta::new_readonly(system_program::id(), false),
     ];
@@ -230,4 +441,1103 @@ pub fn create_token(
         accounts,
         data: AppInstruction::CreateToken(args).try_to_vec().unwrap(),
     })
-}
\ No newline at end of file
+}
+
+pub fn set_collection_size(
+    program_id: &Pubkey,
+    signer: &Pubkey,
+    config: &Pubkey,
+    collection_mint: &Pubkey,
+    pda_creator_info: &Pubkey,
+    collection_info: &Pubkey,
+    collection_metadata: &Pubkey,
+    collection_authority_record: &Pubkey,
+    metadata_program_info: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let accounts = vec![
+        AccountMeta::new(*signer, true),
+        AccountMeta::new_readonly(*config, false),
+        AccountMeta::new_readonly(*collection_mint, false),
+        AccountMeta::new_readonly(*pda_creator_info, false),
+        AccountMeta::new_readonly(*collection_info, false),
+        AccountMeta::new(*collection_metadata, false),
+        AccountMeta::new_readonly(*collection_authority_record, false),
+        AccountMeta::new_readonly(*metadata_program_info, false),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data: AppInstruction::SetCollectionSize.try_to_vec().unwrap(),
+    })
+}
+
+pub fn create_edition_drop(
+    program_id: &Pubkey,
+    signer: &Pubkey,
+    config: &Pubkey,
+    collection_mint: &Pubkey,
+    collection_info: &Pubkey,
+    pda_creator_info: &Pubkey,
+    master_mint: &Pubkey,
+    master_token_account: &Pubkey,
+    metadata_info: &Pubkey,
+    edition_info: &Pubkey,
+    collection_metadata: &Pubkey,
+    collection_master_edition_account: &Pubkey,
+    collection_authority_record: &Pubkey,
+    edition_drop_info: &Pubkey,
+    metadata_program_info: &Pubkey,
+    token_program_info: &Pubkey,
+    args: CreateEditionDropArgs,
+) -> Result<Instruction, ProgramError> {
+    let accounts = vec![
+        AccountMeta::new(*signer, true),
+        AccountMeta::new_readonly(*config, false),
+        AccountMeta::new(*collection_mint, false),
+        AccountMeta::new_readonly(*collection_info, false),
+        AccountMeta::new_readonly(*pda_creator_info, false),
+        AccountMeta::new(*master_mint, false),
+        AccountMeta::new(*master_token_account, false),
+        AccountMeta::new(*metadata_info, false),
+        AccountMeta::new(*edition_info, false),
+        AccountMeta::new(*collection_metadata, false),
+        AccountMeta::new(*collection_master_edition_account, false),
+        AccountMeta::new(*collection_authority_record, false),
+        AccountMeta::new(*edition_drop_info, false),
+        AccountMeta::new_readonly(*metadata_program_info, false),
+        AccountMeta::new_readonly(*token_program_info, false),
+        AccountMeta::new_readonly(rent::id(), false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data: AppInstruction::CreateEditionDrop(args)
+            .try_to_vec()
+            .unwrap(),
+    })
+}
+
+pub fn mint_edition(
+    program_id: &Pubkey,
+    siger: &Pubkey,
+    pda_creator_info: &Pubkey,
+    new_mint: &Pubkey,
+    new_metadata: &Pubkey,
+    new_edition: &Pubkey,
+    edition_marker: &Pubkey,
+    master_mint: &Pubkey,
+    master_token_account: &Pubkey,
+    master_metadata: &Pubkey,
+    master_edition: &Pubkey,
+    edition_drop_info: &Pubkey,
+    collection_mint: &Pubkey,
+    collection_info: &Pubkey,
+    promotion_info: &Pubkey,
+    charge_info: &Pubkey,
+    metadata_program_info: &Pubkey,
+    token_program_info: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let accounts = vec![
+        AccountMeta::new(*siger, true),
+        AccountMeta::new_readonly(*pda_creator_info, false),
+        AccountMeta::new(*new_mint, false),
+        AccountMeta::new(*new_metadata, false),
+        AccountMeta::new(*new_edition, false),
+        AccountMeta::new(*edition_marker, false),
+        AccountMeta::new_readonly(*master_mint, false),
+        AccountMeta::new_readonly(*master_token_account, false),
+        AccountMeta::new_readonly(*master_metadata, false),
+        AccountMeta::new(*master_edition, false),
+        AccountMeta::new(*edition_drop_info, false),
+        AccountMeta::new_readonly(*collection_mint, false),
+        AccountMeta::new_readonly(*collection_info, false),
+        AccountMeta::new_readonly(*promotion_info, false),
+        AccountMeta::new(*charge_info, false),
+        AccountMeta::new_readonly(*metadata_program_info, false),
+        AccountMeta::new_readonly(*token_program_info, false),
+        AccountMeta::new_readonly(rent::id(), false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data: AppInstruction::MintEdition.try_to_vec().unwrap(),
+    })
+}
+
+pub fn close_promotion(
+    program_id: &Pubkey,
+    signer: &Pubkey,
+    config: &Pubkey,
+    collection_mint: &Pubkey,
+    collection_info: &Pubkey,
+    promotion_info: &Pubkey,
+    treasury: &Pubkey,
+    recipient: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let accounts = vec![
+        AccountMeta::new(*signer, true),
+        AccountMeta::new_readonly(*config, false),
+        AccountMeta::new_readonly(*collection_mint, false),
+        AccountMeta::new_readonly(*collection_info, false),
+        AccountMeta::new(*promotion_info, false),
+        AccountMeta::new_readonly(*treasury, false),
+        AccountMeta::new(*recipient, false),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data: AppInstruction::ClosePromotion.try_to_vec().unwrap(),
+    })
+}
+
+pub fn close_collection(
+    program_id: &Pubkey,
+    signer: &Pubkey,
+    config: &Pubkey,
+    collection_mint: &Pubkey,
+    collection_info: &Pubkey,
+    promotion_info: &Pubkey,
+    treasury_info: &Pubkey,
+    pool_info: &Pubkey,
+    recipient: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let accounts = vec![
+        AccountMeta::new(*signer, true),
+        AccountMeta::new_readonly(*config, false),
+        AccountMeta::new_readonly(*collection_mint, false),
+        AccountMeta::new(*collection_info, false),
+        AccountMeta::new_readonly(*promotion_info, false),
+        AccountMeta::new(*treasury_info, false),
+        AccountMeta::new_readonly(*pool_info, false),
+        AccountMeta::new(*recipient, false),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data: AppInstruction::CloseCollection.try_to_vec().unwrap(),
+    })
+}
+
+/// The record rent goes back to `user`.
+pub fn close_user_record(
+    program_id: &Pubkey,
+    signer: &Pubkey,
+    config: &Pubkey,
+    user: &Pubkey,
+    user_info: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let accounts = vec![
+        AccountMeta::new(*signer, true),
+        AccountMeta::new_readonly(*config, false),
+        AccountMeta::new(*user, false),
+        AccountMeta::new(*user_info, false),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data: AppInstruction::CloseUserRecord.try_to_vec().unwrap(),
+    })
+}
+
+pub fn finalize_sale(
+    program_id: &Pubkey,
+    signer: &Pubkey,
+    config: &Pubkey,
+    collection_mint: &Pubkey,
+    collection_info: &Pubkey,
+    promotion_info: &Pubkey,
+    treasury: &Pubkey,
+    charge_info: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let accounts = vec![
+        AccountMeta::new(*signer, true),
+        AccountMeta::new_readonly(*config, false),
+        AccountMeta::new_readonly(*collection_mint, false),
+        AccountMeta::new_readonly(*collection_info, false),
+        AccountMeta::new_readonly(*promotion_info, false),
+        AccountMeta::new(*treasury, false),
+        AccountMeta::new(*charge_info, false),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data: AppInstruction::FinalizeSale.try_to_vec().unwrap(),
+    })
+}
+
+pub fn cancel_sale(
+    program_id: &Pubkey,
+    signer: &Pubkey,
+    config: &Pubkey,
+    collection_mint: &Pubkey,
+    collection_info: &Pubkey,
+    treasury: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let accounts = vec![
+        AccountMeta::new(*signer, true),
+        AccountMeta::new_readonly(*config, false),
+        AccountMeta::new_readonly(*collection_mint, false),
+        AccountMeta::new_readonly(*collection_info, false),
+        AccountMeta::new(*treasury, false),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data: AppInstruction::CancelSale.try_to_vec().unwrap(),
+    })
+}
+
+/// `token_record` is only used by programmable items, pass `None` otherwise.
+pub fn refund(
+    program_id: &Pubkey,
+    siger: &Pubkey,
+    collection_mint: &Pubkey,
+    treasury: &Pubkey,
+    receipt: &Pubkey,
+    mint_info: &Pubkey,
+    token_account: &Pubkey,
+    metadata_info: &Pubkey,
+    edition_info: &Pubkey,
+    collection_metadata: &Pubkey,
+    token_record: Option<&Pubkey>,
+    metadata_program_info: &Pubkey,
+    token_program_info: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let accounts = vec![
+        AccountMeta::new(*siger, true),
+        AccountMeta::new_readonly(*collection_mint, false),
+        AccountMeta::new(*treasury, false),
+        AccountMeta::new(*receipt, false),
+        AccountMeta::new(*mint_info, false),
+        AccountMeta::new(*token_account, false),
+        AccountMeta::new(*metadata_info, false),
+        AccountMeta::new(*edition_info, false),
+        AccountMeta::new(*collection_metadata, false),
+        match token_record {
+            Some(token_record) => AccountMeta::new(*token_record, false),
+            None => AccountMeta::new_readonly(*metadata_program_info, false),
+        },
+        AccountMeta::new_readonly(*metadata_program_info, false),
+        AccountMeta::new_readonly(*token_program_info, false),
+        AccountMeta::new_readonly(sysvar::instructions::id(), false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data: AppInstruction::Refund.try_to_vec().unwrap(),
+    })
+}
+
+pub fn claim_rebate(
+    program_id: &Pubkey,
+    siger: &Pubkey,
+    collection_mint: &Pubkey,
+    treasury: &Pubkey,
+    receipt: &Pubkey,
+    mint_info: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let accounts = vec![
+        AccountMeta::new(*siger, true),
+        AccountMeta::new_readonly(*collection_mint, false),
+        AccountMeta::new(*treasury, false),
+        AccountMeta::new(*receipt, false),
+        AccountMeta::new_readonly(*mint_info, false),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data: AppInstruction::ClaimRebate.try_to_vec().unwrap(),
+    })
+}
+
+pub fn create_auction(
+    program_id: &Pubkey,
+    signer: &Pubkey,
+    config: &Pubkey,
+    collection_mint: &Pubkey,
+    collection_info: &Pubkey,
+    auction_info: &Pubkey,
+    args: CreateAuctionArgs,
+) -> Result<Instruction, ProgramError> {
+    let accounts = vec![
+        AccountMeta::new(*signer, true),
+        AccountMeta::new_readonly(*config, false),
+        AccountMeta::new_readonly(*collection_mint, false),
+        AccountMeta::new(*collection_info, false),
+        AccountMeta::new(*auction_info, false),
+        AccountMeta::new_readonly(rent::id(), false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data: AppInstruction::CreateAuction(args).try_to_vec().unwrap(),
+    })
+}
+
+pub fn place_bid(
+    program_id: &Pubkey,
+    siger: &Pubkey,
+    collection_mint: &Pubkey,
+    auction_info: &Pubkey,
+    previous_bidder: &Pubkey,
+    args: PlaceBidArgs,
+) -> Result<Instruction, ProgramError> {
+    let accounts = vec![
+        AccountMeta::new(*siger, true),
+        AccountMeta::new_readonly(*collection_mint, false),
+        AccountMeta::new(*auction_info, false),
+        AccountMeta::new(*previous_bidder, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data: AppInstruction::PlaceBid(args).try_to_vec().unwrap(),
+    })
+}
+
+/// Programmable collections need [`programmable_accounts`] appended.
+pub fn settle_auction(
+    program_id: &Pubkey,
+    siger: &Pubkey,
+    winner: &Pubkey,
+    pda_creator_info: &Pubkey,
+    mint_info: &Pubkey,
+    token_account: &Pubkey,
+    metadata_info: &Pubkey,
+    edition_info: &Pubkey,
+    collection_mint: &Pubkey,
+    collection_metadata: &Pubkey,
+    collection_master_edition_account: &Pubkey,
+    collection_authority_record: &Pubkey,
+    collection_info: &Pubkey,
+    auction_info: &Pubkey,
+    charge_info: &Pubkey,
+    creator_info: &Pubkey,
+    metadata_program_info: &Pubkey,
+    token_program_info: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let accounts = vec![
+        AccountMeta::new(*siger, true),
+        AccountMeta::new(*winner, false),
+        AccountMeta::new(*pda_creator_info, false),
+        AccountMeta::new(*mint_info, true),
+        AccountMeta::new(*token_account, false),
+        AccountMeta::new(*metadata_info, false),
+        AccountMeta::new(*edition_info, false),
+        AccountMeta::new(*collection_mint, false),
+        AccountMeta::new(*collection_metadata, false),
+        AccountMeta::new(*collection_master_edition_account, false),
+        AccountMeta::new(*collection_authority_record, false),
+        AccountMeta::new(*collection_info, false),
+        AccountMeta::new(*auction_info, false),
+        AccountMeta::new(*charge_info, false),
+        AccountMeta::new(*creator_info, false),
+        AccountMeta::new_readonly(*metadata_program_info, false),
+        AccountMeta::new_readonly(*token_program_info, false),
+        AccountMeta::new_readonly(rent::id(), false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data: AppInstruction::SettleAuction.try_to_vec().unwrap(),
+    })
+}
+
+pub fn update_collection(
+    program_id: &Pubkey,
+    siger: &Pubkey,
+    config_info: &Pubkey,
+    collection_mint: &Pubkey,
+    collection_info: &Pubkey,
+    args: UpdateCollectionArgs,
+) -> Result<Instruction, ProgramError> {
+    let accounts = vec![
+        AccountMeta::new(*siger, true),
+        AccountMeta::new_readonly(*config_info, false),
+        AccountMeta::new_readonly(*collection_mint, false),
+        AccountMeta::new(*collection_info, false),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data: AppInstruction::UpdateCollection(args).try_to_vec().unwrap(),
+    })
+}
+
+/// Verify `siger`, a creator of the collection, on the items of `mints`.
+pub fn sign_metadata(
+    program_id: &Pubkey,
+    siger: &Pubkey,
+    collection_mint: &Pubkey,
+    collection_info: &Pubkey,
+    mints: &[Pubkey],
+) -> Result<Instruction, ProgramError> {
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*siger, true),
+        AccountMeta::new_readonly(*collection_mint, false),
+        AccountMeta::new_readonly(*collection_info, false),
+        AccountMeta::new_readonly(mpl_token_metadata::ID, false),
+    ];
+    accounts.extend(
+        mints
+            .iter()
+            .map(|mint| AccountMeta::new(Metadata::find_pda(mint).0, false)),
+    );
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data: AppInstruction::SignMetadata.try_to_vec().unwrap(),
+    })
+}
+
+/// The trailing mint, edition, system program and instructions sysvar are
+/// only read for programmable items.
+pub fn update_item(
+    program_id: &Pubkey,
+    siger: &Pubkey,
+    config_info: &Pubkey,
+    collection_mint: &Pubkey,
+    collection_info: &Pubkey,
+    pda_creator_info: &Pubkey,
+    mint: &Pubkey,
+    args: UpdateItemArgs,
+) -> Result<Instruction, ProgramError> {
+    let accounts = vec![
+        AccountMeta::new(*siger, true),
+        AccountMeta::new_readonly(*config_info, false),
+        AccountMeta::new_readonly(*collection_mint, false),
+        AccountMeta::new_readonly(*collection_info, false),
+        AccountMeta::new_readonly(*pda_creator_info, false),
+        AccountMeta::new(Metadata::find_pda(mint).0, false),
+        AccountMeta::new_readonly(mpl_token_metadata::ID, false),
+        AccountMeta::new_readonly(*mint, false),
+        AccountMeta::new_readonly(MasterEdition::find_pda(mint).0, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(sysvar::instructions::id(), false),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data: AppInstruction::UpdateItem(args).try_to_vec().unwrap(),
+    })
+}
+
+/// Mint a reserved item to `args.recipient`. Programmable collections need
+/// [`programmable_accounts`] appended.
+pub fn admin_mint(
+    program_id: &Pubkey,
+    siger: &Pubkey,
+    config_info: &Pubkey,
+    pda_creator_info: &Pubkey,
+    mint_info: &Pubkey,
+    token_account: &Pubkey,
+    metadata_info: &Pubkey,
+    edition_info: &Pubkey,
+    collection_mint: &Pubkey,
+    collection_metadata: &Pubkey,
+    collection_master_edition_account: &Pubkey,
+    collection_authority_record: &Pubkey,
+    collection_info: &Pubkey,
+    metadata_program_info: &Pubkey,
+    token_program_info: &Pubkey,
+    args: AdminMintArgs,
+) -> Result<Instruction, ProgramError> {
+    let accounts = vec![
+        AccountMeta::new(*siger, true),
+        AccountMeta::new_readonly(*config_info, false),
+        AccountMeta::new(args.recipient, false),
+        AccountMeta::new(*pda_creator_info, false),
+        AccountMeta::new(*mint_info, true),
+        AccountMeta::new(*token_account, false),
+        AccountMeta::new(*metadata_info, false),
+        AccountMeta::new(*edition_info, false),
+        AccountMeta::new(*collection_mint, false),
+        AccountMeta::new(*collection_metadata, false),
+        AccountMeta::new(*collection_master_edition_account, false),
+        AccountMeta::new(*collection_authority_record, false),
+        AccountMeta::new(*collection_info, false),
+        AccountMeta::new_readonly(*metadata_program_info, false),
+        AccountMeta::new_readonly(*token_program_info, false),
+        AccountMeta::new_readonly(rent::id(), false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data: AppInstruction::AdminMint(args).try_to_vec().unwrap(),
+    })
+}
+
+pub fn list(
+    program_id: &Pubkey,
+    siger: &Pubkey,
+    mint_info: &Pubkey,
+    token_account: &Pubkey,
+    metadata_info: &Pubkey,
+    collection_info: &Pubkey,
+    listing_info: &Pubkey,
+    vault_info: &Pubkey,
+    token_program_info: &Pubkey,
+    args: ListArgs,
+) -> Result<Instruction, ProgramError> {
+    let accounts = vec![
+        AccountMeta::new(*siger, true),
+        AccountMeta::new_readonly(*mint_info, false),
+        AccountMeta::new(*token_account, false),
+        AccountMeta::new_readonly(*metadata_info, false),
+        AccountMeta::new(*collection_info, false),
+        AccountMeta::new(*listing_info, false),
+        AccountMeta::new(*vault_info, false),
+        AccountMeta::new_readonly(*token_program_info, false),
+        AccountMeta::new_readonly(rent::id(), false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data: AppInstruction::List(args).try_to_vec().unwrap(),
+    })
+}
+
+pub fn delist(
+    program_id: &Pubkey,
+    siger: &Pubkey,
+    mint_info: &Pubkey,
+    token_account: &Pubkey,
+    listing_info: &Pubkey,
+    vault_info: &Pubkey,
+    collection_info: &Pubkey,
+    token_program_info: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let accounts = vec![
+        AccountMeta::new(*siger, true),
+        AccountMeta::new_readonly(*mint_info, false),
+        AccountMeta::new(*token_account, false),
+        AccountMeta::new(*listing_info, false),
+        AccountMeta::new(*vault_info, false),
+        AccountMeta::new(*collection_info, false),
+        AccountMeta::new_readonly(*token_program_info, false),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data: AppInstruction::Delist.try_to_vec().unwrap(),
+    })
+}
+
+/// `creators` are the collection's creators, in order.
+pub fn buy(
+    program_id: &Pubkey,
+    siger: &Pubkey,
+    seller: &Pubkey,
+    mint_info: &Pubkey,
+    token_account: &Pubkey,
+    listing_info: &Pubkey,
+    vault_info: &Pubkey,
+    collection_info: &Pubkey,
+    config: &Pubkey,
+    fee_recipient: &Pubkey,
+    token_program_info: &Pubkey,
+    creators: &[Pubkey],
+    args: BuyArgs,
+) -> Result<Instruction, ProgramError> {
+    let mut accounts = vec![
+        AccountMeta::new(*siger, true),
+        AccountMeta::new(*seller, false),
+        AccountMeta::new_readonly(*mint_info, false),
+        AccountMeta::new(*token_account, false),
+        AccountMeta::new(*listing_info, false),
+        AccountMeta::new(*vault_info, false),
+        AccountMeta::new(*collection_info, false),
+        AccountMeta::new_readonly(*config, false),
+        AccountMeta::new(*fee_recipient, false),
+        AccountMeta::new_readonly(*token_program_info, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    accounts.extend(
+        creators
+            .iter()
+            .map(|creator| AccountMeta::new(*creator, false)),
+    );
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data: AppInstruction::Buy(args).try_to_vec().unwrap(),
+    })
+}
+
+pub fn place_collection_offer(
+    program_id: &Pubkey,
+    siger: &Pubkey,
+    collection_mint: &Pubkey,
+    collection_info: &Pubkey,
+    offer_info: &Pubkey,
+    args: OfferArgs,
+) -> Result<Instruction, ProgramError> {
+    let accounts = vec![
+        AccountMeta::new(*siger, true),
+        AccountMeta::new_readonly(*collection_mint, false),
+        AccountMeta::new_readonly(*collection_info, false),
+        AccountMeta::new(*offer_info, false),
+        AccountMeta::new_readonly(rent::id(), false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data: AppInstruction::PlaceCollectionOffer(args)
+            .try_to_vec()
+            .unwrap(),
+    })
+}
+
+pub fn cancel_collection_offer(
+    program_id: &Pubkey,
+    siger: &Pubkey,
+    collection_mint: &Pubkey,
+    offer_info: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let accounts = vec![
+        AccountMeta::new(*siger, true),
+        AccountMeta::new_readonly(*collection_mint, false),
+        AccountMeta::new(*offer_info, false),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data: AppInstruction::CancelCollectionOffer.try_to_vec().unwrap(),
+    })
+}
+
+/// `creators` are the collection's creators, in order.
+pub fn accept_offer(
+    program_id: &Pubkey,
+    siger: &Pubkey,
+    bidder: &Pubkey,
+    mint_info: &Pubkey,
+    token_account: &Pubkey,
+    bidder_token_account: &Pubkey,
+    metadata_info: &Pubkey,
+    collection_info: &Pubkey,
+    offer_info: &Pubkey,
+    config: &Pubkey,
+    fee_recipient: &Pubkey,
+    token_program_info: &Pubkey,
+    creators: &[Pubkey],
+    args: AcceptOfferArgs,
+) -> Result<Instruction, ProgramError> {
+    let mut accounts = vec![
+        AccountMeta::new(*siger, true),
+        AccountMeta::new(*bidder, false),
+        AccountMeta::new_readonly(*mint_info, false),
+        AccountMeta::new(*token_account, false),
+        AccountMeta::new(*bidder_token_account, false),
+        AccountMeta::new_readonly(*metadata_info, false),
+        AccountMeta::new_readonly(*collection_info, false),
+        AccountMeta::new(*offer_info, false),
+        AccountMeta::new_readonly(*config, false),
+        AccountMeta::new(*fee_recipient, false),
+        AccountMeta::new_readonly(*token_program_info, false),
+    ];
+    accounts.extend(
+        creators
+            .iter()
+            .map(|creator| AccountMeta::new(*creator, false)),
+    );
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data: AppInstruction::AcceptOffer(args).try_to_vec().unwrap(),
+    })
+}
+
+pub fn create_pool(
+    program_id: &Pubkey,
+    signer: &Pubkey,
+    config: &Pubkey,
+    collection_mint: &Pubkey,
+    collection_info: &Pubkey,
+    pool_info: &Pubkey,
+    reward_mint: &Pubkey,
+    token_info: &Pubkey,
+    args: CreatePoolArgs,
+) -> Result<Instruction, ProgramError> {
+    let accounts = vec![
+        AccountMeta::new(*signer, true),
+        AccountMeta::new_readonly(*config, false),
+        AccountMeta::new_readonly(*collection_mint, false),
+        AccountMeta::new_readonly(*collection_info, false),
+        AccountMeta::new(*pool_info, false),
+        AccountMeta::new_readonly(*reward_mint, false),
+        AccountMeta::new_readonly(*token_info, false),
+        AccountMeta::new_readonly(rent::id(), false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data: AppInstruction::CreatePool(args).try_to_vec().unwrap(),
+    })
+}
+
+pub fn fund_pool(
+    program_id: &Pubkey,
+    siger: &Pubkey,
+    pool_info: &Pubkey,
+    reward_mint: &Pubkey,
+    source_info: &Pubkey,
+    mint_vault: &Pubkey,
+    token_program_info: &Pubkey,
+    args: FundPoolArgs,
+) -> Result<Instruction, ProgramError> {
+    let accounts = vec![
+        AccountMeta::new(*siger, true),
+        AccountMeta::new(*pool_info, false),
+        AccountMeta::new_readonly(*reward_mint, false),
+        AccountMeta::new(*source_info, false),
+        AccountMeta::new(*mint_vault, false),
+        AccountMeta::new_readonly(*token_program_info, false),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data: AppInstruction::FundPool(args).try_to_vec().unwrap(),
+    })
+}
+
+/// `destination` is a token account of the reward mint receiving the
+/// unspent balance.
+pub fn close_pool(
+    program_id: &Pubkey,
+    siger: &Pubkey,
+    config_info: &Pubkey,
+    collection_mint: &Pubkey,
+    collection_info: &Pubkey,
+    pool_info: &Pubkey,
+    reward_mint: &Pubkey,
+    mint_vault: &Pubkey,
+    mint_auth: &Pubkey,
+    destination: &Pubkey,
+    token_program_info: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let accounts = vec![
+        AccountMeta::new(*siger, true),
+        AccountMeta::new_readonly(*config_info, false),
+        AccountMeta::new_readonly(*collection_mint, false),
+        AccountMeta::new_readonly(*collection_info, false),
+        AccountMeta::new(*pool_info, false),
+        AccountMeta::new_readonly(*reward_mint, false),
+        AccountMeta::new(*mint_vault, false),
+        AccountMeta::new_readonly(*mint_auth, false),
+        AccountMeta::new(*destination, false),
+        AccountMeta::new_readonly(*token_program_info, false),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data: AppInstruction::ClosePool.try_to_vec().unwrap(),
+    })
+}
+
+pub fn graduate_collection(
+    program_id: &Pubkey,
+    siger: &Pubkey,
+    config_info: &Pubkey,
+    collection_mint: &Pubkey,
+    collection_info: &Pubkey,
+    pda_creator_info: &Pubkey,
+    collection_authority_record: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let accounts = vec![
+        AccountMeta::new(*siger, true),
+        AccountMeta::new_readonly(*config_info, false),
+        AccountMeta::new_readonly(*collection_mint, false),
+        AccountMeta::new_readonly(*collection_info, false),
+        AccountMeta::new(*pda_creator_info, false),
+        AccountMeta::new_readonly(Metadata::find_pda(collection_mint).0, false),
+        AccountMeta::new(*collection_authority_record, false),
+        AccountMeta::new_readonly(mpl_token_metadata::ID, false),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data: AppInstruction::GraduateCollection.try_to_vec().unwrap(),
+    })
+}
+
+pub fn stake(
+    program_id: &Pubkey,
+    siger: &Pubkey,
+    mint_info: &Pubkey,
+    token_account: &Pubkey,
+    metadata_info: &Pubkey,
+    edition_info: &Pubkey,
+    collection_info: &Pubkey,
+    pool_info: &Pubkey,
+    stake_info: &Pubkey,
+    token_program_info: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let accounts = vec![
+        AccountMeta::new(*siger, true),
+        AccountMeta::new_readonly(*mint_info, false),
+        AccountMeta::new(*token_account, false),
+        AccountMeta::new_readonly(*metadata_info, false),
+        AccountMeta::new_readonly(*edition_info, false),
+        AccountMeta::new_readonly(*collection_info, false),
+        AccountMeta::new(*pool_info, false),
+        AccountMeta::new(*stake_info, false),
+        AccountMeta::new_readonly(mpl_token_metadata::ID, false),
+        AccountMeta::new_readonly(*token_program_info, false),
+        AccountMeta::new_readonly(rent::id(), false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data: AppInstruction::Stake.try_to_vec().unwrap(),
+    })
+}
+
+/// `reward_account` is the owner's token account of the reward mint.
+pub fn unstake(
+    program_id: &Pubkey,
+    siger: &Pubkey,
+    mint_info: &Pubkey,
+    token_account: &Pubkey,
+    edition_info: &Pubkey,
+    pool_info: &Pubkey,
+    stake_info: &Pubkey,
+    reward_mint: &Pubkey,
+    mint_vault: &Pubkey,
+    mint_auth: &Pubkey,
+    reward_account: &Pubkey,
+    token_program_info: &Pubkey,
+    reward_token_program_info: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let accounts = vec![
+        AccountMeta::new(*siger, true),
+        AccountMeta::new_readonly(*mint_info, false),
+        AccountMeta::new(*token_account, false),
+        AccountMeta::new_readonly(*edition_info, false),
+        AccountMeta::new(*pool_info, false),
+        AccountMeta::new(*stake_info, false),
+        AccountMeta::new_readonly(*reward_mint, false),
+        AccountMeta::new(*mint_vault, false),
+        AccountMeta::new_readonly(*mint_auth, false),
+        AccountMeta::new(*reward_account, false),
+        AccountMeta::new_readonly(mpl_token_metadata::ID, false),
+        AccountMeta::new_readonly(*token_program_info, false),
+        AccountMeta::new_readonly(*reward_token_program_info, false),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data: AppInstruction::Unstake.try_to_vec().unwrap(),
+    })
+}
+
+/// `reward_account` is the owner's token account of the reward mint.
+pub fn claim_rewards(
+    program_id: &Pubkey,
+    siger: &Pubkey,
+    mint_info: &Pubkey,
+    pool_info: &Pubkey,
+    stake_info: &Pubkey,
+    reward_mint: &Pubkey,
+    mint_vault: &Pubkey,
+    mint_auth: &Pubkey,
+    reward_account: &Pubkey,
+    reward_token_program_info: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let accounts = vec![
+        AccountMeta::new(*siger, true),
+        AccountMeta::new_readonly(*mint_info, false),
+        AccountMeta::new(*pool_info, false),
+        AccountMeta::new(*stake_info, false),
+        AccountMeta::new_readonly(*reward_mint, false),
+        AccountMeta::new(*mint_vault, false),
+        AccountMeta::new_readonly(*mint_auth, false),
+        AccountMeta::new(*reward_account, false),
+        AccountMeta::new_readonly(*reward_token_program_info, false),
+    ];
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data: AppInstruction::ClaimRewards.try_to_vec().unwrap(),
+    })
+}
+
+pub fn create_recipe(
+    program_id: &Pubkey,
+    signer: &Pubkey,
+    config: &Pubkey,
+    recipe_info: &Pubkey,
+    args: CreateRecipeArgs,
+) -> Result<Instruction, ProgramError> {
+    let mut accounts = vec![
+        AccountMeta::new(*signer, true),
+        AccountMeta::new_readonly(*config, false),
+        AccountMeta::new(*recipe_info, false),
+        AccountMeta::new_readonly(rent::id(), false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    accounts.extend(args.inputs.iter().map(|input| {
+        let (collection_info, _) = Pubkey::find_program_address(
+            &[
+                program_id.as_ref(),
+                input.collection.as_ref(),
+                "collection".as_bytes(),
+            ],
+            program_id,
+        );
+        AccountMeta::new_readonly(collection_info, false)
+    }));
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data: AppInstruction::CreateRecipe(args).try_to_vec().unwrap(),
+    })
+}
+
+/// One item burned by [`redeem`].
+pub struct RedeemInput {
+    pub mint: Pubkey,
+    pub token_account: Pubkey,
+    /// collection mint the item is verified into
+    pub collection_mint: Pubkey,
+}
+
+/// Redeem a recipe with a collection output. Programmable collections need
+/// [`programmable_accounts`] appended.
+pub fn redeem(
+    program_id: &Pubkey,
+    siger: &Pubkey,
+    recipe_info: &Pubkey,
+    pda_creator: &Pubkey,
+    mint_info: &Pubkey,
+    token_account: &Pubkey,
+    metadata_info: &Pubkey,
+    edition_info: &Pubkey,
+    collection_mint: &Pubkey,
+    collection_metadata: &Pubkey,
+    collection_master_edition_account: &Pubkey,
+    collection_authority_record: &Pubkey,
+    collection_info: &Pubkey,
+    token_program_info: &Pubkey,
+    inputs: &[RedeemInput],
+) -> Result<Instruction, ProgramError> {
+    let mut accounts = redeem_accounts(siger, recipe_info);
+    accounts.extend(redeem_inputs(program_id, inputs));
+    accounts.extend([
+        AccountMeta::new_readonly(*pda_creator, false),
+        AccountMeta::new(*mint_info, false),
+        AccountMeta::new(*token_account, false),
+        AccountMeta::new(*metadata_info, false),
+        AccountMeta::new(*edition_info, false),
+        AccountMeta::new_readonly(*collection_mint, false),
+        AccountMeta::new(*collection_metadata, false),
+        AccountMeta::new_readonly(*collection_master_edition_account, false),
+        AccountMeta::new_readonly(*collection_authority_record, false),
+        AccountMeta::new(*collection_info, false),
+        AccountMeta::new_readonly(*token_program_info, false),
+    ]);
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data: AppInstruction::Redeem.try_to_vec().unwrap(),
+    })
+}
+
+/// Redeem a recipe with a receipt output, `redemption_info` is the receipt
+/// numbered by the recipe's current `redeemed` count.
+pub fn redeem_for_receipt(
+    program_id: &Pubkey,
+    siger: &Pubkey,
+    recipe_info: &Pubkey,
+    redemption_info: &Pubkey,
+    inputs: &[RedeemInput],
+) -> Result<Instruction, ProgramError> {
+    let mut accounts = redeem_accounts(siger, recipe_info);
+    accounts.extend(redeem_inputs(program_id, inputs));
+    accounts.push(AccountMeta::new(*redemption_info, false));
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data: AppInstruction::Redeem.try_to_vec().unwrap(),
+    })
+}
+
+fn redeem_accounts(siger: &Pubkey, recipe_info: &Pubkey) -> Vec<AccountMeta> {
+    vec![
+        AccountMeta::new(*siger, true),
+        AccountMeta::new(*recipe_info, false),
+        AccountMeta::new_readonly(mpl_token_metadata::ID, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(rent::id(), false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ]
+}
+
+/// Accounts of the items burned by a redemption, in recipe order.
+pub fn redeem_inputs(program_id: &Pubkey, inputs: &[RedeemInput]) -> Vec<AccountMeta> {
+    inputs
+        .iter()
+        .flat_map(|input| {
+            let (collection_info, _) = Pubkey::find_program_address(
+                &[
+                    program_id.as_ref(),
+                    input.collection_mint.as_ref(),
+                    "collection".as_bytes(),
+                ],
+                program_id,
+            );
+            [
+                AccountMeta::new(Metadata::find_pda(&input.mint).0, false),
+                AccountMeta::new(input.mint, false),
+                AccountMeta::new(input.token_account, false),
+                AccountMeta::new(MasterEdition::find_pda(&input.mint).0, false),
+                AccountMeta::new_readonly(collection_info, false),
+                AccountMeta::new(Metadata::find_pda(&input.collection_mint).0, false),
+            ]
+        })
+        .collect()
+}
@@ -1,5 +1,10 @@
+#![allow(clippy::too_many_arguments)]
+
+#[cfg(feature = "client")]
+pub mod client;
 pub mod entrypoint;
 pub mod error;
+pub mod event;
 pub mod instruction;
 pub mod processor;
 pub mod state;
@@ -7,4 +12,4 @@ pub mod utils;
 // Export current sdk types for downstream users building with a different sdk version
 pub use solana_program;
 
-solana_program::declare_id!("A1c9MZkdDiTXinfzCFUjBAXceDVTJ9Y4bA8VtYxCP4gT");
\ No newline at end of file
+solana_program::declare_id!("A1c9MZkdDiTXinfzCFUjBAXceDVTJ9Y4bA8VtYxCP4gT");
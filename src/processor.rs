@@ -24,6 +24,101 @@ pub use add_promotion::*;
 pub mod create_token;
 pub use create_token::*;
 
+pub mod set_collection_size;
+pub use set_collection_size::*;
+
+pub mod create_edition_drop;
+pub use create_edition_drop::*;
+
+pub mod mint_edition;
+pub use mint_edition::*;
+
+pub mod close_promotion;
+pub use close_promotion::*;
+
+pub mod close_collection;
+pub use close_collection::*;
+
+pub mod close_user_record;
+pub use close_user_record::*;
+
+pub mod finalize_sale;
+pub use finalize_sale::*;
+
+pub mod cancel_sale;
+pub use cancel_sale::*;
+
+pub mod refund;
+pub use refund::*;
+
+pub mod claim_rebate;
+pub use claim_rebate::*;
+
+pub mod create_auction;
+pub use create_auction::*;
+
+pub mod place_bid;
+pub use place_bid::*;
+
+pub mod settle_auction;
+pub use settle_auction::*;
+
+pub mod list;
+pub use list::*;
+
+pub mod delist;
+pub use delist::*;
+
+pub mod buy;
+pub use buy::*;
+
+pub mod place_collection_offer;
+pub use place_collection_offer::*;
+
+pub mod cancel_collection_offer;
+pub use cancel_collection_offer::*;
+
+pub mod accept_offer;
+pub use accept_offer::*;
+
+pub mod create_pool;
+pub use create_pool::*;
+
+pub mod fund_pool;
+pub use fund_pool::*;
+
+pub mod stake;
+pub use stake::*;
+
+pub mod unstake;
+pub use unstake::*;
+
+pub mod claim_rewards;
+pub use claim_rewards::*;
+
+pub mod create_recipe;
+pub use create_recipe::*;
+
+pub mod redeem;
+pub use redeem::*;
+
+pub mod admin_mint;
+pub use admin_mint::*;
+
+pub mod update_collection;
+pub use update_collection::*;
+
+pub mod sign_metadata;
+pub use sign_metadata::*;
+
+pub mod update_item;
+pub use update_item::*;
+
+pub mod close_pool;
+pub use close_pool::*;
+pub mod graduate_collection;
+pub use graduate_collection::*;
+
 pub fn process_instruction(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
@@ -59,5 +154,137 @@ pub fn process_instruction(
             msg!("Instruction: CreateToken");
             process_create_token(program_id, accounts, args)
         }
+        AppInstruction::SetCollectionSize => {
+            msg!("Instruction: SetCollectionSize");
+            process_set_collection_size(program_id, accounts)
+        }
+        AppInstruction::CreateEditionDrop(args) => {
+            msg!("Instruction: CreateEditionDrop");
+            process_create_edition_drop(program_id, accounts, args)
+        }
+        AppInstruction::MintEdition => {
+            msg!("Instruction: MintEdition");
+            process_mint_edition(program_id, accounts)
+        }
+        AppInstruction::ClosePromotion => {
+            msg!("Instruction: ClosePromotion");
+            process_close_promotion(program_id, accounts)
+        }
+        AppInstruction::CloseCollection => {
+            msg!("Instruction: CloseCollection");
+            process_close_collection(program_id, accounts)
+        }
+        AppInstruction::CloseUserRecord => {
+            msg!("Instruction: CloseUserRecord");
+            process_close_user_record(program_id, accounts)
+        }
+        AppInstruction::FinalizeSale => {
+            msg!("Instruction: FinalizeSale");
+            process_finalize_sale(program_id, accounts)
+        }
+        AppInstruction::CancelSale => {
+            msg!("Instruction: CancelSale");
+            process_cancel_sale(program_id, accounts)
+        }
+        AppInstruction::Refund => {
+            msg!("Instruction: Refund");
+            process_refund(program_id, accounts)
+        }
+        AppInstruction::ClaimRebate => {
+            msg!("Instruction: ClaimRebate");
+            process_claim_rebate(program_id, accounts)
+        }
+        AppInstruction::CreateAuction(args) => {
+            msg!("Instruction: CreateAuction");
+            process_create_auction(program_id, accounts, args)
+        }
+        AppInstruction::PlaceBid(args) => {
+            msg!("Instruction: PlaceBid");
+            process_place_bid(program_id, accounts, args)
+        }
+        AppInstruction::SettleAuction => {
+            msg!("Instruction: SettleAuction");
+            process_settle_auction(program_id, accounts)
+        }
+        AppInstruction::List(args) => {
+            msg!("Instruction: List");
+            process_list(program_id, accounts, args)
+        }
+        AppInstruction::Delist => {
+            msg!("Instruction: Delist");
+            process_delist(program_id, accounts)
+        }
+        AppInstruction::Buy(args) => {
+            msg!("Instruction: Buy");
+            process_buy(program_id, accounts, args)
+        }
+        AppInstruction::PlaceCollectionOffer(args) => {
+            msg!("Instruction: PlaceCollectionOffer");
+            process_place_collection_offer(program_id, accounts, args)
+        }
+        AppInstruction::CancelCollectionOffer => {
+            msg!("Instruction: CancelCollectionOffer");
+            process_cancel_collection_offer(program_id, accounts)
+        }
+        AppInstruction::AcceptOffer(args) => {
+            msg!("Instruction: AcceptOffer");
+            process_accept_offer(program_id, accounts, args)
+        }
+        AppInstruction::CreatePool(args) => {
+            msg!("Instruction: CreatePool");
+            process_create_pool(program_id, accounts, args)
+        }
+        AppInstruction::FundPool(args) => {
+            msg!("Instruction: FundPool");
+            process_fund_pool(program_id, accounts, args)
+        }
+        AppInstruction::Stake => {
+            msg!("Instruction: Stake");
+            process_stake(program_id, accounts)
+        }
+        AppInstruction::Unstake => {
+            msg!("Instruction: Unstake");
+            process_unstake(program_id, accounts)
+        }
+        AppInstruction::ClaimRewards => {
+            msg!("Instruction: ClaimRewards");
+            process_claim_rewards(program_id, accounts)
+        }
+        AppInstruction::CreateRecipe(args) => {
+            msg!("Instruction: CreateRecipe");
+            process_create_recipe(program_id, accounts, args)
+        }
+        AppInstruction::Redeem => {
+            msg!("Instruction: Redeem");
+            process_redeem(program_id, accounts)
+        }
+        AppInstruction::MintMany(args) => {
+            msg!("Instruction: MintMany");
+            process_mint_many(program_id, accounts, args)
+        }
+        AppInstruction::AdminMint(args) => {
+            msg!("Instruction: AdminMint");
+            process_admin_mint(program_id, accounts, args)
+        }
+        AppInstruction::UpdateCollection(args) => {
+            msg!("Instruction: UpdateCollection");
+            process_update_collection(program_id, accounts, args)
+        }
+        AppInstruction::SignMetadata => {
+            msg!("Instruction: SignMetadata");
+            process_sign_metadata(program_id, accounts)
+        }
+        AppInstruction::UpdateItem(args) => {
+            msg!("Instruction: UpdateItem");
+            process_update_item(program_id, accounts, args)
+        }
+        AppInstruction::ClosePool => {
+            msg!("Instruction: ClosePool");
+            process_close_pool(program_id, accounts)
+        }
+        AppInstruction::GraduateCollection => {
+            msg!("Instruction: GraduateCollection");
+            process_graduate_collection(program_id, accounts)
+        }
     }
 }
@@ -0,0 +1,99 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    pubkey::Pubkey,
+};
+
+use crate::{error::AppError, event::AppEvent, processor::SaleSplit, state::*, utils::*};
+
+/// Fill a collection offer with an item of that collection. The escrowed
+/// lamports pay the collection creators their royalties, the platform its
+/// fee and the seller the rest; the offer rent goes back to the bidder. The
+/// creators of the collection follow the fixed accounts, in order.
+pub fn process_accept_offer(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: AcceptOfferArgs,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let signer_info = next_account_info(account_info_iter)?;
+    let bidder_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    let token_account = next_account_info(account_info_iter)?;
+    let bidder_token_account = next_account_info(account_info_iter)?;
+    let metadata_info = next_account_info(account_info_iter)?;
+    let collection_info = next_account_info(account_info_iter)?;
+    let offer_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    let fee_recipient_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+
+    assert_signer(signer_info)?;
+    assert_token_program(token_program_info)?;
+    assert_config(program_id, config_info)?;
+    assert_owned_by(config_info, program_id)?;
+    assert_owned_by(offer_info, program_id)?;
+    assert_owned_by(mint_info, token_program_info.key)?;
+
+    assert_collection_item(program_id, mint_info, metadata_info, collection_info)?;
+    let collection_data = CollectionData::from_account_info(collection_info)?;
+    if collection_data.programmable {
+        return Err(AppError::ProgrammableUnsupported.into());
+    }
+    assert_offer(
+        program_id,
+        &collection_data.collection_mint,
+        bidder_info.key,
+        offer_info,
+    )?;
+    let offer_data = OfferData::from_account_info(offer_info)?;
+    if offer_data.price != args.price {
+        return Err(AppError::PriceMismatch.into());
+    }
+    let bidder_token = unpack_token_account(bidder_token_account)?;
+    if bidder_token.owner != offer_data.bidder || bidder_token.mint != *mint_info.key {
+        return Err(AppError::InvalidTokenAccount.into());
+    }
+    let config_data = ConfigureData::from_account_info(config_info)?;
+
+    let split = SaleSplit::new(&collection_data, offer_data.price, config_data.platform_fee)?;
+
+    //royalties
+    for (creator, amount) in split.royalties.iter() {
+        let creator_info = next_account_info(account_info_iter)?;
+        assert_eq_pubkey(creator_info, creator)?;
+        transfer_lamports(offer_info, creator_info, *amount)?;
+    }
+
+    //platform fee
+    if split.platform_fee > 0 {
+        assert_eq_pubkey(fee_recipient_info, &config_data.fee_recipient)?;
+        transfer_lamports(offer_info, fee_recipient_info, split.platform_fee)?;
+    }
+
+    transfer_lamports(offer_info, signer_info, split.proceeds)?;
+
+    spl_token_transfer_invoke(
+        token_program_info.clone(),
+        token_account.clone(),
+        mint_info.clone(),
+        bidder_token_account.clone(),
+        signer_info.clone(),
+        1,
+        0,
+    )?;
+    close_account(offer_info, bidder_info)?;
+
+    AppEvent::OfferAccepted {
+        collection: offer_data.collection,
+        mint: *mint_info.key,
+        seller: *signer_info.key,
+        bidder: offer_data.bidder,
+        price: offer_data.price,
+        royalties: split.total_royalties(),
+        platform_fee: split.platform_fee,
+    }
+    .emit();
+
+    Ok(())
+}
@@ -2,13 +2,10 @@ use borsh::BorshSerialize;
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
     entrypoint::ProgramResult,
-    msg,
-    program_error::ProgramError,
     pubkey::Pubkey,
-    sysvar,
 };
 
-use crate::{ferror, state::*, utils::*};
+use crate::{error::AppError, event::AppEvent, state::*, utils::*};
 
 pub fn process_add_collection(
     program_id: &Pubkey,
@@ -24,20 +21,32 @@ pub fn process_add_collection(
     let rent_info = next_account_info(account_info_iter)?;
     let system_info = next_account_info(account_info_iter)?;
 
-    assert_eq_pubkey(&rent_info, &sysvar::rent::id())?;
-    assert_eq_pubkey(&system_info, &solana_program::system_program::id())?;
-    assert_signer(&signer_info)?;
-    assert_pda_creator(&program_id, collection_mint, pda_creator_info)?;
-    assert_collection(&program_id, collection_mint, collection_info)?;
+    assert_rent_sysvar(rent_info)?;
+    assert_system_program(system_info)?;
+    assert_signer(signer_info)?;
+    if args.token_program != spl_token::id() && args.token_program != spl_token_2022::id() {
+        return Err(AppError::InvalidTokenProgram.into());
+    }
+    assert_owned_by(collection_mint, &args.token_program)?;
+    if args.programmable && args.token_program != spl_token::id() {
+        return Err(AppError::ProgrammableRequiresSplToken.into());
+    }
+    if !args.programmable && args.rule_set.is_some() {
+        return Err(AppError::RuleSetRequiresProgrammable.into());
+    }
+    assert_pda_creator(program_id, collection_mint, pda_creator_info)?;
+    assert_creators(&args.creators, pda_creator_info.key)?;
+    assert_item_metadata(&args.name, &args.symbol, &args.uri, args.fee)?;
+    assert_collection(program_id, collection_mint, collection_info)?;
 
     //check authority
     let config_data = ConfigureData::from_account_info(config_info)?;
     if config_data.authority != *signer_info.key {
-        return ferror!("invalid authority");
+        return Err(AppError::InvalidAuthority.into());
     }
-    assert_owned_by(config_info, &program_id)?;
+    assert_owned_by(config_info, program_id)?;
 
-    let bump = assert_collection(&program_id, &collection_mint, &collection_info)?;
+    let bump = assert_collection(program_id, collection_mint, collection_info)?;
 
     if collection_info.data_is_empty() {
         create_or_allocate_account_raw(
@@ -47,20 +56,69 @@ pub fn process_add_collection(
             system_info,
             signer_info,
             CollectionData::LEN,
-            &[program_id.as_ref(), collection_mint.key.as_ref(), "collection".as_bytes(), &[bump]],
+            &[
+                program_id.as_ref(),
+                collection_mint.key.as_ref(),
+                "collection".as_bytes(),
+                &[bump],
+            ],
         )?;
     }
 
-    let mut collection_data = CollectionData::from_account_info(collection_info)?;
-    collection_data.collection_mint = collection_mint.key.clone();
+    // collections written before layouts were versioned grow to the current one
+    let mut collection_data = CollectionData::from_any_layout(collection_info)?;
+    if collection_info.data_len() != CollectionData::LEN {
+        resize_account(
+            collection_info,
+            rent_info,
+            system_info,
+            signer_info,
+            CollectionData::LEN,
+        )?;
+    }
+    collection_data.collection_mint = *collection_mint.key;
     collection_data.admin = args.admin;
-    collection_data.pda_creator = pda_creator_info.key.clone();
+    collection_data.pda_creator = *pda_creator_info.key;
     collection_data.creators = args.creators;
     collection_data.fee = args.fee;
     collection_data.name = args.name;
     collection_data.symbol = args.symbol;
     collection_data.uri = args.uri;
+    collection_data.token_program = args.token_program;
+    collection_data.programmable = args.programmable;
+    collection_data.rule_set = args.rule_set;
+    collection_data.gatekeeper = args.gatekeeper;
+    collection_data.bot_tax = args.bot_tax;
+    // the cap and reserve can't drop below what was minted
+    if args.supply_cap == 0
+        || args.supply_cap > MAX_COLLECTION_SUPPLY
+        || args.supply_cap < collection_data.max_supply
+    {
+        return Err(AppError::InvalidSupplyCap.into());
+    }
+    let reserve_left = args
+        .reserve
+        .checked_sub(collection_data.reserve_minted)
+        .ok_or(AppError::InvalidReserve)?;
+    let committed = collection_data
+        .max_supply
+        .checked_add(reserve_left)
+        .ok_or(AppError::CheckedCalculateFailed)?;
+    if committed > args.supply_cap {
+        return Err(AppError::InvalidReserve.into());
+    }
+    collection_data.reserve = args.reserve;
+    collection_data.supply_cap = args.supply_cap;
+    collection_data.version = LAYOUT_VERSION;
     collection_data.serialize(&mut &mut collection_info.data.borrow_mut()[..])?;
 
+    AppEvent::CollectionAdded {
+        collection: collection_data.collection_mint,
+        admin: collection_data.admin,
+        token_program: collection_data.token_program,
+        programmable: collection_data.programmable,
+    }
+    .emit();
+
     Ok(())
 }
@@ -2,14 +2,15 @@ use borsh::BorshSerialize;
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
     entrypoint::ProgramResult,
-    msg,
-    program_error::ProgramError,
     pubkey::Pubkey,
-    sysvar,
 };
 
-use crate::{ferror, state::*, utils::*};
+use crate::{error::AppError, event::AppEvent, state::*, utils::*};
 
+/// Set the sale of a collection. A promotion burning a token as its price
+/// takes the token's `token_info` account after the fixed accounts. While an
+/// escrowed sale holds proceeds in an open treasury its escrow and dutch
+/// auction settings are fixed, finalizing and refunds depend on them.
 pub fn process_add_promotion(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
@@ -21,32 +22,58 @@ pub fn process_add_promotion(
     let collection_mint = next_account_info(account_info_iter)?;
     let collection_info = next_account_info(account_info_iter)?;
     let promotion_info = next_account_info(account_info_iter)?;
+    let treasury_info = next_account_info(account_info_iter)?;
     let rent_info = next_account_info(account_info_iter)?;
     let system_info = next_account_info(account_info_iter)?;
 
-    assert_eq_pubkey(&rent_info, &sysvar::rent::id())?;
-    assert_eq_pubkey(&system_info, &solana_program::system_program::id())?;
-    assert_signer(&signer_info)?;
-    assert_collection(&program_id, &collection_mint, &collection_info)?;
+    assert_rent_sysvar(rent_info)?;
+    assert_system_program(system_info)?;
+    assert_signer(signer_info)?;
+    assert_collection(program_id, collection_mint, collection_info)?;
+    assert_treasury(program_id, collection_mint, treasury_info)?;
 
     //check authority
     let config_data = ConfigureData::from_account_info(config_info)?;
     if config_data.authority != *signer_info.key {
-        return ferror!("invalid authority");
+        return Err(AppError::InvalidAuthority.into());
     }
-    assert_owned_by(config_info, &program_id)?;
+    assert_owned_by(config_info, program_id)?;
 
     let collection_data = CollectionData::from_account_info(collection_info)?;
-    let path = &[
-        program_id.as_ref(),
-        collection_info.key.as_ref(),
-    ];
-    let bump = assert_derivation(&program_id, &promotion_info, path)?;
-    let bump_seed = &[
-        program_id.as_ref(),
-        collection_info.key.as_ref(),
-        &[bump],
-    ];
+    if let Some(dutch) = &args.dutch {
+        if dutch.interval == 0 || dutch.floor_price > dutch.start_price {
+            return Err(AppError::InvalidDutchAuction.into());
+        }
+        if dutch.rebate && !args.escrow {
+            return Err(AppError::RebateRequiresEscrow.into());
+        }
+    }
+    if let Some(gate) = &args.gate {
+        // discounted items would pay below the clearing price rebates assume
+        let rebate = args.dutch.as_ref().is_some_and(|dutch| dutch.rebate);
+        if gate.start_ts >= args.public_start_ts || (gate.price.is_some() && rebate) {
+            return Err(AppError::InvalidGate.into());
+        }
+    }
+    match &args.token {
+        Some(token) if token.amount() == 0 => {
+            return Err(AppError::InvalidTokenRequirement.into());
+        }
+        Some(TokenRequirement::Burn { mint, .. }) => {
+            // receipts and rebates are in lamports, burn priced items can't be refunded
+            if args.escrow {
+                return Err(AppError::InvalidTokenRequirement.into());
+            }
+            let token_info = next_account_info(account_info_iter)?;
+            assert_owned_by(token_info, program_id)?;
+            assert_token_info(program_id, mint, token_info)?;
+        }
+        _ => {}
+    }
+
+    let path = &[program_id.as_ref(), collection_info.key.as_ref()];
+    let bump = assert_derivation(program_id, promotion_info, path)?;
+    let bump_seed = &[program_id.as_ref(), collection_info.key.as_ref(), &[bump]];
     if promotion_info.data_is_empty() {
         create_or_allocate_account_raw(
             *program_id,
@@ -59,15 +86,51 @@ pub fn process_add_promotion(
         )?;
     }
 
-    let mut promotion_data = PromotionData::from_account_info(promotion_info)?;
+    // promotions written before layouts were versioned grow to the current one
+    let mut promotion_data = PromotionData::from_any_layout(promotion_info)?;
+    if promotion_info.data_len() != PromotionData::LEN {
+        resize_account(
+            promotion_info,
+            rent_info,
+            system_info,
+            signer_info,
+            PromotionData::LEN,
+        )?;
+    }
 
+    if !treasury_info.data_is_empty() {
+        assert_owned_by(treasury_info, program_id)?;
+        let treasury_data = TreasuryData::from_account_info(treasury_info)?;
+        if treasury_data.status == SaleStatus::Open
+            && (promotion_data.escrow != args.escrow || promotion_data.dutch != args.dutch)
+        {
+            return Err(AppError::TreasuryOpen.into());
+        }
+    }
 
     promotion_data.sale_price = args.sale_price;
     promotion_data.public_start_ts = args.public_start_ts;
     promotion_data.char_addr = args.char_addr;
+    promotion_data.escrow = args.escrow;
+    promotion_data.dutch = args.dutch;
+    promotion_data.gate = args.gate;
+    promotion_data.token = args.token;
     promotion_data.collection = collection_data.collection_mint;
-    
+    promotion_data.version = LAYOUT_VERSION;
+
     promotion_data.serialize(&mut &mut promotion_info.data.borrow_mut()[..])?;
 
+    AppEvent::PromotionUpdated {
+        collection: promotion_data.collection,
+        sale_price: promotion_data.sale_price,
+        public_start_ts: promotion_data.public_start_ts,
+        char_addr: promotion_data.char_addr,
+        escrow: promotion_data.escrow,
+        dutch: promotion_data.dutch,
+        gate: promotion_data.gate,
+        token: promotion_data.token,
+    }
+    .emit();
+
     Ok(())
 }
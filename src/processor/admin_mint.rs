@@ -0,0 +1,131 @@
+use borsh::BorshSerialize;
+use mpl_token_metadata::types::PrintSupply;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    pubkey::Pubkey,
+};
+use spl_associated_token_account::get_associated_token_address_with_program_id;
+
+use crate::{error::AppError, event::AppEvent, processor::*, state::*, utils::*};
+
+/// Mint a reserved item to `recipient` without payment, sale time or mint
+/// rate checks. The signer pays for the item accounts and, for classic items,
+/// must be the mint authority of `mint` with its single token already in the
+/// recipient's associated token account.
+pub fn process_admin_mint(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: AdminMintArgs,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let signer_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    let recipient_info = next_account_info(account_info_iter)?;
+    let pda_creator_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    let token_account = next_account_info(account_info_iter)?;
+    let metadata_info = next_account_info(account_info_iter)?;
+    let edition_info = next_account_info(account_info_iter)?;
+    let collection_mint = next_account_info(account_info_iter)?;
+    let collection_metadata = next_account_info(account_info_iter)?;
+    let collection_master_edition_account = next_account_info(account_info_iter)?;
+    let collection_authority_record = next_account_info(account_info_iter)?;
+    let collection_info = next_account_info(account_info_iter)?;
+    let metadata_program_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+    let rent_info = next_account_info(account_info_iter)?;
+    let system_info = next_account_info(account_info_iter)?;
+
+    assert_signer(signer_info)?;
+    assert_token_program(token_program_info)?;
+    assert_rent_sysvar(rent_info)?;
+    assert_system_program(system_info)?;
+    assert_config(program_id, config_info)?;
+    assert_owned_by(config_info, program_id)?;
+    assert_owned_by(collection_info, program_id)?;
+    assert_collection(program_id, collection_mint, collection_info)?;
+    assert_eq_pubkey(recipient_info, &args.recipient)?;
+
+    //check authority
+    let config_data = ConfigureData::from_account_info(config_info)?;
+    let mut collection_data = CollectionData::from_account_info(collection_info)?;
+    if config_data.authority != *signer_info.key && collection_data.admin != *signer_info.key {
+        return Err(AppError::InvalidAuthority.into());
+    }
+
+    if collection_data.reserve_left() == 0 {
+        return Err(AppError::ReserveExhausted.into());
+    }
+    if collection_data.max_supply >= collection_data.supply_cap {
+        return Err(AppError::SoldOut.into());
+    }
+
+    assert_eq_pubkey(token_program_info, &collection_data.token_program)?;
+    let recipient_ata = get_associated_token_address_with_program_id(
+        &args.recipient,
+        mint_info.key,
+        token_program_info.key,
+    );
+    assert_eq_pubkey(token_account, &recipient_ata)?;
+    let programmable = if collection_data.programmable {
+        Some(ProgrammableAccounts::from_iter(
+            account_info_iter,
+            &collection_data,
+        )?)
+    } else {
+        assert_owned_by(mint_info, token_program_info.key)?;
+        let token = unpack_token_account(token_account)?;
+        if token.owner != args.recipient || token.mint != *mint_info.key || token.amount != 1 {
+            return Err(AppError::InvalidTokenAccount.into());
+        }
+        None
+    };
+
+    let pda_bump = assert_pda_creator(program_id, collection_mint, pda_creator_info)?;
+    let pda_seed = [
+        program_id.as_ref(),
+        collection_mint.key.as_ref(),
+        "pda_creator".as_bytes(),
+        &[pda_bump],
+    ];
+
+    let nft_accounts = MintNftAccounts {
+        payer: signer_info,
+        owner: recipient_info,
+        pda_creator: pda_creator_info,
+        mint: mint_info,
+        token_account,
+        metadata: metadata_info,
+        edition: edition_info,
+        collection_mint,
+        collection_metadata,
+        collection_master_edition: collection_master_edition_account,
+        collection_authority_record,
+        metadata_program: metadata_program_info,
+        token_program: token_program_info,
+        rent: rent_info,
+        system: system_info,
+        programmable,
+    };
+    create_collection_item(
+        &nft_accounts,
+        &collection_data,
+        PrintSupply::Zero,
+        &pda_seed,
+    )?;
+
+    collection_data.max_supply += 1;
+    collection_data.reserve_minted += 1;
+    collection_data.serialize(&mut *collection_info.try_borrow_mut_data()?)?;
+
+    AppEvent::AdminMinted {
+        collection: *collection_mint.key,
+        mint: *mint_info.key,
+        recipient: args.recipient,
+        index: collection_data.max_supply,
+    }
+    .emit();
+
+    Ok(())
+}
@@ -5,7 +5,6 @@ use solana_program::{
     msg,
     program::invoke,
     pubkey::Pubkey,
-    sysvar,
 };
 
 use crate::utils::*;
@@ -23,12 +22,16 @@ pub fn process_approve_collection(program_id: &Pubkey, accounts: &[AccountInfo])
     let rent_info = next_account_info(account_info_iter)?;
     let system_info = next_account_info(account_info_iter)?;
 
-    assert_eq_pubkey(&metadata_program_info, &mpl_token_metadata::programs::MPL_TOKEN_METADATA_ID)?;
-    assert_eq_pubkey(&token_program_info, &spl_token::id())?;
-    assert_eq_pubkey(&rent_info, &sysvar::rent::id())?;
-    assert_eq_pubkey(&system_info, &solana_program::system_program::id())?;
-    assert_signer(&signer_info)?;
-    assert_pda_creator(&program_id, mint_info, pda_creator_info)?;
+    assert_eq_pubkey(
+        metadata_program_info,
+        &mpl_token_metadata::programs::MPL_TOKEN_METADATA_ID,
+    )?;
+    assert_token_program(token_program_info)?;
+    assert_rent_sysvar(rent_info)?;
+    assert_system_program(system_info)?;
+    assert_signer(signer_info)?;
+    assert_owned_by(mint_info, token_program_info.key)?;
+    assert_pda_creator(program_id, mint_info, pda_creator_info)?;
 
     let approve_collection_accounts = vec![
         collection_authority_record.clone(),
@@ -42,20 +45,17 @@ pub fn process_approve_collection(program_id: &Pubkey, accounts: &[AccountInfo])
     ];
 
     msg!("approve collection");
-    let aca = ApproveCollectionAuthority{
-        collection_authority_record:*collection_authority_record.key,
-        new_collection_authority:*pda_creator_info.key,
-        update_authority:*signer_info.key,
-        payer:*signer_info.key,
-        metadata:*metadata_info.key,
-        mint:*mint_info.key,
-        system_program:*system_info.key,
-        rent:Some(*rent_info.key),
+    let aca = ApproveCollectionAuthority {
+        collection_authority_record: *collection_authority_record.key,
+        new_collection_authority: *pda_creator_info.key,
+        update_authority: *signer_info.key,
+        payer: *signer_info.key,
+        metadata: *metadata_info.key,
+        mint: *mint_info.key,
+        system_program: *system_info.key,
+        rent: Some(*rent_info.key),
     };
-    invoke(
-        &aca.instruction(),
-        &approve_collection_accounts,
-    )?;
+    invoke(&aca.instruction(), &approve_collection_accounts)?;
 
     Ok(())
 }
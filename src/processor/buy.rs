@@ -0,0 +1,183 @@
+use borsh::BorshSerialize;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program::invoke,
+    pubkey::Pubkey,
+    system_instruction,
+};
+
+use crate::{error::AppError, event::AppEvent, state::*, utils::*};
+
+/// Split the royalties of a secondary sale at `price` between the
+/// collection's creators by share, `fee` basis points in total.
+pub fn creator_royalties(collection_data: &CollectionData, price: u64) -> Vec<(Pubkey, u64)> {
+    let royalties = bps_of(price, collection_data.fee);
+    collection_data
+        .creators
+        .iter()
+        .map(|creator| {
+            let amount = (royalties as u128 * creator.share as u128 / 100) as u64;
+            (creator.address, amount)
+        })
+        .collect()
+}
+
+/// Where the lamports of a secondary sale go.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SaleSplit {
+    pub royalties: Vec<(Pubkey, u64)>,
+    pub platform_fee: u64,
+    pub proceeds: u64,
+}
+
+impl SaleSplit {
+    /// Split a sale at `price` into the creator royalties, the platform fee
+    /// and the seller's proceeds. The platform takes `platform_fee` basis
+    /// points of what the royalties leave, so any royalty rate stays sellable.
+    pub fn new(
+        collection_data: &CollectionData,
+        price: u64,
+        platform_fee: u16,
+    ) -> Result<SaleSplit, AppError> {
+        let royalties = creator_royalties(collection_data, price);
+        let rest = royalties
+            .iter()
+            .try_fold(price, |rest, (_, amount)| rest.checked_sub(*amount))
+            .ok_or(AppError::CheckedCalculateFailed)?;
+        let platform_fee = bps_of(rest, platform_fee);
+        let proceeds = rest - platform_fee;
+        Ok(SaleSplit {
+            royalties,
+            platform_fee,
+            proceeds,
+        })
+    }
+
+    pub fn total_royalties(&self) -> u64 {
+        self.royalties.iter().map(|(_, amount)| amount).sum()
+    }
+}
+
+fn pay<'a>(
+    payer: &AccountInfo<'a>,
+    to: &AccountInfo<'a>,
+    system_info: &AccountInfo<'a>,
+    amount: u64,
+) -> ProgramResult {
+    if amount == 0 {
+        return Ok(());
+    }
+    invoke(
+        &system_instruction::transfer(payer.key, to.key, amount),
+        &[payer.clone(), to.clone(), system_info.clone()],
+    )
+}
+
+/// Buy a listed item. The buyer pays the collection creators their
+/// royalties, the platform its fee and the seller the rest of the price. The
+/// creators of the collection follow the fixed accounts, in order.
+pub fn process_buy(program_id: &Pubkey, accounts: &[AccountInfo], args: BuyArgs) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let signer_info = next_account_info(account_info_iter)?;
+    let seller_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    let token_account = next_account_info(account_info_iter)?;
+    let listing_info = next_account_info(account_info_iter)?;
+    let vault_info = next_account_info(account_info_iter)?;
+    let collection_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    let fee_recipient_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+    let system_info = next_account_info(account_info_iter)?;
+
+    assert_signer(signer_info)?;
+    assert_token_program(token_program_info)?;
+    assert_system_program(system_info)?;
+    assert_config(program_id, config_info)?;
+    assert_owned_by(config_info, program_id)?;
+    assert_owned_by(listing_info, program_id)?;
+    assert_owned_by(collection_info, program_id)?;
+    let listing_bump = assert_listing(program_id, mint_info, listing_info)?;
+    assert_listing_vault(program_id, mint_info, vault_info)?;
+
+    let listing_data = ListingData::from_account_info(listing_info)?;
+    assert_eq_pubkey(seller_info, &listing_data.seller)?;
+    if listing_data.price != args.price {
+        return Err(AppError::PriceMismatch.into());
+    }
+    let path = &[
+        program_id.as_ref(),
+        listing_data.collection.as_ref(),
+        "collection".as_bytes(),
+    ];
+    assert_derivation(program_id, collection_info, path)?;
+    let mut collection_data = CollectionData::from_account_info(collection_info)?;
+    let config_data = ConfigureData::from_account_info(config_info)?;
+
+    let split = SaleSplit::new(
+        &collection_data,
+        listing_data.price,
+        config_data.platform_fee,
+    )?;
+
+    //royalties
+    for (creator, amount) in split.royalties.iter() {
+        let creator_info = next_account_info(account_info_iter)?;
+        assert_eq_pubkey(creator_info, creator)?;
+        pay(signer_info, creator_info, system_info, *amount)?;
+    }
+
+    //platform fee
+    if split.platform_fee > 0 {
+        assert_eq_pubkey(fee_recipient_info, &config_data.fee_recipient)?;
+        pay(
+            signer_info,
+            fee_recipient_info,
+            system_info,
+            split.platform_fee,
+        )?;
+    }
+
+    pay(signer_info, seller_info, system_info, split.proceeds)?;
+
+    let listing_seeds = [
+        program_id.as_ref(),
+        mint_info.key.as_ref(),
+        "listing".as_bytes(),
+        &[listing_bump],
+    ];
+    spl_token_transfer(
+        token_program_info.clone(),
+        vault_info.clone(),
+        mint_info.clone(),
+        token_account.clone(),
+        listing_info.clone(),
+        1,
+        0,
+        &listing_seeds,
+    )?;
+    spl_token_close_account(
+        token_program_info,
+        vault_info,
+        seller_info,
+        listing_info,
+        &listing_seeds,
+    )?;
+    close_account(listing_info, seller_info)?;
+    collection_data.listings -= 1;
+    collection_data.serialize(&mut *collection_info.try_borrow_mut_data()?)?;
+
+    AppEvent::Sold {
+        collection: listing_data.collection,
+        mint: listing_data.mint,
+        seller: listing_data.seller,
+        buyer: *signer_info.key,
+        price: listing_data.price,
+        royalties: split.total_royalties(),
+        platform_fee: split.platform_fee,
+    }
+    .emit();
+
+    Ok(())
+}
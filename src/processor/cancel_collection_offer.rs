@@ -0,0 +1,34 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    pubkey::Pubkey,
+};
+
+use crate::{event::AppEvent, state::*, utils::*};
+
+/// Withdraw a collection offer, returning the escrowed lamports and the rent
+/// to the bidder.
+pub fn process_cancel_collection_offer(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let signer_info = next_account_info(account_info_iter)?;
+    let collection_mint = next_account_info(account_info_iter)?;
+    let offer_info = next_account_info(account_info_iter)?;
+
+    assert_signer(signer_info)?;
+    assert_owned_by(offer_info, program_id)?;
+    assert_offer(program_id, collection_mint.key, signer_info.key, offer_info)?;
+    let offer_data = OfferData::from_account_info(offer_info)?;
+
+    close_account(offer_info, signer_info)?;
+
+    AppEvent::OfferCancelled {
+        collection: offer_data.collection,
+        bidder: offer_data.bidder,
+    }
+    .emit();
+
+    Ok(())
+}
@@ -0,0 +1,48 @@
+use borsh::BorshSerialize;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    pubkey::Pubkey,
+};
+
+use crate::{error::AppError, event::AppEvent, state::*, utils::*};
+
+/// Cancel an escrowed sale. Mints stop and every buyer can refund their item
+/// for the price on its receipt.
+pub fn process_cancel_sale(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let signer_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    let collection_mint = next_account_info(account_info_iter)?;
+    let collection_info = next_account_info(account_info_iter)?;
+    let treasury_info = next_account_info(account_info_iter)?;
+
+    assert_signer(signer_info)?;
+    assert_config(program_id, config_info)?;
+    assert_owned_by(config_info, program_id)?;
+    assert_owned_by(collection_info, program_id)?;
+    assert_owned_by(treasury_info, program_id)?;
+    assert_collection(program_id, collection_mint, collection_info)?;
+    assert_treasury(program_id, collection_mint, treasury_info)?;
+
+    //check authority
+    let config_data = ConfigureData::from_account_info(config_info)?;
+    let collection_data = CollectionData::from_account_info(collection_info)?;
+    if config_data.authority != *signer_info.key && collection_data.admin != *signer_info.key {
+        return Err(AppError::InvalidAuthority.into());
+    }
+
+    let mut treasury_data = TreasuryData::from_account_info(treasury_info)?;
+    if treasury_data.status != SaleStatus::Open {
+        return Err(AppError::SaleEnded.into());
+    }
+    treasury_data.status = SaleStatus::Cancelled;
+    treasury_data.serialize(&mut *treasury_info.try_borrow_mut_data()?)?;
+
+    AppEvent::SaleCancelled {
+        collection: collection_data.collection_mint,
+    }
+    .emit();
+
+    Ok(())
+}
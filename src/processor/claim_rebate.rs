@@ -0,0 +1,57 @@
+use borsh::BorshSerialize;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    pubkey::Pubkey,
+};
+
+use crate::{error::AppError, event::AppEvent, state::*, utils::*};
+
+/// Pay a buyer of a finalized dutch auction back what they paid above the
+/// clearing price, closing the receipt.
+pub fn process_claim_rebate(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let signer_info = next_account_info(account_info_iter)?;
+    let collection_mint = next_account_info(account_info_iter)?;
+    let treasury_info = next_account_info(account_info_iter)?;
+    let receipt_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+
+    assert_signer(signer_info)?;
+    assert_owned_by(treasury_info, program_id)?;
+    assert_owned_by(receipt_info, program_id)?;
+    assert_treasury(program_id, collection_mint, treasury_info)?;
+    assert_receipt(program_id, mint_info, receipt_info)?;
+
+    let receipt_data = ReceiptData::from_account_info(receipt_info)?;
+    if receipt_data.buyer != *signer_info.key {
+        return Err(AppError::InvalidOwner.into());
+    }
+    assert_eq_pubkey(collection_mint, &receipt_data.collection)?;
+
+    let mut treasury_data = TreasuryData::from_account_info(treasury_info)?;
+    if treasury_data.status != SaleStatus::Finalized || !treasury_data.rebate {
+        return Err(AppError::RebateUnavailable.into());
+    }
+
+    let amount = receipt_data
+        .price
+        .saturating_sub(treasury_data.clearing_price);
+    transfer_lamports(treasury_info, signer_info, amount)?;
+    treasury_data.total = treasury_data
+        .total
+        .checked_sub(amount)
+        .ok_or(AppError::CheckedCalculateFailed)?;
+    treasury_data.serialize(&mut *treasury_info.try_borrow_mut_data()?)?;
+    close_account(receipt_info, signer_info)?;
+
+    AppEvent::RebateClaimed {
+        collection: receipt_data.collection,
+        mint: receipt_data.mint,
+        buyer: receipt_data.buyer,
+        amount,
+    }
+    .emit();
+
+    Ok(())
+}
@@ -0,0 +1,107 @@
+use borsh::BorshSerialize;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+use crate::{error::AppError, event::AppEvent, state::*, utils::*};
+
+/// Pay the rewards a stake accrued since its last claim out of the
+/// `mint_vault` of the reward token, capped by what is left in the pool.
+/// The claim only advances by the seconds paid for, so rewards above the
+/// balance can be claimed once the pool is funded again. Updates both
+/// records, the caller writes them back.
+pub fn pay_rewards<'a>(
+    program_id: &Pubkey,
+    pool_data: &mut PoolData,
+    stake_data: &mut StakeData,
+    reward_mint: &AccountInfo<'a>,
+    mint_vault: &AccountInfo<'a>,
+    mint_auth: &AccountInfo<'a>,
+    reward_account: &AccountInfo<'a>,
+    token_program_info: &AccountInfo<'a>,
+) -> Result<u64, ProgramError> {
+    assert_token_program(token_program_info)?;
+    assert_owned_by(reward_mint, token_program_info.key)?;
+    assert_eq_pubkey(reward_mint, &pool_data.reward_mint)?;
+    assert_mint_vault(program_id, reward_mint, mint_vault)?;
+    let auth_bump = assert_mint_authority(program_id, reward_mint, mint_auth)?;
+
+    let (seconds, amount) = pool_data.payable(stake_data.last_claim_ts, now_timestamp());
+    stake_data.last_claim_ts += seconds;
+    if amount == 0 {
+        return Ok(0);
+    }
+
+    let decimals = unpack_mint(reward_mint)?.decimals;
+    let authority_seed = [
+        program_id.as_ref(),
+        reward_mint.key.as_ref(),
+        "mint_auth".as_bytes(),
+        &[auth_bump],
+    ];
+    spl_token_transfer(
+        token_program_info.clone(),
+        mint_vault.clone(),
+        reward_mint.clone(),
+        reward_account.clone(),
+        mint_auth.clone(),
+        amount,
+        decimals,
+        &authority_seed,
+    )?;
+    pool_data.balance -= amount;
+
+    Ok(amount)
+}
+
+/// Claim the rewards of a staked item, keeping it staked.
+pub fn process_claim_rewards(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let signer_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let stake_info = next_account_info(account_info_iter)?;
+    let reward_mint = next_account_info(account_info_iter)?;
+    let mint_vault = next_account_info(account_info_iter)?;
+    let mint_auth = next_account_info(account_info_iter)?;
+    let reward_account = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+
+    assert_signer(signer_info)?;
+    assert_owned_by(pool_info, program_id)?;
+    assert_owned_by(stake_info, program_id)?;
+    assert_stake(program_id, mint_info, stake_info)?;
+
+    let mut stake_data = StakeData::from_account_info(stake_info)?;
+    if stake_data.owner != *signer_info.key {
+        return Err(AppError::InvalidOwner.into());
+    }
+    assert_pool(program_id, &stake_data.collection, pool_info)?;
+    let mut pool_data = PoolData::from_account_info(pool_info)?;
+
+    let amount = pay_rewards(
+        program_id,
+        &mut pool_data,
+        &mut stake_data,
+        reward_mint,
+        mint_vault,
+        mint_auth,
+        reward_account,
+        token_program_info,
+    )?;
+    pool_data.serialize(&mut *pool_info.try_borrow_mut_data()?)?;
+    stake_data.serialize(&mut *stake_info.try_borrow_mut_data()?)?;
+
+    AppEvent::RewardsClaimed {
+        collection: stake_data.collection,
+        mint: stake_data.mint,
+        owner: stake_data.owner,
+        amount,
+    }
+    .emit();
+
+    Ok(())
+}
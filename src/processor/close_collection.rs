@@ -0,0 +1,66 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    pubkey::Pubkey,
+};
+
+use crate::{error::AppError, event::AppEvent, state::*, utils::*};
+
+/// Close a collection's sale record once its sale has ended, that is once
+/// its promotion has been closed, and nothing else depends on it: no
+/// staking pool, listing or unsettled auction, and no treasury still open or
+/// holding proceeds. An emptied treasury is closed along with it.
+pub fn process_close_collection(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let signer_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    let collection_mint = next_account_info(account_info_iter)?;
+    let collection_info = next_account_info(account_info_iter)?;
+    let promotion_info = next_account_info(account_info_iter)?;
+    let treasury_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let recipient_info = next_account_info(account_info_iter)?;
+
+    assert_signer(signer_info)?;
+    assert_config(program_id, config_info)?;
+    assert_owned_by(config_info, program_id)?;
+    assert_owned_by(collection_info, program_id)?;
+    assert_collection(program_id, collection_mint, collection_info)?;
+    let path = &[program_id.as_ref(), collection_info.key.as_ref()];
+    assert_derivation(program_id, promotion_info, path)?;
+    assert_treasury(program_id, collection_mint, treasury_info)?;
+    assert_pool(program_id, collection_mint.key, pool_info)?;
+
+    //check authority
+    let config_data = ConfigureData::from_account_info(config_info)?;
+    let collection_data = CollectionData::from_account_info(collection_info)?;
+    if config_data.authority != *signer_info.key && collection_data.admin != *signer_info.key {
+        return Err(AppError::InvalidAuthority.into());
+    }
+
+    //check sale state
+    if !promotion_info.data_is_empty() {
+        return Err(AppError::SaleActive.into());
+    }
+    if !pool_info.data_is_empty() || collection_data.listings > 0 || collection_data.auctions > 0 {
+        return Err(AppError::CollectionInUse.into());
+    }
+    if !treasury_info.data_is_empty() {
+        assert_owned_by(treasury_info, program_id)?;
+        let treasury_data = TreasuryData::from_account_info(treasury_info)?;
+        if treasury_data.status == SaleStatus::Open || treasury_data.total > 0 {
+            return Err(AppError::CollectionInUse.into());
+        }
+        close_account(treasury_info, recipient_info)?;
+    }
+
+    close_account(collection_info, recipient_info)?;
+
+    AppEvent::CollectionClosed {
+        collection: collection_data.collection_mint,
+        recipient: *recipient_info.key,
+    }
+    .emit();
+
+    Ok(())
+}
@@ -0,0 +1,79 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    pubkey::Pubkey,
+};
+
+use crate::{error::AppError, event::AppEvent, state::*, utils::*};
+
+/// Close the staking pool of a collection once nothing is staked, sending
+/// its unspent balance from the `mint_vault` of the reward token to
+/// `destination`. The pool rent goes back to the signer.
+pub fn process_close_pool(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let signer_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    let collection_mint = next_account_info(account_info_iter)?;
+    let collection_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let reward_mint = next_account_info(account_info_iter)?;
+    let mint_vault = next_account_info(account_info_iter)?;
+    let mint_auth = next_account_info(account_info_iter)?;
+    let destination = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+
+    assert_signer(signer_info)?;
+    assert_token_program(token_program_info)?;
+    assert_config(program_id, config_info)?;
+    assert_owned_by(config_info, program_id)?;
+    assert_owned_by(collection_info, program_id)?;
+    assert_collection(program_id, collection_mint, collection_info)?;
+    assert_owned_by(pool_info, program_id)?;
+    assert_pool(program_id, collection_mint.key, pool_info)?;
+
+    //check authority
+    let config_data = ConfigureData::from_account_info(config_info)?;
+    let collection_data = CollectionData::from_account_info(collection_info)?;
+    if config_data.authority != *signer_info.key && collection_data.admin != *signer_info.key {
+        return Err(AppError::InvalidAuthority.into());
+    }
+
+    let pool_data = PoolData::from_account_info(pool_info)?;
+    if pool_data.staked > 0 {
+        return Err(AppError::PoolHasStakes.into());
+    }
+
+    if pool_data.balance > 0 {
+        assert_owned_by(reward_mint, token_program_info.key)?;
+        assert_eq_pubkey(reward_mint, &pool_data.reward_mint)?;
+        assert_mint_vault(program_id, reward_mint, mint_vault)?;
+        let auth_bump = assert_mint_authority(program_id, reward_mint, mint_auth)?;
+        let authority_seed = [
+            program_id.as_ref(),
+            reward_mint.key.as_ref(),
+            "mint_auth".as_bytes(),
+            &[auth_bump],
+        ];
+        let decimals = unpack_mint(reward_mint)?.decimals;
+        spl_token_transfer(
+            token_program_info.clone(),
+            mint_vault.clone(),
+            reward_mint.clone(),
+            destination.clone(),
+            mint_auth.clone(),
+            pool_data.balance,
+            decimals,
+            &authority_seed,
+        )?;
+    }
+    close_account(pool_info, signer_info)?;
+
+    AppEvent::PoolClosed {
+        collection: pool_data.collection,
+        authority: *signer_info.key,
+        withdrawn: pool_data.balance,
+    }
+    .emit();
+
+    Ok(())
+}
@@ -0,0 +1,62 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    pubkey::Pubkey,
+};
+
+use crate::{error::AppError, event::AppEvent, state::*, utils::*};
+
+/// Close a collection's promotion, which ends its sale. The config authority
+/// can close it at any time, the collection admin only once sold out. An
+/// escrowed sale must be finalized or cancelled first.
+pub fn process_close_promotion(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let signer_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    let collection_mint = next_account_info(account_info_iter)?;
+    let collection_info = next_account_info(account_info_iter)?;
+    let promotion_info = next_account_info(account_info_iter)?;
+    let treasury_info = next_account_info(account_info_iter)?;
+    let recipient_info = next_account_info(account_info_iter)?;
+
+    assert_signer(signer_info)?;
+    assert_config(program_id, config_info)?;
+    assert_owned_by(config_info, program_id)?;
+    assert_owned_by(collection_info, program_id)?;
+    assert_owned_by(promotion_info, program_id)?;
+    assert_collection(program_id, collection_mint, collection_info)?;
+    let path = &[program_id.as_ref(), collection_info.key.as_ref()];
+    assert_derivation(program_id, promotion_info, path)?;
+    assert_treasury(program_id, collection_mint, treasury_info)?;
+
+    //check authority
+    let config_data = ConfigureData::from_account_info(config_info)?;
+    let collection_data = CollectionData::from_account_info(collection_info)?;
+    if config_data.authority != *signer_info.key {
+        if collection_data.admin != *signer_info.key {
+            return Err(AppError::InvalidAuthority.into());
+        }
+        if collection_data.sale_left() > 0 {
+            return Err(AppError::SaleActive.into());
+        }
+    }
+
+    //finalizing reads the charge account from the promotion
+    let pro_data = PromotionData::from_account_info(promotion_info)?;
+    if pro_data.escrow
+        && !treasury_info.data_is_empty()
+        && TreasuryData::from_account_info(treasury_info)?.status == SaleStatus::Open
+    {
+        return Err(AppError::SaleActive.into());
+    }
+
+    close_account(promotion_info, recipient_info)?;
+
+    AppEvent::PromotionClosed {
+        collection: collection_data.collection_mint,
+        recipient: *recipient_info.key,
+    }
+    .emit();
+
+    Ok(())
+}
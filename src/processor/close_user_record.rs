@@ -0,0 +1,40 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    pubkey::Pubkey,
+};
+
+use crate::{error::AppError, event::AppEvent, state::*, utils::*};
+
+/// Close a buyer's mint record, returning its rent to the buyer who paid it.
+/// The record carries the wallet's mint history across drops, so only the
+/// config authority may clear it.
+pub fn process_close_user_record(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let signer_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    let user = next_account_info(account_info_iter)?;
+    let user_info = next_account_info(account_info_iter)?;
+
+    assert_signer(signer_info)?;
+    assert_config(program_id, config_info)?;
+    assert_owned_by(config_info, program_id)?;
+    assert_owned_by(user_info, program_id)?;
+    assert_user_info(program_id, user.key, user_info)?;
+
+    //check authority
+    let config_data = ConfigureData::from_account_info(config_info)?;
+    if config_data.authority != *signer_info.key {
+        return Err(AppError::InvalidAuthority.into());
+    }
+
+    close_account(user_info, user)?;
+
+    AppEvent::UserRecordClosed {
+        user: *user.key,
+        recipient: *user.key,
+    }
+    .emit();
+
+    Ok(())
+}
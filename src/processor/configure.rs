@@ -2,13 +2,10 @@ use borsh::BorshSerialize;
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
     entrypoint::ProgramResult,
-    msg,
-    sysvar,
-    program_error::ProgramError,
     pubkey::Pubkey,
 };
 
-use crate::{ferror, state::*, utils::*};
+use crate::{error::AppError, event::AppEvent, state::*, utils::*};
 
 pub fn process_configure(
     program_id: &Pubkey,
@@ -21,10 +18,10 @@ pub fn process_configure(
     let rent_info = next_account_info(account_info_iter)?;
     let system_info = next_account_info(account_info_iter)?;
 
-    assert_eq_pubkey(&rent_info, &sysvar::rent::id())?;
-    assert_eq_pubkey(&system_info, &solana_program::system_program::id())?;
-    assert_signer(&signer_info)?;
-    let bump = assert_config(&program_id, &config_info)?;
+    assert_rent_sysvar(rent_info)?;
+    assert_system_program(system_info)?;
+    assert_signer(signer_info)?;
+    let bump = assert_config(program_id, config_info)?;
 
     let mut is_created = true;
     if config_info.data_is_empty() {
@@ -35,27 +32,44 @@ pub fn process_configure(
             system_info,
             signer_info,
             ConfigureData::LEN,
-            &[
-                program_id.as_ref(),
-                "config".as_bytes(),
-                &[bump],
-            ],
+            &[program_id.as_ref(), "config".as_bytes(), &[bump]],
         )?;
         is_created = false;
     }
 
-    let mut config_data = ConfigureData::from_account_info(config_info)?;
+    let mut config_data = ConfigureData::from_any_layout(config_info)?;
 
     if is_created {
         if config_data.authority != *signer_info.key {
-            return ferror!("invalid authority");
+            return Err(AppError::InvalidAuthority.into());
+        }
+        assert_owned_by(config_info, program_id)?;
+        // configs written before marketplace fees grow to the current layout
+        if config_info.data_len() != ConfigureData::LEN {
+            resize_account(
+                config_info,
+                rent_info,
+                system_info,
+                signer_info,
+                ConfigureData::LEN,
+            )?;
         }
-        assert_owned_by(config_info, &program_id)?;
     }
 
-
+    if args.platform_fee > 10000 {
+        return Err(AppError::InvalidFee.into());
+    }
     config_data.authority = args.authority;
+    config_data.platform_fee = args.platform_fee;
+    config_data.fee_recipient = args.fee_recipient;
     config_data.serialize(&mut &mut config_info.data.borrow_mut()[..])?;
 
+    AppEvent::Configured {
+        authority: config_data.authority,
+        platform_fee: config_data.platform_fee,
+        fee_recipient: config_data.fee_recipient,
+    }
+    .emit();
+
     Ok(())
 }
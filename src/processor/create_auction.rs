@@ -0,0 +1,94 @@
+use borsh::BorshSerialize;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    pubkey::Pubkey,
+};
+
+use crate::{error::AppError, event::AppEvent, state::*, utils::*};
+
+/// Open an english auction for a single item of a collection, minted to the
+/// winner on settlement.
+pub fn process_create_auction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: CreateAuctionArgs,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let signer_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    let collection_mint = next_account_info(account_info_iter)?;
+    let collection_info = next_account_info(account_info_iter)?;
+    let auction_info = next_account_info(account_info_iter)?;
+    let rent_info = next_account_info(account_info_iter)?;
+    let system_info = next_account_info(account_info_iter)?;
+
+    assert_rent_sysvar(rent_info)?;
+    assert_system_program(system_info)?;
+    assert_signer(signer_info)?;
+    assert_config(program_id, config_info)?;
+    assert_owned_by(config_info, program_id)?;
+    assert_owned_by(collection_info, program_id)?;
+    assert_collection(program_id, collection_mint, collection_info)?;
+
+    //check authority
+    let config_data = ConfigureData::from_account_info(config_info)?;
+    let mut collection_data = CollectionData::from_account_info(collection_info)?;
+    if config_data.authority != *signer_info.key && collection_data.admin != *signer_info.key {
+        return Err(AppError::InvalidAuthority.into());
+    }
+
+    if args.start_ts >= args.end_ts || args.end_ts <= now_timestamp() || args.min_increment == 0 {
+        return Err(AppError::InvalidAuction.into());
+    }
+    if !auction_info.data_is_empty() {
+        return Err(AppError::AlreadyInitialized.into());
+    }
+
+    let bump = assert_auction(program_id, collection_mint, args.id, auction_info)?;
+    let auction_seeds = [
+        program_id.as_ref(),
+        collection_mint.key.as_ref(),
+        &args.id.to_le_bytes(),
+        "auction".as_bytes(),
+        &[bump],
+    ];
+    create_or_allocate_account_raw(
+        *program_id,
+        auction_info,
+        rent_info,
+        system_info,
+        signer_info,
+        AuctionData::LEN,
+        &auction_seeds,
+    )?;
+
+    let auction_data = AuctionData {
+        collection: *collection_mint.key,
+        id: args.id,
+        name: args.name,
+        uri: args.uri,
+        reserve_price: args.reserve_price,
+        start_ts: args.start_ts,
+        end_ts: args.end_ts,
+        extension: args.extension,
+        min_increment: args.min_increment,
+        char_addr: args.char_addr,
+        highest_bidder: None,
+        highest_bid: 0,
+        creator: *signer_info.key,
+    };
+    auction_data.serialize(&mut *auction_info.try_borrow_mut_data()?)?;
+    collection_data.auctions += 1;
+    collection_data.serialize(&mut *collection_info.try_borrow_mut_data()?)?;
+
+    AppEvent::AuctionCreated {
+        collection: auction_data.collection,
+        auction: *auction_info.key,
+        reserve_price: auction_data.reserve_price,
+        end_ts: auction_data.end_ts,
+    }
+    .emit();
+
+    Ok(())
+}
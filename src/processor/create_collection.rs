@@ -3,7 +3,7 @@ use mpl_token_metadata::{
         ApproveCollectionAuthority, CreateMasterEditionV3, CreateMasterEditionV3InstructionArgs,
         CreateMetadataAccountV3, CreateMetadataAccountV3InstructionArgs,
     },
-    types::DataV2,
+    types::{CollectionDetails, DataV2},
 };
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
@@ -11,10 +11,9 @@ use solana_program::{
     msg,
     program::invoke,
     pubkey::Pubkey,
-    sysvar,
 };
 
-use crate::{state::*, utils::*};
+use crate::{event::AppEvent, state::*, utils::*};
 
 pub fn process_create_collection(
     program_id: &Pubkey,
@@ -35,14 +34,15 @@ pub fn process_create_collection(
     let system_info = next_account_info(account_info_iter)?;
 
     assert_eq_pubkey(
-        &metadata_program_info,
+        metadata_program_info,
         &mpl_token_metadata::programs::MPL_TOKEN_METADATA_ID,
     )?;
-    assert_eq_pubkey(&token_program_info, &spl_token::id())?;
-    assert_eq_pubkey(&rent_info, &sysvar::rent::id())?;
-    assert_eq_pubkey(&system_info, &solana_program::system_program::id())?;
-    assert_signer(&signer_info)?;
-    assert_pda_creator(&program_id, mint_info, pda_creator_info)?;
+    assert_token_program(token_program_info)?;
+    assert_rent_sysvar(rent_info)?;
+    assert_system_program(system_info)?;
+    assert_signer(signer_info)?;
+    assert_owned_by(mint_info, token_program_info.key)?;
+    assert_pda_creator(program_id, mint_info, pda_creator_info)?;
 
     let creators = vec![mpl_token_metadata::types::Creator {
         address: *signer_info.key,
@@ -70,9 +70,9 @@ pub fn process_create_collection(
         uses: None,
     };
     let cmv3_args = CreateMetadataAccountV3InstructionArgs {
-        data: data,
+        data,
         is_mutable: true,
-        collection_details: None,
+        collection_details: Some(CollectionDetails::V1 { size: 0 }),
     };
     invoke(
         &cmv3.instruction(cmv3_args),
@@ -150,5 +150,11 @@ pub fn process_create_collection(
     };
     invoke(&aca.instruction(), &approve_collection_accounts)?;
 
+    AppEvent::CollectionCreated {
+        collection_mint: *mint_info.key,
+        creator: *signer_info.key,
+    }
+    .emit();
+
     Ok(())
 }
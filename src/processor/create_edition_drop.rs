@@ -0,0 +1,144 @@
+use borsh::BorshSerialize;
+use mpl_token_metadata::types::PrintSupply;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    pubkey::Pubkey,
+};
+
+use crate::{error::AppError, event::AppEvent, processor::*, state::*, utils::*};
+
+/// Create the master edition of an edition drop. The master token must sit in
+/// a token account owned by `pda_creator` so the program can sign prints.
+pub fn process_create_edition_drop(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: CreateEditionDropArgs,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let signer_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    let collection_mint = next_account_info(account_info_iter)?;
+    let collection_info = next_account_info(account_info_iter)?;
+    let pda_creator_info = next_account_info(account_info_iter)?;
+    let master_mint = next_account_info(account_info_iter)?;
+    let master_token_account = next_account_info(account_info_iter)?;
+    let metadata_info = next_account_info(account_info_iter)?;
+    let edition_info = next_account_info(account_info_iter)?;
+    let collection_metadata = next_account_info(account_info_iter)?;
+    let collection_master_edition_account = next_account_info(account_info_iter)?;
+    let collection_authority_record = next_account_info(account_info_iter)?;
+    let edition_drop_info = next_account_info(account_info_iter)?;
+    let metadata_program_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+    let rent_info = next_account_info(account_info_iter)?;
+    let system_info = next_account_info(account_info_iter)?;
+
+    assert_eq_pubkey(
+        metadata_program_info,
+        &mpl_token_metadata::programs::MPL_TOKEN_METADATA_ID,
+    )?;
+    assert_rent_sysvar(rent_info)?;
+    assert_system_program(system_info)?;
+    assert_signer(signer_info)?;
+    assert_config(program_id, config_info)?;
+    assert_owned_by(config_info, program_id)?;
+    assert_owned_by(collection_info, program_id)?;
+    assert_collection(program_id, collection_mint, collection_info)?;
+    let pda_bump = assert_pda_creator(program_id, collection_mint, pda_creator_info)?;
+    let drop_bump = assert_edition_drop(program_id, master_mint, edition_drop_info)?;
+
+    //check authority
+    let config_data = ConfigureData::from_account_info(config_info)?;
+    let collection_data = CollectionData::from_account_info(collection_info)?;
+    if config_data.authority != *signer_info.key && collection_data.admin != *signer_info.key {
+        return Err(AppError::InvalidAuthority.into());
+    }
+    if collection_data.programmable {
+        return Err(AppError::EditionDropRequiresClassic.into());
+    }
+    assert_eq_pubkey(token_program_info, &collection_data.token_program)?;
+    assert_owned_by(master_mint, token_program_info.key)?;
+
+    let master_token = unpack_token_account(master_token_account)?;
+    if master_token.mint != *master_mint.key
+        || master_token.owner != *pda_creator_info.key
+        || master_token.amount != 1
+    {
+        return Err(AppError::InvalidMasterToken.into());
+    }
+
+    if !edition_drop_info.data_is_empty() {
+        return Err(AppError::AlreadyInitialized.into());
+    }
+    create_or_allocate_account_raw(
+        *program_id,
+        edition_drop_info,
+        rent_info,
+        system_info,
+        signer_info,
+        EditionDropData::LEN,
+        &[
+            program_id.as_ref(),
+            master_mint.key.as_ref(),
+            "edition_drop".as_bytes(),
+            &[drop_bump],
+        ],
+    )?;
+
+    let pda_seed = [
+        program_id.as_ref(),
+        collection_mint.key.as_ref(),
+        "pda_creator".as_bytes(),
+        &[pda_bump],
+    ];
+    let print_supply = match args.max_supply {
+        Some(supply) => PrintSupply::Limited(supply),
+        None => PrintSupply::Unlimited,
+    };
+    let master_data = CollectionData {
+        name: args.name,
+        symbol: args.symbol,
+        uri: args.uri,
+        ..collection_data
+    };
+
+    msg!("create master edition");
+    let nft_accounts = MintNftAccounts {
+        payer: signer_info,
+        owner: pda_creator_info,
+        pda_creator: pda_creator_info,
+        mint: master_mint,
+        token_account: master_token_account,
+        metadata: metadata_info,
+        edition: edition_info,
+        collection_mint,
+        collection_metadata,
+        collection_master_edition: collection_master_edition_account,
+        collection_authority_record,
+        metadata_program: metadata_program_info,
+        token_program: token_program_info,
+        rent: rent_info,
+        system: system_info,
+        programmable: None,
+    };
+    create_collection_item(&nft_accounts, &master_data, print_supply, &pda_seed)?;
+
+    let drop_data = EditionDropData {
+        collection: *collection_mint.key,
+        master_mint: *master_mint.key,
+        max_supply: args.max_supply,
+        minted: 0,
+    };
+    drop_data.serialize(&mut &mut edition_drop_info.data.borrow_mut()[..])?;
+
+    AppEvent::EditionDropCreated {
+        collection: drop_data.collection,
+        master_mint: drop_data.master_mint,
+        max_supply: drop_data.max_supply,
+    }
+    .emit();
+
+    Ok(())
+}
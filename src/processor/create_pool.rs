@@ -0,0 +1,86 @@
+use borsh::BorshSerialize;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    pubkey::Pubkey,
+};
+
+use crate::{error::AppError, event::AppEvent, state::*, utils::*};
+
+/// Open the staking pool of a collection, rewarding stakers with a token
+/// created by `CreateToken`.
+pub fn process_create_pool(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: CreatePoolArgs,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let signer_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    let collection_mint = next_account_info(account_info_iter)?;
+    let collection_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let reward_mint = next_account_info(account_info_iter)?;
+    let token_info = next_account_info(account_info_iter)?;
+    let rent_info = next_account_info(account_info_iter)?;
+    let system_info = next_account_info(account_info_iter)?;
+
+    assert_rent_sysvar(rent_info)?;
+    assert_system_program(system_info)?;
+    assert_signer(signer_info)?;
+    assert_config(program_id, config_info)?;
+    assert_owned_by(config_info, program_id)?;
+    assert_owned_by(collection_info, program_id)?;
+    assert_collection(program_id, collection_mint, collection_info)?;
+    assert_owned_by(token_info, program_id)?;
+    assert_token_info(program_id, reward_mint.key, token_info)?;
+
+    //check authority
+    let config_data = ConfigureData::from_account_info(config_info)?;
+    let collection_data = CollectionData::from_account_info(collection_info)?;
+    if config_data.authority != *signer_info.key && collection_data.admin != *signer_info.key {
+        return Err(AppError::InvalidAuthority.into());
+    }
+
+    if args.reward_rate == 0 {
+        return Err(AppError::InvalidRewardRate.into());
+    }
+    if !pool_info.data_is_empty() {
+        return Err(AppError::AlreadyInitialized.into());
+    }
+
+    let bump = assert_pool(program_id, collection_mint.key, pool_info)?;
+    let pool_seeds = [
+        program_id.as_ref(),
+        collection_mint.key.as_ref(),
+        "pool".as_bytes(),
+        &[bump],
+    ];
+    create_or_allocate_account_raw(
+        *program_id,
+        pool_info,
+        rent_info,
+        system_info,
+        signer_info,
+        PoolData::LEN,
+        &pool_seeds,
+    )?;
+
+    let pool_data = PoolData {
+        collection: *collection_mint.key,
+        reward_mint: *reward_mint.key,
+        reward_rate: args.reward_rate,
+        staked: 0,
+        balance: 0,
+    };
+    pool_data.serialize(&mut *pool_info.try_borrow_mut_data()?)?;
+
+    AppEvent::PoolCreated {
+        collection: pool_data.collection,
+        reward_mint: pool_data.reward_mint,
+        reward_rate: pool_data.reward_rate,
+    }
+    .emit();
+
+    Ok(())
+}
@@ -0,0 +1,97 @@
+use borsh::BorshSerialize;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    pubkey::Pubkey,
+};
+
+use crate::{error::AppError, event::AppEvent, state::*, utils::*};
+
+/// Create a redemption recipe: the items to burn, by collection and
+/// quantity, and what a redemption gives back. The collection record of every
+/// input follows the fixed accounts, in order; `Redeem` only burns classic
+/// spl-token items.
+pub fn process_create_recipe(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: CreateRecipeArgs,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let signer_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    let recipe_info = next_account_info(account_info_iter)?;
+    let rent_info = next_account_info(account_info_iter)?;
+    let system_info = next_account_info(account_info_iter)?;
+
+    assert_rent_sysvar(rent_info)?;
+    assert_system_program(system_info)?;
+    assert_signer(signer_info)?;
+    assert_config(program_id, config_info)?;
+    assert_owned_by(config_info, program_id)?;
+
+    //check authority
+    let config_data = ConfigureData::from_account_info(config_info)?;
+    if config_data.authority != *signer_info.key {
+        return Err(AppError::InvalidAuthority.into());
+    }
+
+    let recipe_data = RecipeData {
+        id: args.id,
+        inputs: args.inputs,
+        output: args.output,
+        redeemed: 0,
+    };
+    let burns = recipe_data.burns();
+    if burns == 0
+        || burns > MAX_RECIPE_INPUTS
+        || recipe_data.inputs.iter().any(|input| input.quantity == 0)
+    {
+        return Err(AppError::InvalidRecipe.into());
+    }
+    for input in recipe_data.inputs.iter() {
+        let collection_info = next_account_info(account_info_iter)?;
+        assert_owned_by(collection_info, program_id)?;
+        let path = &[
+            program_id.as_ref(),
+            input.collection.as_ref(),
+            "collection".as_bytes(),
+        ];
+        assert_derivation(program_id, collection_info, path)?;
+        let collection_data = CollectionData::from_any_layout(collection_info)?;
+        if collection_data.programmable {
+            return Err(AppError::ProgrammableUnsupported.into());
+        }
+        if collection_data.token_program != spl_token::id() {
+            return Err(AppError::InvalidTokenProgram.into());
+        }
+    }
+    if !recipe_info.data_is_empty() {
+        return Err(AppError::AlreadyInitialized.into());
+    }
+
+    let bump = assert_recipe(program_id, args.id, recipe_info)?;
+    let recipe_seeds = [
+        program_id.as_ref(),
+        &args.id.to_le_bytes(),
+        "recipe".as_bytes(),
+        &[bump],
+    ];
+    create_or_allocate_account_raw(
+        *program_id,
+        recipe_info,
+        rent_info,
+        system_info,
+        signer_info,
+        RecipeData::LEN,
+        &recipe_seeds,
+    )?;
+    recipe_data.serialize(&mut &mut recipe_info.data.borrow_mut()[..])?;
+
+    AppEvent::RecipeCreated {
+        recipe: *recipe_info.key,
+        id: recipe_data.id,
+    }
+    .emit();
+
+    Ok(())
+}
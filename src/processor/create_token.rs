@@ -1,21 +1,17 @@
 use borsh::BorshSerialize;
 use mpl_token_metadata::{
-    instructions::{
-        CreateMetadataAccountV3, CreateMetadataAccountV3InstructionArgs,
-    },
+    instructions::{CreateMetadataAccountV3, CreateMetadataAccountV3InstructionArgs},
     types::DataV2,
 };
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
     entrypoint::ProgramResult,
     msg,
-    program::invoke,
-    program_error::ProgramError,
+    program::invoke_signed,
     pubkey::Pubkey,
-    sysvar,
 };
 
-use crate::{ferror, state::*, utils::*};
+use crate::{error::AppError, event::AppEvent, state::*, utils::*};
 
 pub fn process_create_token(
     program_id: &Pubkey,
@@ -36,16 +32,16 @@ pub fn process_create_token(
     let system_info = next_account_info(account_info_iter)?;
 
     assert_eq_pubkey(
-        &metadata_program_info,
+        metadata_program_info,
         &mpl_token_metadata::programs::MPL_TOKEN_METADATA_ID,
     )?;
-    assert_eq_pubkey(&rent_info, &sysvar::rent::id())?;
-    assert_eq_pubkey(&token_program_info, &spl_token::id())?;
-    assert_eq_pubkey(&system_info, &solana_program::system_program::id())?;
-    assert_signer(&signer_info)?;
-    assert_token_info(&program_id, &mint.key, token_info)?;
+    assert_rent_sysvar(rent_info)?;
+    assert_token_program(token_program_info)?;
+    assert_system_program(system_info)?;
+    assert_signer(signer_info)?;
+    assert_token_info(program_id, mint.key, token_info)?;
 
-    let bump = assert_token_info(program_id, &mint.key, token_info)?;
+    let bump = assert_token_info(program_id, mint.key, token_info)?;
     let mint_vault_bump = assert_mint_vault(program_id, mint, mint_vault)?;
     let auth_bump = assert_mint_authority(program_id, mint, mint_auth)?;
     let authority_seed = [
@@ -54,6 +50,10 @@ pub fn process_create_token(
         "mint_auth".as_bytes(),
         &[auth_bump],
     ];
+    if *token_program_info.key == spl_token::id() && !args.extensions.is_empty() {
+        return Err(AppError::ExtensionsRequireToken2022.into());
+    }
+
     //create mint
     let mut is_created = true;
     if token_info.data_is_empty() {
@@ -64,27 +64,34 @@ pub fn process_create_token(
             system_info,
             signer_info,
             TokenData::LEN,
-            &[program_id.as_ref(), "config".as_bytes(), &[bump]],
+            &[
+                program_id.as_ref(),
+                mint.key.as_ref(),
+                "token_info".as_bytes(),
+                &[bump],
+            ],
         )?;
         msg!("spl token create mint");
         spl_token_create_mint(
             token_program_info,
             signer_info,
             mint,
-            &mint_auth,
+            mint_auth,
             &[],
             &[],
             rent_info,
             args.decimals,
+            &args.extensions,
+            metadata_info.key,
         )?;
         //creat mint vault
         msg!("create mint vault");
         spl_token_create_account(
-            &token_program_info,
-            &signer_info,
-            &mint,
-            &mint_vault,
-            &mint_auth,
+            token_program_info,
+            signer_info,
+            mint,
+            mint_vault,
+            mint_auth,
             &[
                 program_id.as_ref(),
                 mint.key.as_ref(),
@@ -92,7 +99,7 @@ pub fn process_create_token(
                 &[mint_vault_bump],
             ],
             &authority_seed,
-            &rent_info,
+            rent_info,
         )?;
 
         //create token metadata
@@ -105,7 +112,7 @@ pub fn process_create_token(
         let cmv3 = CreateMetadataAccountV3 {
             metadata: *metadata_info.key,
             mint: *mint.key,
-            mint_authority: *signer_info.key,
+            mint_authority: *mint_auth.key,
             payer: *signer_info.key,
             update_authority: (*signer_info.key, true),
             system_program: *system_info.key,
@@ -121,37 +128,60 @@ pub fn process_create_token(
             uses: None,
         };
         let cmv3_args = CreateMetadataAccountV3InstructionArgs {
-            data: data,
+            data,
             is_mutable: true,
             collection_details: None,
         };
-        invoke(
+        invoke_signed(
             &cmv3.instruction(cmv3_args),
             &[
                 metadata_info.clone(),
                 mint.clone(),
+                mint_auth.clone(),
                 signer_info.clone(),
                 metadata_program_info.clone(),
                 token_program_info.clone(),
                 system_info.clone(),
                 rent_info.clone(),
             ],
+            &[&authority_seed],
         )?;
         is_created = false;
     }
 
-    let mut token_data = TokenData::from_account_info(token_info)?;
+    let mut token_data = TokenData::from_any_layout(token_info)?;
     if is_created {
         assert_eq_pubkey(signer_info, &token_data.creator)?;
+        assert_eq_pubkey(token_program_info, &token_data.token_program)?;
         //update metadata todo
+        // tokens written before layouts were versioned grow to the current one
+        if token_info.data_len() != TokenData::LEN {
+            resize_account(
+                token_info,
+                rent_info,
+                system_info,
+                signer_info,
+                TokenData::LEN,
+            )?;
+        }
     }
 
-    token_data.creator = signer_info.key.clone();
+    token_data.creator = *signer_info.key;
     token_data.decimals = args.decimals;
     token_data.name = args.name;
     token_data.symbol = args.symbol;
-    token_data.mint = mint.key.clone();
+    token_data.mint = *mint.key;
+    token_data.token_program = *token_program_info.key;
+    token_data.version = LAYOUT_VERSION;
     token_data.serialize(&mut &mut token_info.data.borrow_mut()[..])?;
 
+    AppEvent::TokenCreated {
+        mint: token_data.mint,
+        creator: token_data.creator,
+        decimals: token_data.decimals,
+        token_program: token_data.token_program,
+    }
+    .emit();
+
     Ok(())
 }
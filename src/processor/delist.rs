@@ -0,0 +1,75 @@
+use borsh::BorshSerialize;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    pubkey::Pubkey,
+};
+
+use crate::{error::AppError, event::AppEvent, state::*, utils::*};
+
+/// Take a listed item back out of its vault, closing the listing.
+pub fn process_delist(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let signer_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    let token_account = next_account_info(account_info_iter)?;
+    let listing_info = next_account_info(account_info_iter)?;
+    let vault_info = next_account_info(account_info_iter)?;
+    let collection_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+
+    assert_signer(signer_info)?;
+    assert_token_program(token_program_info)?;
+    assert_owned_by(listing_info, program_id)?;
+    assert_owned_by(collection_info, program_id)?;
+    let listing_bump = assert_listing(program_id, mint_info, listing_info)?;
+    assert_listing_vault(program_id, mint_info, vault_info)?;
+
+    let listing_data = ListingData::from_account_info(listing_info)?;
+    if listing_data.seller != *signer_info.key {
+        return Err(AppError::InvalidOwner.into());
+    }
+    let path = &[
+        program_id.as_ref(),
+        listing_data.collection.as_ref(),
+        "collection".as_bytes(),
+    ];
+    assert_derivation(program_id, collection_info, path)?;
+    let mut collection_data = CollectionData::from_account_info(collection_info)?;
+
+    let listing_seeds = [
+        program_id.as_ref(),
+        mint_info.key.as_ref(),
+        "listing".as_bytes(),
+        &[listing_bump],
+    ];
+    spl_token_transfer(
+        token_program_info.clone(),
+        vault_info.clone(),
+        mint_info.clone(),
+        token_account.clone(),
+        listing_info.clone(),
+        1,
+        0,
+        &listing_seeds,
+    )?;
+    spl_token_close_account(
+        token_program_info,
+        vault_info,
+        signer_info,
+        listing_info,
+        &listing_seeds,
+    )?;
+    close_account(listing_info, signer_info)?;
+    collection_data.listings -= 1;
+    collection_data.serialize(&mut *collection_info.try_borrow_mut_data()?)?;
+
+    AppEvent::Delisted {
+        collection: listing_data.collection,
+        mint: listing_data.mint,
+        seller: listing_data.seller,
+    }
+    .emit();
+
+    Ok(())
+}
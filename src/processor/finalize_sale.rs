@@ -0,0 +1,74 @@
+use borsh::BorshSerialize;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    pubkey::Pubkey,
+};
+
+use crate::{error::AppError, event::AppEvent, state::*, utils::*};
+
+/// Close an escrowed sale and pay the held proceeds to the promotion's
+/// charge account. Dutch auctions with rebates only pay out the clearing
+/// price of every item, the rest stays in escrow for buyers to claim.
+pub fn process_finalize_sale(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let signer_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    let collection_mint = next_account_info(account_info_iter)?;
+    let collection_info = next_account_info(account_info_iter)?;
+    let promotion_info = next_account_info(account_info_iter)?;
+    let treasury_info = next_account_info(account_info_iter)?;
+    let charge_info = next_account_info(account_info_iter)?;
+
+    assert_signer(signer_info)?;
+    assert_config(program_id, config_info)?;
+    assert_owned_by(config_info, program_id)?;
+    assert_owned_by(collection_info, program_id)?;
+    assert_owned_by(promotion_info, program_id)?;
+    assert_owned_by(treasury_info, program_id)?;
+    assert_collection(program_id, collection_mint, collection_info)?;
+    let path = &[program_id.as_ref(), collection_info.key.as_ref()];
+    assert_derivation(program_id, promotion_info, path)?;
+    assert_treasury(program_id, collection_mint, treasury_info)?;
+
+    //check authority
+    let config_data = ConfigureData::from_account_info(config_info)?;
+    let collection_data = CollectionData::from_account_info(collection_info)?;
+    if config_data.authority != *signer_info.key && collection_data.admin != *signer_info.key {
+        return Err(AppError::InvalidAuthority.into());
+    }
+
+    let pro_data = PromotionData::from_account_info(promotion_info)?;
+    assert_charge_account(charge_info, &pro_data.char_addr)?;
+
+    let mut treasury_data = TreasuryData::from_account_info(treasury_info)?;
+    if treasury_data.status != SaleStatus::Open {
+        return Err(AppError::SaleEnded.into());
+    }
+
+    let rebate = pro_data.dutch.as_ref().is_some_and(|dutch| dutch.rebate);
+    let amount = if rebate {
+        treasury_data
+            .clearing_price
+            .checked_mul(treasury_data.sold)
+            .ok_or(AppError::CheckedCalculateFailed)?
+    } else {
+        treasury_data.total
+    };
+    transfer_lamports(treasury_info, charge_info, amount)?;
+    treasury_data.status = SaleStatus::Finalized;
+    treasury_data.total = treasury_data
+        .total
+        .checked_sub(amount)
+        .ok_or(AppError::CheckedCalculateFailed)?;
+    treasury_data.rebate = rebate;
+    treasury_data.serialize(&mut *treasury_info.try_borrow_mut_data()?)?;
+
+    AppEvent::SaleFinalized {
+        collection: collection_data.collection_mint,
+        amount,
+    }
+    .emit();
+
+    Ok(())
+}
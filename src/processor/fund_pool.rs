@@ -0,0 +1,66 @@
+use borsh::BorshSerialize;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    pubkey::Pubkey,
+};
+
+use crate::{error::AppError, event::AppEvent, state::*, utils::*};
+
+/// Move reward tokens into the `mint_vault` of the reward token and credit
+/// them to a pool. The pool is credited with what the vault actually
+/// received, so transfer fees of token-2022 mints are accounted for.
+pub fn process_fund_pool(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: FundPoolArgs,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let signer_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let reward_mint = next_account_info(account_info_iter)?;
+    let source_info = next_account_info(account_info_iter)?;
+    let mint_vault = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+
+    assert_signer(signer_info)?;
+    assert_token_program(token_program_info)?;
+    assert_owned_by(reward_mint, token_program_info.key)?;
+    assert_owned_by(pool_info, program_id)?;
+    assert_mint_vault(program_id, reward_mint, mint_vault)?;
+
+    let mut pool_data = PoolData::from_account_info(pool_info)?;
+    assert_pool(program_id, &pool_data.collection, pool_info)?;
+    assert_eq_pubkey(reward_mint, &pool_data.reward_mint)?;
+
+    let decimals = unpack_mint(reward_mint)?.decimals;
+    let before = unpack_token_account(mint_vault)?.amount;
+    spl_token_transfer_invoke(
+        token_program_info.clone(),
+        source_info.clone(),
+        reward_mint.clone(),
+        mint_vault.clone(),
+        signer_info.clone(),
+        args.amount,
+        decimals,
+    )?;
+    let received = unpack_token_account(mint_vault)?
+        .amount
+        .checked_sub(before)
+        .ok_or(AppError::CheckedCalculateFailed)?;
+
+    pool_data.balance = pool_data
+        .balance
+        .checked_add(received)
+        .ok_or(AppError::CheckedCalculateFailed)?;
+    pool_data.serialize(&mut *pool_info.try_borrow_mut_data()?)?;
+
+    AppEvent::PoolFunded {
+        collection: pool_data.collection,
+        funder: *signer_info.key,
+        amount: received,
+    }
+    .emit();
+
+    Ok(())
+}
@@ -0,0 +1,84 @@
+use mpl_token_metadata::{accounts::Metadata, instructions::RevokeCollectionAuthority};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program::invoke_signed,
+    pubkey::Pubkey,
+};
+
+use crate::{error::AppError, event::AppEvent, state::*, utils::*};
+
+/// Hand the collection back to the update authority of the collection NFT by
+/// revoking the collection authority of the pda creator. Once the sale and
+/// the reserve are used up and no auction is open the factory has nothing
+/// left to verify into the collection.
+pub fn process_graduate_collection(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let signer_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    let collection_mint = next_account_info(account_info_iter)?;
+    let collection_info = next_account_info(account_info_iter)?;
+    let pda_creator_info = next_account_info(account_info_iter)?;
+    let collection_metadata = next_account_info(account_info_iter)?;
+    let collection_authority_record = next_account_info(account_info_iter)?;
+    let metadata_program_info = next_account_info(account_info_iter)?;
+
+    assert_signer(signer_info)?;
+    assert_config(program_id, config_info)?;
+    assert_owned_by(config_info, program_id)?;
+    assert_owned_by(collection_info, program_id)?;
+    assert_collection(program_id, collection_mint, collection_info)?;
+    assert_eq_pubkey(metadata_program_info, &mpl_token_metadata::ID)?;
+    assert_eq_pubkey(
+        collection_metadata,
+        &Metadata::find_pda(collection_mint.key).0,
+    )?;
+    let pda_bump = assert_pda_creator(program_id, collection_mint, pda_creator_info)?;
+
+    //check authority
+    let config_data = ConfigureData::from_account_info(config_info)?;
+    let collection_data = CollectionData::from_account_info(collection_info)?;
+    if config_data.authority != *signer_info.key && collection_data.admin != *signer_info.key {
+        return Err(AppError::InvalidAuthority.into());
+    }
+
+    if collection_data.sale_left() > 0 || collection_data.reserve_left() > 0 {
+        return Err(AppError::SaleActive.into());
+    }
+    if collection_data.auctions > 0 {
+        return Err(AppError::CollectionInUse.into());
+    }
+
+    let pda_seed = [
+        program_id.as_ref(),
+        collection_mint.key.as_ref(),
+        "pda_creator".as_bytes(),
+        &[pda_bump],
+    ];
+    invoke_signed(
+        &RevokeCollectionAuthority {
+            collection_authority_record: *collection_authority_record.key,
+            delegate_authority: *pda_creator_info.key,
+            revoke_authority: *pda_creator_info.key,
+            metadata: *collection_metadata.key,
+            mint: *collection_mint.key,
+        }
+        .instruction(),
+        &[
+            collection_authority_record.clone(),
+            pda_creator_info.clone(),
+            collection_metadata.clone(),
+            collection_mint.clone(),
+            metadata_program_info.clone(),
+        ],
+        &[&pda_seed],
+    )?;
+
+    AppEvent::CollectionGraduated {
+        collection: *collection_info.key,
+        authority: *signer_info.key,
+    }
+    .emit();
+
+    Ok(())
+}
@@ -0,0 +1,124 @@
+use borsh::BorshSerialize;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    pubkey::Pubkey,
+};
+
+use crate::{error::AppError, event::AppEvent, state::*, utils::*};
+
+/// List an item of a factory collection for sale, moving it into a vault
+/// held by the listing. Listing an item that is already listed by the same
+/// seller only changes its price.
+pub fn process_list(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: ListArgs,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let signer_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    let token_account = next_account_info(account_info_iter)?;
+    let metadata_info = next_account_info(account_info_iter)?;
+    let collection_info = next_account_info(account_info_iter)?;
+    let listing_info = next_account_info(account_info_iter)?;
+    let vault_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+    let rent_info = next_account_info(account_info_iter)?;
+    let system_info = next_account_info(account_info_iter)?;
+
+    assert_signer(signer_info)?;
+    assert_token_program(token_program_info)?;
+    assert_rent_sysvar(rent_info)?;
+    assert_system_program(system_info)?;
+    assert_owned_by(mint_info, token_program_info.key)?;
+    let listing_bump = assert_listing(program_id, mint_info, listing_info)?;
+    let vault_bump = assert_listing_vault(program_id, mint_info, vault_info)?;
+
+    //update the price of an existing listing
+    if !listing_info.data_is_empty() {
+        assert_owned_by(listing_info, program_id)?;
+        let mut listing_data = ListingData::from_account_info(listing_info)?;
+        if listing_data.seller != *signer_info.key {
+            return Err(AppError::InvalidOwner.into());
+        }
+        listing_data.price = args.price;
+        listing_data.serialize(&mut *listing_info.try_borrow_mut_data()?)?;
+        AppEvent::Listed {
+            collection: listing_data.collection,
+            mint: listing_data.mint,
+            seller: listing_data.seller,
+            price: listing_data.price,
+        }
+        .emit();
+        return Ok(());
+    }
+
+    assert_collection_item(program_id, mint_info, metadata_info, collection_info)?;
+    let mut collection_data = CollectionData::from_account_info(collection_info)?;
+    if collection_data.programmable {
+        return Err(AppError::ProgrammableUnsupported.into());
+    }
+
+    let listing_seeds = [
+        program_id.as_ref(),
+        mint_info.key.as_ref(),
+        "listing".as_bytes(),
+        &[listing_bump],
+    ];
+    create_or_allocate_account_raw(
+        *program_id,
+        listing_info,
+        rent_info,
+        system_info,
+        signer_info,
+        ListingData::LEN,
+        &listing_seeds,
+    )?;
+
+    let vault_seeds = [
+        program_id.as_ref(),
+        mint_info.key.as_ref(),
+        "listing_vault".as_bytes(),
+        &[vault_bump],
+    ];
+    spl_token_create_account(
+        token_program_info,
+        signer_info,
+        mint_info,
+        vault_info,
+        listing_info,
+        &vault_seeds,
+        &[],
+        rent_info,
+    )?;
+    spl_token_transfer_invoke(
+        token_program_info.clone(),
+        token_account.clone(),
+        mint_info.clone(),
+        vault_info.clone(),
+        signer_info.clone(),
+        1,
+        0,
+    )?;
+
+    let listing_data = ListingData {
+        seller: *signer_info.key,
+        mint: *mint_info.key,
+        collection: collection_data.collection_mint,
+        price: args.price,
+    };
+    listing_data.serialize(&mut *listing_info.try_borrow_mut_data()?)?;
+    collection_data.listings += 1;
+    collection_data.serialize(&mut *collection_info.try_borrow_mut_data()?)?;
+
+    AppEvent::Listed {
+        collection: listing_data.collection,
+        mint: listing_data.mint,
+        seller: listing_data.seller,
+        price: listing_data.price,
+    }
+    .emit();
+
+    Ok(())
+}
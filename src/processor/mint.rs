@@ -1,10 +1,11 @@
-use crate::{ferror, state::*, utils::*};
+use crate::{error::AppError, event::AppEvent, state::*, utils::*};
 use borsh::BorshSerialize;
 use mpl_token_metadata::instructions::{
     CreateMasterEditionV3, CreateMasterEditionV3InstructionArgs, CreateMetadataAccountV3,
-    CreateMetadataAccountV3InstructionArgs, VerifyCollection,
+    CreateMetadataAccountV3InstructionArgs, CreateV1, CreateV1InstructionArgs, MintV1,
+    MintV1InstructionArgs, VerifyCollectionV1, VerifySizedCollectionItem,
 };
-use mpl_token_metadata::types::{Collection, Creator, DataV2};
+use mpl_token_metadata::types::{Collection, Creator, DataV2, PrintSupply, TokenStandard};
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
     entrypoint::ProgramResult,
@@ -15,7 +16,425 @@ use solana_program::{
     system_instruction, sysvar,
 };
 
+/// Extra accounts required to mint a programmable NFT.
+pub struct ProgrammableAccounts<'a, 'b> {
+    pub sysvar_instructions: &'b AccountInfo<'a>,
+    pub token_record: &'b AccountInfo<'a>,
+    pub spl_ata_program: &'b AccountInfo<'a>,
+    pub authorization_rules_program: &'b AccountInfo<'a>,
+    pub authorization_rules: &'b AccountInfo<'a>,
+}
+
+impl<'a, 'b> ProgrammableAccounts<'a, 'b> {
+    pub fn from_iter<I>(
+        account_info_iter: &mut I,
+        collection_data: &CollectionData,
+    ) -> Result<Self, ProgramError>
+    where
+        I: Iterator<Item = &'b AccountInfo<'a>>,
+    {
+        let accounts = ProgrammableAccounts {
+            sysvar_instructions: next_account_info(account_info_iter)?,
+            token_record: next_account_info(account_info_iter)?,
+            spl_ata_program: next_account_info(account_info_iter)?,
+            authorization_rules_program: next_account_info(account_info_iter)?,
+            authorization_rules: next_account_info(account_info_iter)?,
+        };
+        assert_eq_pubkey(accounts.sysvar_instructions, &sysvar::instructions::id())?;
+        assert_eq_pubkey(
+            accounts.spl_ata_program,
+            &spl_associated_token_account::id(),
+        )?;
+        assert_eq_pubkey(
+            accounts.authorization_rules_program,
+            &MPL_TOKEN_AUTH_RULES_ID,
+        )?;
+        if let Some(rule_set) = collection_data.rule_set {
+            assert_eq_pubkey(accounts.authorization_rules, &rule_set)?;
+        }
+        Ok(accounts)
+    }
+}
+
+/// Accounts needed to create one item of a collection, shared by every
+/// instruction that mints into a collection.
+pub struct MintNftAccounts<'a, 'b> {
+    /// pays for the new accounts and holds the mint authority of classic mints
+    pub payer: &'b AccountInfo<'a>,
+    /// owner of the token account receiving the item
+    pub owner: &'b AccountInfo<'a>,
+    pub pda_creator: &'b AccountInfo<'a>,
+    pub mint: &'b AccountInfo<'a>,
+    pub token_account: &'b AccountInfo<'a>,
+    pub metadata: &'b AccountInfo<'a>,
+    pub edition: &'b AccountInfo<'a>,
+    pub collection_mint: &'b AccountInfo<'a>,
+    pub collection_metadata: &'b AccountInfo<'a>,
+    pub collection_master_edition: &'b AccountInfo<'a>,
+    pub collection_authority_record: &'b AccountInfo<'a>,
+    pub metadata_program: &'b AccountInfo<'a>,
+    pub token_program: &'b AccountInfo<'a>,
+    pub rent: &'b AccountInfo<'a>,
+    pub system: &'b AccountInfo<'a>,
+    /// only set for collections minting programmable NFTs
+    pub programmable: Option<ProgrammableAccounts<'a, 'b>>,
+}
+
+/// Create metadata and master edition for a new item and verify it into the
+/// collection, signing as `pda_creator`. `print_supply` is `PrintSupply::Zero`
+/// for regular items, only edition drops allow prints.
+pub fn create_collection_item(
+    accounts: &MintNftAccounts,
+    collection_data: &CollectionData,
+    print_supply: PrintSupply,
+    pda_seed: &[&[u8]],
+) -> ProgramResult {
+    //deal creators
+    let mut creators = vec![Creator {
+        address: *accounts.pda_creator.key,
+        verified: true,
+        share: 0,
+    }];
+    // the metadata program only verifies creators that sign, collection
+    // creators verify themselves afterwards with `SignMetadata`
+    for creator in collection_data.creators.iter() {
+        creators.push(Creator {
+            verified: false,
+            ..creator.clone()
+        });
+    }
+
+    match &accounts.programmable {
+        Some(programmable) => create_programmable_item(
+            accounts,
+            programmable,
+            collection_data,
+            creators,
+            print_supply,
+            pda_seed,
+        ),
+        None => create_classic_item(accounts, collection_data, creators, print_supply, pda_seed),
+    }
+}
+
+fn create_classic_item(
+    accounts: &MintNftAccounts,
+    collection_data: &CollectionData,
+    creators: Vec<Creator>,
+    print_supply: PrintSupply,
+    pda_seed: &[&[u8]],
+) -> ProgramResult {
+    //create metadata
+    let cmv3 = CreateMetadataAccountV3 {
+        metadata: *accounts.metadata.key,
+        mint: *accounts.mint.key,
+        mint_authority: *accounts.payer.key,
+        payer: *accounts.payer.key,
+        update_authority: (*accounts.pda_creator.key, true),
+        system_program: *accounts.system.key,
+        rent: Some(*accounts.rent.key),
+    };
+    let data = DataV2 {
+        name: collection_data.name.clone(),
+        symbol: collection_data.symbol.clone(),
+        uri: collection_data.uri.clone(),
+        seller_fee_basis_points: collection_data.fee,
+        creators: Some(creators),
+        collection: Some(Collection {
+            verified: false,
+            key: *accounts.collection_mint.key,
+        }),
+        uses: None,
+    };
+    let cmv3_args = CreateMetadataAccountV3InstructionArgs {
+        data,
+        is_mutable: true,
+        collection_details: None,
+    };
+    invoke_signed(
+        &cmv3.instruction(cmv3_args),
+        &[
+            accounts.metadata.clone(),
+            accounts.mint.clone(),
+            accounts.payer.clone(),
+            accounts.metadata_program.clone(),
+            accounts.token_program.clone(),
+            accounts.system.clone(),
+            accounts.rent.clone(),
+            accounts.pda_creator.clone(),
+            accounts.collection_mint.clone(),
+        ],
+        &[pda_seed],
+    )?;
+
+    //create edition
+    msg!("Create Master Edition");
+    let cmev3 = CreateMasterEditionV3 {
+        edition: *accounts.edition.key,
+        mint: *accounts.mint.key,
+        update_authority: *accounts.pda_creator.key,
+        mint_authority: *accounts.payer.key,
+        payer: *accounts.payer.key,
+        metadata: *accounts.metadata.key,
+        token_program: *accounts.token_program.key,
+        system_program: *accounts.system.key,
+        rent: Some(*accounts.rent.key),
+    };
+    let max_supply = match print_supply {
+        PrintSupply::Zero => Some(0),
+        PrintSupply::Limited(supply) => Some(supply),
+        PrintSupply::Unlimited => None,
+    };
+    let cmev3_args = CreateMasterEditionV3InstructionArgs { max_supply };
+    invoke_signed(
+        &cmev3.instruction(cmev3_args),
+        &[
+            accounts.edition.clone(),
+            accounts.mint.clone(),
+            accounts.payer.clone(),
+            accounts.metadata.clone(),
+            accounts.metadata_program.clone(),
+            accounts.token_program.clone(),
+            accounts.system.clone(),
+            accounts.rent.clone(),
+            accounts.pda_creator.clone(),
+        ],
+        &[pda_seed],
+    )?;
+
+    msg!("verify sized collection item");
+    let vc = VerifySizedCollectionItem {
+        metadata: *accounts.metadata.key,
+        collection_authority: *accounts.pda_creator.key,
+        payer: *accounts.payer.key,
+        collection_mint: *accounts.collection_mint.key,
+        collection: *accounts.collection_metadata.key,
+        collection_master_edition_account: *accounts.collection_master_edition.key,
+        collection_authority_record: Some(*accounts.collection_authority_record.key),
+    };
+    invoke_signed(
+        &vc.instruction(),
+        &[
+            accounts.collection_mint.clone(),
+            accounts.payer.clone(),
+            accounts.metadata.clone(),
+            accounts.metadata_program.clone(),
+            accounts.token_program.clone(),
+            accounts.system.clone(),
+            accounts.rent.clone(),
+            accounts.collection_metadata.clone(),
+            accounts.collection_master_edition.clone(),
+            accounts.collection_authority_record.clone(),
+            accounts.pda_creator.clone(),
+        ],
+        &[pda_seed],
+    )?;
+
+    Ok(())
+}
+
+fn create_programmable_item<'a>(
+    accounts: &MintNftAccounts<'a, '_>,
+    programmable: &ProgrammableAccounts<'a, '_>,
+    collection_data: &CollectionData,
+    creators: Vec<Creator>,
+    print_supply: PrintSupply,
+    pda_seed: &[&[u8]],
+) -> ProgramResult {
+    //create metadata, master edition and the mint itself
+    msg!("Create programmable NFT");
+    let create = CreateV1 {
+        metadata: *accounts.metadata.key,
+        master_edition: Some(*accounts.edition.key),
+        mint: (*accounts.mint.key, true),
+        authority: *accounts.payer.key,
+        payer: *accounts.payer.key,
+        update_authority: (*accounts.pda_creator.key, true),
+        system_program: *accounts.system.key,
+        sysvar_instructions: *programmable.sysvar_instructions.key,
+        spl_token_program: *accounts.token_program.key,
+    };
+    let create_args = CreateV1InstructionArgs {
+        name: collection_data.name.clone(),
+        symbol: collection_data.symbol.clone(),
+        uri: collection_data.uri.clone(),
+        seller_fee_basis_points: collection_data.fee,
+        creators: Some(creators),
+        primary_sale_happened: false,
+        is_mutable: true,
+        token_standard: TokenStandard::ProgrammableNonFungible,
+        collection: Some(Collection {
+            verified: false,
+            key: *accounts.collection_mint.key,
+        }),
+        uses: None,
+        collection_details: None,
+        rule_set: collection_data.rule_set,
+        decimals: Some(0),
+        print_supply: Some(print_supply),
+    };
+    invoke_signed(
+        &create.instruction(create_args),
+        &[
+            accounts.metadata.clone(),
+            accounts.edition.clone(),
+            accounts.mint.clone(),
+            accounts.payer.clone(),
+            accounts.pda_creator.clone(),
+            accounts.system.clone(),
+            programmable.sysvar_instructions.clone(),
+            accounts.token_program.clone(),
+            accounts.metadata_program.clone(),
+        ],
+        &[pda_seed],
+    )?;
+
+    //mint the single token, pNFTs can only be minted by the update authority
+    msg!("Mint programmable NFT");
+    let mint = MintV1 {
+        token: *accounts.token_account.key,
+        token_owner: Some(*accounts.owner.key),
+        metadata: *accounts.metadata.key,
+        master_edition: Some(*accounts.edition.key),
+        token_record: Some(*programmable.token_record.key),
+        mint: *accounts.mint.key,
+        authority: *accounts.pda_creator.key,
+        delegate_record: None,
+        payer: *accounts.payer.key,
+        system_program: *accounts.system.key,
+        sysvar_instructions: *programmable.sysvar_instructions.key,
+        spl_token_program: *accounts.token_program.key,
+        spl_ata_program: *programmable.spl_ata_program.key,
+        authorization_rules_program: Some(*programmable.authorization_rules_program.key),
+        authorization_rules: collection_data
+            .rule_set
+            .map(|_| *programmable.authorization_rules.key),
+    };
+    let mint_args = MintV1InstructionArgs {
+        amount: 1,
+        authorization_data: None,
+    };
+    invoke_signed(
+        &mint.instruction(mint_args),
+        &[
+            accounts.token_account.clone(),
+            accounts.owner.clone(),
+            accounts.metadata.clone(),
+            accounts.edition.clone(),
+            programmable.token_record.clone(),
+            accounts.mint.clone(),
+            accounts.pda_creator.clone(),
+            accounts.payer.clone(),
+            accounts.system.clone(),
+            programmable.sysvar_instructions.clone(),
+            accounts.token_program.clone(),
+            programmable.spl_ata_program.clone(),
+            programmable.authorization_rules_program.clone(),
+            programmable.authorization_rules.clone(),
+            accounts.metadata_program.clone(),
+        ],
+        &[pda_seed],
+    )?;
+
+    msg!("verify collection");
+    let vc = VerifyCollectionV1 {
+        authority: *accounts.pda_creator.key,
+        delegate_record: Some(*accounts.collection_authority_record.key),
+        metadata: *accounts.metadata.key,
+        collection_mint: *accounts.collection_mint.key,
+        collection_metadata: Some(*accounts.collection_metadata.key),
+        collection_master_edition: Some(*accounts.collection_master_edition.key),
+        system_program: *accounts.system.key,
+        sysvar_instructions: *programmable.sysvar_instructions.key,
+    };
+    invoke_signed(
+        &vc.instruction(),
+        &[
+            accounts.pda_creator.clone(),
+            accounts.collection_authority_record.clone(),
+            accounts.metadata.clone(),
+            accounts.collection_mint.clone(),
+            accounts.collection_metadata.clone(),
+            accounts.collection_master_edition.clone(),
+            accounts.system.clone(),
+            programmable.sysvar_instructions.clone(),
+            accounts.metadata_program.clone(),
+        ],
+        &[pda_seed],
+    )?;
+
+    Ok(())
+}
+
+/// Reject a mint the buyer could have known was not allowed: before its
+/// phase opens, with a gate item already used in the phase or without the
+/// required tokens. Collections with a bot tax charge the signer the tax and
+/// succeed, so bots pay for spamming instead of only losing the transaction
+/// fee; others fail with `err`. Mints losing a race to the last item or to
+/// the end of the sale always fail, honest buyers hit those too.
+fn charge_bot_tax<'a>(
+    collection_data: &CollectionData,
+    signer_info: &AccountInfo<'a>,
+    charge_info: &AccountInfo<'a>,
+    system_info: &AccountInfo<'a>,
+    err: AppError,
+) -> ProgramResult {
+    let tax = match collection_data.bot_tax {
+        Some(tax) => tax,
+        None => return Err(err.into()),
+    };
+    msg!("Bot tax: {}", err);
+    if tax > 0 {
+        invoke(
+            &system_instruction::transfer(signer_info.key, charge_info.key, tax),
+            &[
+                signer_info.clone(),
+                charge_info.clone(),
+                system_info.clone(),
+            ],
+        )?;
+    }
+    AppEvent::BotTaxed {
+        collection: collection_data.collection_mint,
+        payer: *signer_info.key,
+        tax,
+        error: err as u32,
+    }
+    .emit();
+    Ok(())
+}
+
 pub fn process_mint(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    mint_items(program_id, accounts, 1)
+}
+
+pub fn process_mint_many(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: MintManyArgs,
+) -> ProgramResult {
+    if args.count == 0 || args.count > MAX_MINT_COUNT {
+        return Err(AppError::InvalidMintCount.into());
+    }
+    mint_items(program_id, accounts, args.count)
+}
+
+/// Per item accounts of a mint, the first item sits in the fixed accounts and
+/// every further item of `MintMany` is appended at the end.
+struct MintItem<'a, 'b> {
+    mint: &'b AccountInfo<'a>,
+    token_account: &'b AccountInfo<'a>,
+    metadata: &'b AccountInfo<'a>,
+    edition: &'b AccountInfo<'a>,
+    /// only set for programmable collections
+    token_record: Option<&'b AccountInfo<'a>>,
+    /// only set for escrowed sales
+    receipt: Option<&'b AccountInfo<'a>>,
+}
+
+/// Mint `count` items of a collection sale, charging the price of each and
+/// counting them against the mint rate as a single mint.
+fn mint_items(program_id: &Pubkey, accounts: &[AccountInfo], count: u8) -> ProgramResult {
     let account_info_iter = &mut accounts.iter();
     let signer_info = next_account_info(account_info_iter)?;
     let pda_creator_info = next_account_info(account_info_iter)?; //nft creator: pda
@@ -36,16 +455,155 @@ pub fn process_mint(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramRes
     let rent_info = next_account_info(account_info_iter)?;
     let system_info = next_account_info(account_info_iter)?;
 
-    assert_signer(&signer_info)?;
-    assert_eq_pubkey(&token_program_info, &spl_token::id())?;
-    assert_eq_pubkey_0(&rent_info, &sysvar::rent::id())?;
-    assert_eq_pubkey_1(&system_info, &solana_program::system_program::id())?;
+    assert_signer(signer_info)?;
+    assert_token_program(token_program_info)?;
+    assert_rent_sysvar(rent_info)?;
+    assert_system_program(system_info)?;
+    assert_owned_by(collection_info, program_id)?;
+    assert_collection(program_id, collection_mint, collection_info)?;
+    assert_owned_by(promotion_info, program_id)?;
+    assert_derivation(
+        program_id,
+        promotion_info,
+        &[program_id.as_ref(), collection_info.key.as_ref()],
+    )?;
 
     let pro_data = PromotionData::from_account_info(promotion_info)?;
     let mut collection_data = CollectionData::from_account_info(collection_info)?;
-    assert_eq_pubkey_2(&charge_info, &pro_data.char_addr)?;
+    assert_eq_pubkey(token_program_info, &collection_data.token_program)?;
+    assert_charge_account(charge_info, &pro_data.char_addr)?;
+
+    // the gatekeeper comes right after the fixed accounts so it is checked
+    // before anything can be charged the bot tax
+    if let Some(gatekeeper) = collection_data.gatekeeper {
+        let gatekeeper_info = next_account_info(account_info_iter)?;
+        if !gatekeeper_info.is_signer || *gatekeeper_info.key != gatekeeper {
+            return Err(AppError::InvalidGatekeeper.into());
+        }
+    }
+
+    let programmable = if collection_data.programmable {
+        Some(ProgrammableAccounts::from_iter(
+            account_info_iter,
+            &collection_data,
+        )?)
+    } else {
+        None
+    };
+
+    // escrowed sales pay the treasury and leave a receipt for refunds
+    let (treasury, receipt) = if pro_data.escrow {
+        let treasury_info = next_account_info(account_info_iter)?;
+        let receipt_info = next_account_info(account_info_iter)?;
+        (Some(treasury_info), Some(receipt_info))
+    } else {
+        (None, None)
+    };
+
+    // during a gated phase only holders of a gate item mint, once per item
+    let now_ts = now_timestamp();
+    let gate = match &pro_data.gate {
+        Some(gate) if now_ts < pro_data.public_start_ts => {
+            if now_ts < gate.start_ts {
+                return charge_bot_tax(
+                    &collection_data,
+                    signer_info,
+                    charge_info,
+                    system_info,
+                    AppError::SaleNotOpen,
+                );
+            }
+            if count > 1 {
+                return Err(AppError::InvalidMintCount.into());
+            }
+            let gate_mint = next_account_info(account_info_iter)?;
+            let gate_token_account = next_account_info(account_info_iter)?;
+            let gate_metadata = next_account_info(account_info_iter)?;
+            let gate_use_info = next_account_info(account_info_iter)?;
 
-    let user_bump = assert_user_info(program_id, &signer_info.key, user_info)?;
+            assert_verified_item(gate_mint, gate_metadata, &gate.collection)?;
+            let token = unpack_token_account(gate_token_account)?;
+            if token.owner != *signer_info.key || token.mint != *gate_mint.key || token.amount != 1
+            {
+                return Err(AppError::InvalidTokenAccount.into());
+            }
+            let gate_use_bump =
+                assert_gate_use(program_id, collection_mint, gate_mint, gate_use_info)?;
+            if !gate_use_info.data_is_empty()
+                && GateUseData::from_account_info(gate_use_info)?.phase == gate.phase
+            {
+                return charge_bot_tax(
+                    &collection_data,
+                    signer_info,
+                    charge_info,
+                    system_info,
+                    AppError::GateUsed,
+                );
+            }
+            Some((gate, gate_mint, gate_use_info, gate_use_bump))
+        }
+        _ => None,
+    };
+
+    let token = match &pro_data.token {
+        Some(requirement) => {
+            let token_mint = next_account_info(account_info_iter)?;
+            let token_account = next_account_info(account_info_iter)?;
+            let token_program = next_account_info(account_info_iter)?;
+            assert_token_program(token_program)?;
+            assert_eq_pubkey(token_mint, requirement.mint())?;
+            assert_owned_by(token_mint, token_program.key)?;
+            let token = unpack_token_account(token_account)?;
+            if token.owner != *signer_info.key || token.mint != *token_mint.key {
+                return Err(AppError::InvalidTokenAccount.into());
+            }
+            if token.amount < requirement.amount() {
+                return charge_bot_tax(
+                    &collection_data,
+                    signer_info,
+                    charge_info,
+                    system_info,
+                    AppError::InsufficientTokens,
+                );
+            }
+            Some((requirement, token_mint, token_account, token_program))
+        }
+        None => None,
+    };
+
+    let mut items = vec![MintItem {
+        mint: mint_info,
+        token_account,
+        metadata: metadata_info,
+        edition: edition_info,
+        token_record: programmable
+            .as_ref()
+            .map(|programmable| programmable.token_record),
+        receipt,
+    }];
+    for _ in 1..count {
+        items.push(MintItem {
+            mint: next_account_info(account_info_iter)?,
+            token_account: next_account_info(account_info_iter)?,
+            metadata: next_account_info(account_info_iter)?,
+            edition: next_account_info(account_info_iter)?,
+            token_record: match programmable {
+                Some(_) => Some(next_account_info(account_info_iter)?),
+                None => None,
+            },
+            receipt: match receipt {
+                Some(_) => Some(next_account_info(account_info_iter)?),
+                None => None,
+            },
+        });
+    }
+    if programmable.is_none() {
+        for item in items.iter() {
+            assert_owned_by(item.mint, token_program_info.key)?;
+        }
+    }
+
+    let user_bump = assert_user_info(program_id, signer_info.key, user_info)?;
     let user_seeds = [
         program_id.as_ref(),
         signer_info.key.as_ref(),
@@ -61,25 +619,26 @@ pub fn process_mint(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramRes
             system_info,
             signer_info,
             UserData::LEN,
-            &user_seeds
+            &user_seeds,
         )?;
     }
 
-    let name = collection_data.name.clone();
-    let symbol = collection_data.symbol.clone();
-    let uri = collection_data.uri.clone();
-    let fee = collection_data.fee.clone();
-    let now_ts = now_timestamp();
     //check sale state
-    if pro_data.public_start_ts > now_ts {
-        return ferror!("sale not open");
+    if gate.is_none() && pro_data.public_start_ts > now_ts {
+        return charge_bot_tax(
+            &collection_data,
+            signer_info,
+            charge_info,
+            system_info,
+            AppError::SaleNotOpen,
+        );
     }
 
-    if collection_data.max_supply == 10000 {
-        return ferror!("sold out");
+    if count as u64 > collection_data.sale_left() {
+        return Err(AppError::SoldOut.into());
     }
 
-    let pda_bump = assert_pda_creator(&program_id, collection_mint, pda_creator_info)?;
+    let pda_bump = assert_pda_creator(program_id, collection_mint, pda_creator_info)?;
     let pda_seed = [
         program_id.as_ref(),
         collection_mint.key.as_ref(),
@@ -87,175 +646,191 @@ pub fn process_mint(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramRes
         &[pda_bump],
     ];
 
-    let price = pro_data.sale_price;
-    invoke(
-        &system_instruction::transfer(&signer_info.key, &pro_data.char_addr, price),
-        &[
-            signer_info.clone(),
-            charge_info.clone(),
-            system_info.clone(),
-        ],
-    )?;
-
-    //deal creators
-    let mut creators = vec![Creator {
-        address: *pda_creator_info.key,
-        verified: true,
-        share: 0,
-    }];
-    for creator in collection_data.creators.iter() {
-        creators.push(creator.clone());
+    let mut payee_info = charge_info;
+    if let Some(treasury_info) = treasury {
+        let treasury_bump = assert_treasury(program_id, collection_mint, treasury_info)?;
+        if treasury_info.data_is_empty() {
+            let treasury_seeds = [
+                program_id.as_ref(),
+                collection_mint.key.as_ref(),
+                "treasury".as_bytes(),
+                &[treasury_bump],
+            ];
+            create_or_allocate_account_raw(
+                *program_id,
+                treasury_info,
+                rent_info,
+                system_info,
+                signer_info,
+                TreasuryData::LEN,
+                &treasury_seeds,
+            )?;
+            TreasuryData {
+                collection: *collection_mint.key,
+                status: SaleStatus::Open,
+                total: 0,
+                sold: 0,
+                clearing_price: 0,
+                rebate: false,
+            }
+            .serialize(&mut *treasury_info.try_borrow_mut_data()?)?;
+        }
+        if TreasuryData::from_account_info(treasury_info)?.status != SaleStatus::Open {
+            return Err(AppError::SaleEnded.into());
+        }
+        payee_info = treasury_info;
     }
 
-    //create metadata
-    let cmv3 = CreateMetadataAccountV3 {
-        metadata: *metadata_info.key,
-        mint: *mint_info.key,
-        mint_authority: *signer_info.key,
-        payer: *signer_info.key,
-        update_authority: (*pda_creator_info.key, true),
-        system_program: *system_info.key,
-        rent: Some(*rent_info.key),
-    };
-    let data = DataV2 {
-        name: name,
-        symbol: symbol,
-        uri: uri,
-        seller_fee_basis_points: fee,
-        creators: Some(creators),
-        collection: Some(Collection {
-            verified: false,
-            key: *collection_mint.key,
-        }),
-        uses: None,
-    };
-    let cmv3_args = CreateMetadataAccountV3InstructionArgs {
-        data: data,
-        is_mutable: true,
-        collection_details: None,
-    };
-    invoke_signed(
-        &cmv3.instruction(cmv3_args),
-        // &create_metadata_accounts_v2(
-        //     *metadata_program_info.key,
-        //     *metadata_info.key,
-        //     *mint_info.key,
-        //     *signer_info.key,
-        //     *signer_info.key,
-        //     *pda_creator_info.key,
-        //     name,
-        //     collection_data.symbol.clone(),
-        //     uri,
-        //     Some(creators),
-        //     collection_data.fee,
-        //     true,
-        //     true,
-        //     Some(Collection {
-        //         verified: false,
-        //         key: *collection_mint.key,
-        //     }),
-        //     None,
-        // ),
-        &[
-            metadata_info.clone(),
-            mint_info.clone(),
-            signer_info.clone(),
-            metadata_program_info.clone(),
-            token_program_info.clone(),
-            system_info.clone(),
-            rent_info.clone(),
-            pda_creator_info.clone(),
-            collection_mint.clone(),
-        ],
-        &[&pda_seed],
-    )?;
-
-    //create edition
-    msg!("Create Master Edition");
-    let cmev3 = CreateMasterEditionV3 {
-        edition: *edition_info.key,
-        mint: *mint_info.key,
-        update_authority: *pda_creator_info.key,
-        mint_authority: *signer_info.key,
-        payer: *signer_info.key,
-        metadata: *metadata_info.key,
-        token_program: *token_program_info.key,
-        system_program: *system_info.key,
-        rent: Some(*rent_info.key),
-    };
-    let cmev3_args = CreateMasterEditionV3InstructionArgs {
-        max_supply: Some(0),
+    let burn_priced = matches!(pro_data.token, Some(TokenRequirement::Burn { .. }));
+    let price = match (gate, &token) {
+        (_, Some((TokenRequirement::Burn { .. }, ..))) => 0,
+        (Some((gate, ..)), _) => gate.price.unwrap_or_else(|| pro_data.current_price(now_ts)),
+        (None, _) => pro_data.current_price(now_ts),
     };
+    let total = price
+        .checked_mul(count as u64)
+        .ok_or(AppError::CheckedCalculateFailed)?;
+    if let Some((TokenRequirement::Burn { amount, .. }, token_mint, token_account, token_program)) =
+        token
+    {
+        spl_token_burn(
+            token_program,
+            token_mint,
+            token_account,
+            signer_info,
+            &[],
+            rent_info,
+            amount
+                .checked_mul(count as u64)
+                .ok_or(AppError::CheckedCalculateFailed)?,
+        )?;
+    }
+    if total > 0 {
+        invoke(
+            &system_instruction::transfer(signer_info.key, payee_info.key, total),
+            &[signer_info.clone(), payee_info.clone(), system_info.clone()],
+        )?;
+    }
 
-    invoke_signed(
-        &cmev3.instruction(cmev3_args),
-        // &create_master_edition_v3(
-        //     *metadata_program_info.key,
-        //     *edition_info.key,
-        //     *mint_info.key,
-        //     *pda_creator_info.key,
-        //     *signer_info.key,
-        //     *metadata_info.key,
-        //     *signer_info.key,
-        //     Some(0),
-        // ),
-        &[
-            edition_info.clone(),
-            mint_info.clone(),
-            signer_info.clone(),
-            metadata_info.clone(),
-            metadata_program_info.clone(),
-            token_program_info.clone(),
-            system_info.clone(),
-            rent_info.clone(),
-            pda_creator_info.clone(),
-        ],
-        &[&pda_seed],
-    )?;
+    for item in items.iter() {
+        let nft_accounts = MintNftAccounts {
+            payer: signer_info,
+            owner: signer_info,
+            pda_creator: pda_creator_info,
+            mint: item.mint,
+            token_account: item.token_account,
+            metadata: item.metadata,
+            edition: item.edition,
+            collection_mint,
+            collection_metadata,
+            collection_master_edition: collection_master_edition_account,
+            collection_authority_record,
+            metadata_program: metadata_program_info,
+            token_program: token_program_info,
+            rent: rent_info,
+            system: system_info,
+            programmable: programmable
+                .as_ref()
+                .map(|programmable| ProgrammableAccounts {
+                    sysvar_instructions: programmable.sysvar_instructions,
+                    token_record: item.token_record.unwrap(),
+                    spl_ata_program: programmable.spl_ata_program,
+                    authorization_rules_program: programmable.authorization_rules_program,
+                    authorization_rules: programmable.authorization_rules,
+                }),
+        };
+        create_collection_item(
+            &nft_accounts,
+            &collection_data,
+            PrintSupply::Zero,
+            &pda_seed,
+        )?;
+    }
 
-    msg!("verify collection");
-    let vc = VerifyCollection {
-        metadata: *metadata_info.key,
-        collection_authority: *pda_creator_info.key,
-        payer: *signer_info.key,
-        collection_mint: *collection_mint.key,
-        collection: *collection_metadata.key,
-        collection_master_edition_account: *collection_master_edition_account.key,
-        collection_authority_record: Some(*collection_authority_record.key),
-    };
-    invoke_signed(
-        &vc.instruction(),
-        // &verify_collection(
-        //     *metadata_program_info.key,
-        //     *metadata_info.key,
-        //     *pda_creator_info.key,
-        //     *signer_info.key,
-        //     *collection_mint.key,
-        //     *collection_metadata.key,
-        //     *collection_master_edition_account.key,
-        //     Some(*collection_authority_record.key),
-        // ),
-        &[
-            collection_mint.clone(),
-            signer_info.clone(),
-            metadata_info.clone(),
-            metadata_program_info.clone(),
-            token_program_info.clone(),
-            system_info.clone(),
-            rent_info.clone(),
-            collection_metadata.clone(),
-            collection_master_edition_account.clone(),
-            collection_authority_record.clone(),
-            pda_creator_info.clone(),
-        ],
-        &[&pda_seed],
-    )?;
     let mut user_data = UserData::from_account_info(user_info)?;
     user_data.shots += 1;
     if now_ts > collection_data.ts {
-        collection_data.max_supply += 1;
         collection_data.ts = now_ts;
-        user_data.minted += 1;
+        user_data.minted += count as u16;
+        if let Some((gate, gate_mint, gate_use_info, gate_use_bump)) = gate {
+            if gate_use_info.data_is_empty() {
+                let gate_use_seeds = [
+                    program_id.as_ref(),
+                    collection_mint.key.as_ref(),
+                    gate_mint.key.as_ref(),
+                    "gate".as_bytes(),
+                    &[gate_use_bump],
+                ];
+                create_or_allocate_account_raw(
+                    *program_id,
+                    gate_use_info,
+                    rent_info,
+                    system_info,
+                    signer_info,
+                    GateUseData::LEN,
+                    &gate_use_seeds,
+                )?;
+            }
+            GateUseData {
+                collection: *collection_mint.key,
+                gate_mint: *gate_mint.key,
+                phase: gate.phase,
+            }
+            .serialize(&mut *gate_use_info.try_borrow_mut_data()?)?;
+        }
+        if let Some(treasury_info) = treasury {
+            for item in items.iter() {
+                let receipt_info = item.receipt.unwrap();
+                let receipt_bump = assert_receipt(program_id, item.mint, receipt_info)?;
+                let receipt_seeds = [
+                    program_id.as_ref(),
+                    item.mint.key.as_ref(),
+                    "receipt".as_bytes(),
+                    &[receipt_bump],
+                ];
+                create_or_allocate_account_raw(
+                    *program_id,
+                    receipt_info,
+                    rent_info,
+                    system_info,
+                    signer_info,
+                    ReceiptData::LEN,
+                    &receipt_seeds,
+                )?;
+                ReceiptData {
+                    collection: *collection_mint.key,
+                    mint: *item.mint.key,
+                    buyer: *signer_info.key,
+                    price,
+                }
+                .serialize(&mut *receipt_info.try_borrow_mut_data()?)?;
+            }
+
+            let mut treasury_data = TreasuryData::from_account_info(treasury_info)?;
+            treasury_data.total = treasury_data
+                .total
+                .checked_add(total)
+                .ok_or(AppError::CheckedCalculateFailed)?;
+            treasury_data.sold += count as u64;
+            treasury_data.clearing_price = price;
+            treasury_data.serialize(&mut *treasury_info.try_borrow_mut_data()?)?;
+        }
+        for item in items.iter() {
+            collection_data.max_supply += 1;
+            AppEvent::Minted {
+                collection: *collection_mint.key,
+                mint: *item.mint.key,
+                buyer: *signer_info.key,
+                price,
+                index: collection_data.max_supply,
+            }
+            .emit();
+        }
+    } else if collection_data.programmable || treasury.is_some() || count > 1 || burn_priced {
+        // pNFT token accounts are frozen, they can't take the plain burn below,
+        // escrowed payments must come with a refundable item, batches are not
+        // burned wholesale and burned price tokens must buy an item
+        return Err(AppError::MintRateExceeded.into());
     } else {
         spl_token_burn(
             token_program_info,
@@ -0,0 +1,160 @@
+use borsh::BorshSerialize;
+use mpl_token_metadata::{
+    instructions::{
+        MintNewEditionFromMasterEditionViaToken,
+        MintNewEditionFromMasterEditionViaTokenInstructionArgs,
+    },
+    types::MintNewEditionFromMasterEditionViaTokenArgs,
+};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::{invoke, invoke_signed},
+    pubkey::Pubkey,
+    system_instruction,
+};
+
+use crate::{error::AppError, event::AppEvent, state::*, utils::*};
+
+/// Sell the next print of an edition drop, priced and time gated by the
+/// collection's promotion.
+pub fn process_mint_edition(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let signer_info = next_account_info(account_info_iter)?;
+    let pda_creator_info = next_account_info(account_info_iter)?;
+    let new_mint = next_account_info(account_info_iter)?;
+    let new_metadata = next_account_info(account_info_iter)?;
+    let new_edition = next_account_info(account_info_iter)?;
+    let edition_marker = next_account_info(account_info_iter)?;
+    let master_mint = next_account_info(account_info_iter)?;
+    let master_token_account = next_account_info(account_info_iter)?;
+    let master_metadata = next_account_info(account_info_iter)?;
+    let master_edition = next_account_info(account_info_iter)?;
+    let edition_drop_info = next_account_info(account_info_iter)?;
+    let collection_mint = next_account_info(account_info_iter)?;
+    let collection_info = next_account_info(account_info_iter)?;
+    let promotion_info = next_account_info(account_info_iter)?;
+    let charge_info = next_account_info(account_info_iter)?;
+    let metadata_program_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+    let rent_info = next_account_info(account_info_iter)?;
+    let system_info = next_account_info(account_info_iter)?;
+
+    assert_eq_pubkey(
+        metadata_program_info,
+        &mpl_token_metadata::programs::MPL_TOKEN_METADATA_ID,
+    )?;
+    assert_rent_sysvar(rent_info)?;
+    assert_system_program(system_info)?;
+    assert_signer(signer_info)?;
+    assert_owned_by(edition_drop_info, program_id)?;
+    assert_owned_by(collection_info, program_id)?;
+    assert_owned_by(promotion_info, program_id)?;
+    assert_edition_drop(program_id, master_mint, edition_drop_info)?;
+    assert_collection(program_id, collection_mint, collection_info)?;
+    assert_derivation(
+        program_id,
+        promotion_info,
+        &[program_id.as_ref(), collection_info.key.as_ref()],
+    )?;
+    let pda_bump = assert_pda_creator(program_id, collection_mint, pda_creator_info)?;
+
+    let mut drop_data = EditionDropData::from_account_info(edition_drop_info)?;
+    let collection_data = CollectionData::from_account_info(collection_info)?;
+    let pro_data = PromotionData::from_account_info(promotion_info)?;
+    assert_eq_pubkey(collection_mint, &drop_data.collection)?;
+    assert_eq_pubkey(token_program_info, &collection_data.token_program)?;
+    assert_charge_account(charge_info, &pro_data.char_addr)?;
+
+    //check sale state
+    if pro_data.escrow {
+        return Err(AppError::EscrowUnsupported.into());
+    }
+    if pro_data.token.is_some() {
+        return Err(AppError::InvalidTokenRequirement.into());
+    }
+    let now_ts = now_timestamp();
+    if pro_data.public_start_ts > now_ts {
+        return Err(AppError::SaleNotOpen.into());
+    }
+    if let Some(max_supply) = drop_data.max_supply {
+        if drop_data.minted >= max_supply {
+            return Err(AppError::SoldOut.into());
+        }
+    }
+
+    let price = pro_data.current_price(now_ts);
+    invoke(
+        &system_instruction::transfer(signer_info.key, &pro_data.char_addr, price),
+        &[
+            signer_info.clone(),
+            charge_info.clone(),
+            system_info.clone(),
+        ],
+    )?;
+
+    let pda_seed = [
+        program_id.as_ref(),
+        collection_mint.key.as_ref(),
+        "pda_creator".as_bytes(),
+        &[pda_bump],
+    ];
+    let edition = drop_data.minted + 1;
+
+    msg!("print edition {}", edition);
+    let mne = MintNewEditionFromMasterEditionViaToken {
+        new_metadata: *new_metadata.key,
+        new_edition: *new_edition.key,
+        master_edition: *master_edition.key,
+        new_mint: *new_mint.key,
+        edition_mark_pda: *edition_marker.key,
+        new_mint_authority: *signer_info.key,
+        payer: *signer_info.key,
+        token_account_owner: *pda_creator_info.key,
+        token_account: *master_token_account.key,
+        new_metadata_update_authority: *pda_creator_info.key,
+        metadata: *master_metadata.key,
+        token_program: *token_program_info.key,
+        system_program: *system_info.key,
+        rent: Some(*rent_info.key),
+    };
+    let mne_args = MintNewEditionFromMasterEditionViaTokenInstructionArgs {
+        mint_new_edition_from_master_edition_via_token_args:
+            MintNewEditionFromMasterEditionViaTokenArgs { edition },
+    };
+    invoke_signed(
+        &mne.instruction(mne_args),
+        &[
+            new_metadata.clone(),
+            new_edition.clone(),
+            master_edition.clone(),
+            new_mint.clone(),
+            edition_marker.clone(),
+            signer_info.clone(),
+            pda_creator_info.clone(),
+            master_token_account.clone(),
+            master_metadata.clone(),
+            token_program_info.clone(),
+            system_info.clone(),
+            rent_info.clone(),
+            metadata_program_info.clone(),
+        ],
+        &[&pda_seed],
+    )?;
+
+    drop_data.minted = edition;
+    drop_data.serialize(&mut &mut edition_drop_info.data.borrow_mut()[..])?;
+
+    AppEvent::EditionMinted {
+        collection: drop_data.collection,
+        master_mint: drop_data.master_mint,
+        mint: *new_mint.key,
+        buyer: *signer_info.key,
+        price,
+        edition,
+    }
+    .emit();
+
+    Ok(())
+}
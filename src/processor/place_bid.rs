@@ -0,0 +1,82 @@
+use borsh::BorshSerialize;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program::invoke,
+    pubkey::Pubkey,
+    system_instruction,
+};
+
+use crate::{error::AppError, event::AppEvent, state::*, utils::*};
+
+/// Escrow a bid in the auction account and refund the bid it beats.
+/// `previous_bidder` is the current highest bidder, any account when there is
+/// none yet.
+pub fn process_place_bid(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: PlaceBidArgs,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let signer_info = next_account_info(account_info_iter)?;
+    let collection_mint = next_account_info(account_info_iter)?;
+    let auction_info = next_account_info(account_info_iter)?;
+    let previous_bidder = next_account_info(account_info_iter)?;
+    let system_info = next_account_info(account_info_iter)?;
+
+    assert_signer(signer_info)?;
+    assert_system_program(system_info)?;
+    assert_owned_by(auction_info, program_id)?;
+
+    let mut auction_data = AuctionData::from_account_info(auction_info)?;
+    assert_auction(program_id, collection_mint, auction_data.id, auction_info)?;
+
+    //check auction state
+    let now_ts = now_timestamp();
+    if now_ts < auction_data.start_ts || now_ts >= auction_data.end_ts {
+        return Err(AppError::AuctionNotLive.into());
+    }
+    let min_bid = match auction_data.highest_bidder {
+        Some(_) => auction_data
+            .highest_bid
+            .checked_add(auction_data.min_increment)
+            .ok_or(AppError::CheckedCalculateFailed)?,
+        None => auction_data.reserve_price,
+    };
+    if args.amount < min_bid {
+        return Err(AppError::BidTooLow.into());
+    }
+
+    invoke(
+        &system_instruction::transfer(signer_info.key, auction_info.key, args.amount),
+        &[
+            signer_info.clone(),
+            auction_info.clone(),
+            system_info.clone(),
+        ],
+    )?;
+
+    //refund the outbid bidder
+    if let Some(highest_bidder) = auction_data.highest_bidder {
+        assert_eq_pubkey(previous_bidder, &highest_bidder)?;
+        transfer_lamports(auction_info, previous_bidder, auction_data.highest_bid)?;
+    }
+
+    //anti-snipe
+    if auction_data.end_ts - now_ts < auction_data.extension {
+        auction_data.end_ts = now_ts + auction_data.extension;
+    }
+    auction_data.highest_bidder = Some(*signer_info.key);
+    auction_data.highest_bid = args.amount;
+    auction_data.serialize(&mut *auction_info.try_borrow_mut_data()?)?;
+
+    AppEvent::BidPlaced {
+        auction: *auction_info.key,
+        bidder: *signer_info.key,
+        amount: args.amount,
+        end_ts: auction_data.end_ts,
+    }
+    .emit();
+
+    Ok(())
+}
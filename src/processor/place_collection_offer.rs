@@ -0,0 +1,94 @@
+use borsh::BorshSerialize;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program::invoke,
+    pubkey::Pubkey,
+    system_instruction,
+};
+
+use crate::{error::AppError, event::AppEvent, state::*, utils::*};
+
+/// Offer `price` for any item of a factory collection, escrowing the lamports
+/// in the offer account. Placing an offer again changes its price, topping up
+/// or refunding the difference.
+pub fn process_place_collection_offer(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: OfferArgs,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let signer_info = next_account_info(account_info_iter)?;
+    let collection_mint = next_account_info(account_info_iter)?;
+    let collection_info = next_account_info(account_info_iter)?;
+    let offer_info = next_account_info(account_info_iter)?;
+    let rent_info = next_account_info(account_info_iter)?;
+    let system_info = next_account_info(account_info_iter)?;
+
+    assert_signer(signer_info)?;
+    assert_rent_sysvar(rent_info)?;
+    assert_system_program(system_info)?;
+    assert_owned_by(collection_info, program_id)?;
+    let path = &[
+        program_id.as_ref(),
+        collection_mint.key.as_ref(),
+        "collection".as_bytes(),
+    ];
+    assert_derivation(program_id, collection_info, path)?;
+    let offer_bump = assert_offer(program_id, collection_mint.key, signer_info.key, offer_info)?;
+    if args.price == 0 {
+        return Err(AppError::BidTooLow.into());
+    }
+
+    let previous_price = if offer_info.data_is_empty() {
+        let offer_seeds = [
+            program_id.as_ref(),
+            collection_mint.key.as_ref(),
+            signer_info.key.as_ref(),
+            "offer".as_bytes(),
+            &[offer_bump],
+        ];
+        create_or_allocate_account_raw(
+            *program_id,
+            offer_info,
+            rent_info,
+            system_info,
+            signer_info,
+            OfferData::LEN,
+            &offer_seeds,
+        )?;
+        0
+    } else {
+        assert_owned_by(offer_info, program_id)?;
+        OfferData::from_account_info(offer_info)?.price
+    };
+
+    if args.price > previous_price {
+        invoke(
+            &system_instruction::transfer(
+                signer_info.key,
+                offer_info.key,
+                args.price - previous_price,
+            ),
+            &[signer_info.clone(), offer_info.clone(), system_info.clone()],
+        )?;
+    } else if args.price < previous_price {
+        transfer_lamports(offer_info, signer_info, previous_price - args.price)?;
+    }
+
+    let offer_data = OfferData {
+        collection: *collection_mint.key,
+        bidder: *signer_info.key,
+        price: args.price,
+    };
+    offer_data.serialize(&mut *offer_info.try_borrow_mut_data()?)?;
+
+    AppEvent::OfferPlaced {
+        collection: offer_data.collection,
+        bidder: offer_data.bidder,
+        price: offer_data.price,
+    }
+    .emit();
+
+    Ok(())
+}
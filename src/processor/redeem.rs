@@ -0,0 +1,219 @@
+use borsh::BorshSerialize;
+use mpl_token_metadata::{
+    accounts::{MasterEdition, Metadata},
+    instructions::BurnNft,
+    types::PrintSupply,
+};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program::invoke,
+    pubkey::Pubkey,
+};
+
+use crate::{error::AppError, event::AppEvent, processor::*, state::*, utils::*};
+
+/// Redeem a recipe: burn its inputs and mint an item of the output collection
+/// or write a redemption receipt. Burned items must be classic spl-token
+/// NFTs.
+///
+/// The burned items follow the fixed accounts in recipe order, each as
+/// metadata, mint, token account, master edition, collection account and
+/// collection metadata. Then come the output accounts: a collection output
+/// takes the same item accounts as `Mint`, signer as the mint authority of
+/// classic mints, followed by the programmable accounts for programmable
+/// collections; a receipt output takes the redemption account.
+pub fn process_redeem(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let signer_info = next_account_info(account_info_iter)?;
+    let recipe_info = next_account_info(account_info_iter)?;
+    let metadata_program_info = next_account_info(account_info_iter)?;
+    let burn_token_program_info = next_account_info(account_info_iter)?;
+    let rent_info = next_account_info(account_info_iter)?;
+    let system_info = next_account_info(account_info_iter)?;
+
+    assert_signer(signer_info)?;
+    assert_eq_pubkey(
+        metadata_program_info,
+        &mpl_token_metadata::programs::MPL_TOKEN_METADATA_ID,
+    )?;
+    assert_eq_pubkey(burn_token_program_info, &spl_token::id())?;
+    assert_rent_sysvar(rent_info)?;
+    assert_system_program(system_info)?;
+    assert_owned_by(recipe_info, program_id)?;
+
+    let mut recipe_data = RecipeData::from_account_info(recipe_info)?;
+    assert_recipe(program_id, recipe_data.id, recipe_info)?;
+
+    //burn inputs
+    let mut burned = Vec::with_capacity(recipe_data.burns());
+    for input in recipe_data.inputs.iter() {
+        for _ in 0..input.quantity {
+            let metadata_info = next_account_info(account_info_iter)?;
+            let mint_info = next_account_info(account_info_iter)?;
+            let token_account = next_account_info(account_info_iter)?;
+            let edition_info = next_account_info(account_info_iter)?;
+            let collection_info = next_account_info(account_info_iter)?;
+            let collection_metadata = next_account_info(account_info_iter)?;
+
+            assert_collection_item(program_id, mint_info, metadata_info, collection_info)?;
+            let collection_data = CollectionData::from_account_info(collection_info)?;
+            if collection_data.collection_mint != input.collection {
+                return Err(AppError::NotCollectionItem.into());
+            }
+            if collection_data.programmable {
+                return Err(AppError::ProgrammableUnsupported.into());
+            }
+            assert_eq_pubkey(edition_info, &MasterEdition::find_pda(mint_info.key).0)?;
+            assert_eq_pubkey(
+                collection_metadata,
+                &Metadata::find_pda(&collection_data.collection_mint).0,
+            )?;
+
+            let burn = BurnNft {
+                metadata: *metadata_info.key,
+                owner: *signer_info.key,
+                mint: *mint_info.key,
+                token_account: *token_account.key,
+                master_edition_account: *edition_info.key,
+                spl_token_program: spl_token::id(),
+                collection_metadata: Some(*collection_metadata.key),
+            };
+            invoke(
+                &burn.instruction(),
+                &[
+                    metadata_info.clone(),
+                    signer_info.clone(),
+                    mint_info.clone(),
+                    token_account.clone(),
+                    edition_info.clone(),
+                    burn_token_program_info.clone(),
+                    collection_metadata.clone(),
+                    metadata_program_info.clone(),
+                ],
+            )?;
+            burned.push(*mint_info.key);
+        }
+    }
+
+    //output
+    let mut minted = None;
+    let mut redemption = None;
+    match recipe_data.output {
+        RecipeOutput::Collection(output_collection) => {
+            let pda_creator_info = next_account_info(account_info_iter)?;
+            let mint_info = next_account_info(account_info_iter)?;
+            let token_account = next_account_info(account_info_iter)?;
+            let metadata_info = next_account_info(account_info_iter)?;
+            let edition_info = next_account_info(account_info_iter)?;
+            let collection_mint = next_account_info(account_info_iter)?;
+            let collection_metadata = next_account_info(account_info_iter)?;
+            let collection_master_edition_account = next_account_info(account_info_iter)?;
+            let collection_authority_record = next_account_info(account_info_iter)?;
+            let collection_info = next_account_info(account_info_iter)?;
+            let token_program_info = next_account_info(account_info_iter)?;
+
+            assert_eq_pubkey(collection_mint, &output_collection)?;
+            assert_owned_by(collection_info, program_id)?;
+            assert_collection(program_id, collection_mint, collection_info)?;
+            let mut collection_data = CollectionData::from_account_info(collection_info)?;
+            assert_eq_pubkey(token_program_info, &collection_data.token_program)?;
+            if collection_data.sale_left() == 0 {
+                return Err(AppError::SoldOut.into());
+            }
+
+            let programmable = if collection_data.programmable {
+                Some(ProgrammableAccounts::from_iter(
+                    account_info_iter,
+                    &collection_data,
+                )?)
+            } else {
+                assert_owned_by(mint_info, token_program_info.key)?;
+                None
+            };
+
+            let pda_bump = assert_pda_creator(program_id, collection_mint, pda_creator_info)?;
+            let pda_seed = [
+                program_id.as_ref(),
+                collection_mint.key.as_ref(),
+                "pda_creator".as_bytes(),
+                &[pda_bump],
+            ];
+            let nft_accounts = MintNftAccounts {
+                payer: signer_info,
+                owner: signer_info,
+                pda_creator: pda_creator_info,
+                mint: mint_info,
+                token_account,
+                metadata: metadata_info,
+                edition: edition_info,
+                collection_mint,
+                collection_metadata,
+                collection_master_edition: collection_master_edition_account,
+                collection_authority_record,
+                metadata_program: metadata_program_info,
+                token_program: token_program_info,
+                rent: rent_info,
+                system: system_info,
+                programmable,
+            };
+            create_collection_item(
+                &nft_accounts,
+                &collection_data,
+                PrintSupply::Zero,
+                &pda_seed,
+            )?;
+
+            collection_data.max_supply += 1;
+            collection_data.serialize(&mut *collection_info.try_borrow_mut_data()?)?;
+            minted = Some(*mint_info.key);
+        }
+        RecipeOutput::Receipt => {
+            let redemption_info = next_account_info(account_info_iter)?;
+            let bump = assert_redemption(
+                program_id,
+                recipe_info,
+                recipe_data.redeemed,
+                redemption_info,
+            )?;
+            let redemption_seeds = [
+                program_id.as_ref(),
+                recipe_info.key.as_ref(),
+                &recipe_data.redeemed.to_le_bytes(),
+                "redemption".as_bytes(),
+                &[bump],
+            ];
+            create_or_allocate_account_raw(
+                *program_id,
+                redemption_info,
+                rent_info,
+                system_info,
+                signer_info,
+                RedemptionData::LEN,
+                &redemption_seeds,
+            )?;
+            RedemptionData {
+                recipe: *recipe_info.key,
+                index: recipe_data.redeemed,
+                redeemer: *signer_info.key,
+                ts: now_timestamp(),
+            }
+            .serialize(&mut *redemption_info.try_borrow_mut_data()?)?;
+            redemption = Some(*redemption_info.key);
+        }
+    }
+
+    recipe_data.redeemed += 1;
+    recipe_data.serialize(&mut &mut recipe_info.data.borrow_mut()[..])?;
+
+    AppEvent::Redeemed {
+        recipe: *recipe_info.key,
+        redeemer: *signer_info.key,
+        burned,
+        mint: minted,
+        redemption,
+    }
+    .emit();
+
+    Ok(())
+}
@@ -0,0 +1,109 @@
+use borsh::BorshSerialize;
+use mpl_token_metadata::instructions::{BurnV1, BurnV1InstructionArgs};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program::invoke,
+    pubkey::Pubkey,
+    sysvar,
+};
+
+use crate::{error::AppError, event::AppEvent, state::*, utils::*};
+
+/// Burn an item bought in a cancelled escrowed sale and pay the buyer back
+/// the price on its receipt. `token_record` is only read for programmable
+/// items, pass the metadata program id otherwise.
+pub fn process_refund(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let signer_info = next_account_info(account_info_iter)?;
+    let collection_mint = next_account_info(account_info_iter)?;
+    let treasury_info = next_account_info(account_info_iter)?;
+    let receipt_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    let token_account = next_account_info(account_info_iter)?;
+    let metadata_info = next_account_info(account_info_iter)?;
+    let edition_info = next_account_info(account_info_iter)?;
+    let collection_metadata = next_account_info(account_info_iter)?;
+    let token_record = next_account_info(account_info_iter)?;
+    let metadata_program_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+    let sysvar_instructions = next_account_info(account_info_iter)?;
+    let system_info = next_account_info(account_info_iter)?;
+
+    assert_signer(signer_info)?;
+    assert_eq_pubkey(
+        metadata_program_info,
+        &mpl_token_metadata::programs::MPL_TOKEN_METADATA_ID,
+    )?;
+    assert_token_program(token_program_info)?;
+    assert_eq_pubkey(sysvar_instructions, &sysvar::instructions::id())?;
+    assert_system_program(system_info)?;
+    assert_owned_by(treasury_info, program_id)?;
+    assert_owned_by(receipt_info, program_id)?;
+    assert_treasury(program_id, collection_mint, treasury_info)?;
+    assert_receipt(program_id, mint_info, receipt_info)?;
+
+    let receipt_data = ReceiptData::from_account_info(receipt_info)?;
+    if receipt_data.buyer != *signer_info.key {
+        return Err(AppError::InvalidOwner.into());
+    }
+    assert_eq_pubkey(collection_mint, &receipt_data.collection)?;
+
+    let mut treasury_data = TreasuryData::from_account_info(treasury_info)?;
+    if treasury_data.status != SaleStatus::Cancelled {
+        return Err(AppError::SaleNotCancelled.into());
+    }
+
+    let token_record_key =
+        (*token_record.key != mpl_token_metadata::ID).then_some(*token_record.key);
+    let burn = BurnV1 {
+        authority: *signer_info.key,
+        collection_metadata: Some(*collection_metadata.key),
+        metadata: *metadata_info.key,
+        edition: Some(*edition_info.key),
+        mint: *mint_info.key,
+        token: *token_account.key,
+        master_edition: None,
+        master_edition_mint: None,
+        master_edition_token: None,
+        edition_marker: None,
+        token_record: token_record_key,
+        system_program: *system_info.key,
+        sysvar_instructions: *sysvar_instructions.key,
+        spl_token_program: *token_program_info.key,
+    };
+    invoke(
+        &burn.instruction(BurnV1InstructionArgs { amount: 1 }),
+        &[
+            signer_info.clone(),
+            collection_metadata.clone(),
+            metadata_info.clone(),
+            edition_info.clone(),
+            mint_info.clone(),
+            token_account.clone(),
+            token_record.clone(),
+            system_info.clone(),
+            sysvar_instructions.clone(),
+            token_program_info.clone(),
+            metadata_program_info.clone(),
+        ],
+    )?;
+
+    transfer_lamports(treasury_info, signer_info, receipt_data.price)?;
+    treasury_data.total = treasury_data
+        .total
+        .checked_sub(receipt_data.price)
+        .ok_or(AppError::CheckedCalculateFailed)?;
+    treasury_data.serialize(&mut *treasury_info.try_borrow_mut_data()?)?;
+    close_account(receipt_info, signer_info)?;
+
+    AppEvent::Refunded {
+        collection: receipt_data.collection,
+        mint: receipt_data.mint,
+        buyer: receipt_data.buyer,
+        amount: receipt_data.price,
+    }
+    .emit();
+
+    Ok(())
+}
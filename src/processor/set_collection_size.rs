@@ -0,0 +1,86 @@
+use mpl_token_metadata::{
+    instructions::{SetCollectionSize, SetCollectionSizeInstructionArgs},
+    types::SetCollectionSizeArgs,
+};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    pubkey::Pubkey,
+};
+
+use crate::{error::AppError, event::AppEvent, state::*, utils::*};
+
+/// Migrate a collection created before collections were sized. The size is
+/// set to the number of items minted so far, after which mints verify with
+/// `VerifySizedCollectionItem`.
+pub fn process_set_collection_size(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let signer_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    let collection_mint = next_account_info(account_info_iter)?;
+    let pda_creator_info = next_account_info(account_info_iter)?;
+    let collection_info = next_account_info(account_info_iter)?;
+    let collection_metadata = next_account_info(account_info_iter)?;
+    let collection_authority_record = next_account_info(account_info_iter)?;
+    let metadata_program_info = next_account_info(account_info_iter)?;
+
+    assert_eq_pubkey(
+        metadata_program_info,
+        &mpl_token_metadata::programs::MPL_TOKEN_METADATA_ID,
+    )?;
+    assert_signer(signer_info)?;
+    assert_config(program_id, config_info)?;
+    assert_owned_by(config_info, program_id)?;
+    assert_owned_by(collection_info, program_id)?;
+    assert_collection(program_id, collection_mint, collection_info)?;
+    let pda_bump = assert_pda_creator(program_id, collection_mint, pda_creator_info)?;
+
+    //check authority
+    let config_data = ConfigureData::from_account_info(config_info)?;
+    // sized collections predate layout versioning, read them in either layout
+    let collection_data = CollectionData::from_any_layout(collection_info)?;
+    if config_data.authority != *signer_info.key && collection_data.admin != *signer_info.key {
+        return Err(AppError::InvalidAuthority.into());
+    }
+
+    let pda_seed = [
+        program_id.as_ref(),
+        collection_mint.key.as_ref(),
+        "pda_creator".as_bytes(),
+        &[pda_bump],
+    ];
+
+    msg!("set collection size {}", collection_data.max_supply);
+    let scs = SetCollectionSize {
+        collection_metadata: *collection_metadata.key,
+        collection_authority: *pda_creator_info.key,
+        collection_mint: *collection_mint.key,
+        collection_authority_record: Some(*collection_authority_record.key),
+    };
+    let scs_args = SetCollectionSizeInstructionArgs {
+        set_collection_size_args: SetCollectionSizeArgs {
+            size: collection_data.max_supply,
+        },
+    };
+    invoke_signed(
+        &scs.instruction(scs_args),
+        &[
+            collection_metadata.clone(),
+            pda_creator_info.clone(),
+            collection_mint.clone(),
+            collection_authority_record.clone(),
+            metadata_program_info.clone(),
+        ],
+        &[&pda_seed],
+    )?;
+
+    AppEvent::CollectionSizeSet {
+        collection: collection_data.collection_mint,
+        size: collection_data.max_supply,
+    }
+    .emit();
+
+    Ok(())
+}
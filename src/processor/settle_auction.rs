@@ -0,0 +1,154 @@
+use borsh::BorshSerialize;
+use mpl_token_metadata::types::PrintSupply;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    pubkey::Pubkey,
+};
+
+use crate::{error::AppError, event::AppEvent, processor::*, state::*, utils::*};
+
+/// Close an ended auction. The item is minted to the winner under the
+/// auction's name and uri and the winning bid paid to the auction's charge
+/// account; without bids nothing is minted. Anyone can settle, the signer
+/// pays for the item accounts and, for classic items, must be the mint
+/// authority of `mint` with its single token already in the winner's
+/// `token_account`. The auction account is closed to its creator.
+pub fn process_settle_auction(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let signer_info = next_account_info(account_info_iter)?;
+    let winner_info = next_account_info(account_info_iter)?;
+    let pda_creator_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    let token_account = next_account_info(account_info_iter)?;
+    let metadata_info = next_account_info(account_info_iter)?;
+    let edition_info = next_account_info(account_info_iter)?;
+    let collection_mint = next_account_info(account_info_iter)?;
+    let collection_metadata = next_account_info(account_info_iter)?;
+    let collection_master_edition_account = next_account_info(account_info_iter)?;
+    let collection_authority_record = next_account_info(account_info_iter)?;
+    let collection_info = next_account_info(account_info_iter)?;
+    let auction_info = next_account_info(account_info_iter)?;
+    let charge_info = next_account_info(account_info_iter)?;
+    let creator_info = next_account_info(account_info_iter)?;
+    let metadata_program_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+    let rent_info = next_account_info(account_info_iter)?;
+    let system_info = next_account_info(account_info_iter)?;
+
+    assert_signer(signer_info)?;
+    assert_token_program(token_program_info)?;
+    assert_rent_sysvar(rent_info)?;
+    assert_system_program(system_info)?;
+    assert_owned_by(collection_info, program_id)?;
+    assert_owned_by(auction_info, program_id)?;
+    assert_collection(program_id, collection_mint, collection_info)?;
+
+    let auction_data = AuctionData::from_account_info(auction_info)?;
+    assert_auction(program_id, collection_mint, auction_data.id, auction_info)?;
+    assert_charge_account(charge_info, &auction_data.char_addr)?;
+    assert_eq_pubkey(creator_info, &auction_data.creator)?;
+
+    //check auction state
+    if now_timestamp() < auction_data.end_ts {
+        return Err(AppError::AuctionNotEnded.into());
+    }
+
+    let mut collection_data = CollectionData::from_account_info(collection_info)?;
+    collection_data.auctions -= 1;
+    let winner = match auction_data.highest_bidder {
+        Some(winner) => winner,
+        None => {
+            collection_data.serialize(&mut *collection_info.try_borrow_mut_data()?)?;
+            close_account(auction_info, creator_info)?;
+            AppEvent::AuctionSettled {
+                collection: auction_data.collection,
+                auction: *auction_info.key,
+                mint: None,
+                winner: None,
+                price: 0,
+            }
+            .emit();
+            return Ok(());
+        }
+    };
+    assert_eq_pubkey(winner_info, &winner)?;
+
+    //the collection sold out while the auction ran, give the bid back
+    if collection_data.sale_left() == 0 {
+        transfer_lamports(auction_info, winner_info, auction_data.highest_bid)?;
+        collection_data.serialize(&mut *collection_info.try_borrow_mut_data()?)?;
+        close_account(auction_info, creator_info)?;
+        AppEvent::AuctionSettled {
+            collection: auction_data.collection,
+            auction: *auction_info.key,
+            mint: None,
+            winner: Some(winner),
+            price: 0,
+        }
+        .emit();
+        return Ok(());
+    }
+
+    assert_eq_pubkey(token_program_info, &collection_data.token_program)?;
+    let programmable = if collection_data.programmable {
+        Some(ProgrammableAccounts::from_iter(
+            account_info_iter,
+            &collection_data,
+        )?)
+    } else {
+        assert_owned_by(mint_info, token_program_info.key)?;
+        let token = unpack_token_account(token_account)?;
+        if token.owner != winner || token.mint != *mint_info.key || token.amount != 1 {
+            return Err(AppError::InvalidTokenAccount.into());
+        }
+        None
+    };
+
+    let pda_bump = assert_pda_creator(program_id, collection_mint, pda_creator_info)?;
+    let pda_seed = [
+        program_id.as_ref(),
+        collection_mint.key.as_ref(),
+        "pda_creator".as_bytes(),
+        &[pda_bump],
+    ];
+
+    let nft_accounts = MintNftAccounts {
+        payer: signer_info,
+        owner: winner_info,
+        pda_creator: pda_creator_info,
+        mint: mint_info,
+        token_account,
+        metadata: metadata_info,
+        edition: edition_info,
+        collection_mint,
+        collection_metadata,
+        collection_master_edition: collection_master_edition_account,
+        collection_authority_record,
+        metadata_program: metadata_program_info,
+        token_program: token_program_info,
+        rent: rent_info,
+        system: system_info,
+        programmable,
+    };
+    let mut item_data = collection_data.clone();
+    item_data.name = auction_data.name.clone();
+    item_data.uri = auction_data.uri.clone();
+    create_collection_item(&nft_accounts, &item_data, PrintSupply::Zero, &pda_seed)?;
+
+    transfer_lamports(auction_info, charge_info, auction_data.highest_bid)?;
+    collection_data.max_supply += 1;
+    collection_data.serialize(&mut *collection_info.try_borrow_mut_data()?)?;
+    close_account(auction_info, creator_info)?;
+
+    AppEvent::AuctionSettled {
+        collection: auction_data.collection,
+        auction: *auction_info.key,
+        mint: Some(*mint_info.key),
+        winner: Some(winner),
+        price: auction_data.highest_bid,
+    }
+    .emit();
+
+    Ok(())
+}
@@ -0,0 +1,86 @@
+use mpl_token_metadata::{accounts::Metadata, instructions::SignMetadata};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program::invoke,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+use crate::{error::AppError, event::AppEvent, state::*, utils::*};
+
+/// Verify the signing creator on every item whose metadata follows the fixed
+/// accounts. The signer must be one of the collection's creators, items it
+/// already verified are skipped.
+pub fn process_sign_metadata(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let signer_info = next_account_info(account_info_iter)?;
+    let collection_mint = next_account_info(account_info_iter)?;
+    let collection_info = next_account_info(account_info_iter)?;
+    let metadata_program_info = next_account_info(account_info_iter)?;
+
+    assert_signer(signer_info)?;
+    assert_owned_by(collection_info, program_id)?;
+    assert_collection(program_id, collection_mint, collection_info)?;
+    assert_eq_pubkey(metadata_program_info, &mpl_token_metadata::ID)?;
+
+    //check authority
+    let collection_data = CollectionData::from_account_info(collection_info)?;
+    if !collection_data
+        .creators
+        .iter()
+        .any(|creator| creator.address == *signer_info.key)
+    {
+        return Err(AppError::InvalidAuthority.into());
+    }
+
+    let mut items = vec![];
+    for metadata_info in account_info_iter {
+        assert_owned_by(metadata_info, &mpl_token_metadata::ID)?;
+        let metadata = Metadata::safe_deserialize(&metadata_info.data.borrow())
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+        if Metadata::find_pda(&metadata.mint).0 != *metadata_info.key {
+            return Err(AppError::InvalidDerivedKey.into());
+        }
+        match &metadata.collection {
+            Some(collection)
+                if collection.verified
+                    && collection.key == *collection_mint.key
+                    && metadata.update_authority == collection_data.pda_creator => {}
+            _ => return Err(AppError::NotCollectionItem.into()),
+        }
+        let verified = metadata
+            .creators
+            .iter()
+            .flatten()
+            .find(|creator| creator.address == *signer_info.key)
+            .ok_or(AppError::InvalidAuthority)?
+            .verified;
+        if verified {
+            continue;
+        }
+
+        invoke(
+            &SignMetadata {
+                metadata: *metadata_info.key,
+                creator: *signer_info.key,
+            }
+            .instruction(),
+            &[
+                metadata_info.clone(),
+                signer_info.clone(),
+                metadata_program_info.clone(),
+            ],
+        )?;
+        items.push(metadata.mint);
+    }
+
+    AppEvent::CreatorVerified {
+        collection: *collection_mint.key,
+        creator: *signer_info.key,
+        mints: items,
+    }
+    .emit();
+
+    Ok(())
+}
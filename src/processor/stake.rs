@@ -0,0 +1,123 @@
+use borsh::BorshSerialize;
+use mpl_token_metadata::{accounts::MasterEdition, instructions::FreezeDelegatedAccount};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program::invoke_signed,
+    pubkey::Pubkey,
+};
+
+use crate::{error::AppError, event::AppEvent, state::*, utils::*};
+
+/// Stake an item of a collection with a pool. The item stays in its owner's
+/// token account, delegated to the stake record and frozen through its
+/// edition until unstaked.
+pub fn process_stake(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let signer_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    let token_account = next_account_info(account_info_iter)?;
+    let metadata_info = next_account_info(account_info_iter)?;
+    let edition_info = next_account_info(account_info_iter)?;
+    let collection_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let stake_info = next_account_info(account_info_iter)?;
+    let metadata_program_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+    let rent_info = next_account_info(account_info_iter)?;
+    let system_info = next_account_info(account_info_iter)?;
+
+    assert_signer(signer_info)?;
+    assert_eq_pubkey(
+        metadata_program_info,
+        &mpl_token_metadata::programs::MPL_TOKEN_METADATA_ID,
+    )?;
+    assert_token_program(token_program_info)?;
+    assert_rent_sysvar(rent_info)?;
+    assert_system_program(system_info)?;
+    assert_owned_by(mint_info, token_program_info.key)?;
+    assert_owned_by(pool_info, program_id)?;
+    assert_eq_pubkey(edition_info, &MasterEdition::find_pda(mint_info.key).0)?;
+
+    assert_collection_item(program_id, mint_info, metadata_info, collection_info)?;
+    let collection_data = CollectionData::from_account_info(collection_info)?;
+    if collection_data.programmable {
+        return Err(AppError::ProgrammableUnsupported.into());
+    }
+    assert_pool(program_id, &collection_data.collection_mint, pool_info)?;
+    let mut pool_data = PoolData::from_account_info(pool_info)?;
+
+    let token = unpack_token_account(token_account)?;
+    if token.owner != *signer_info.key || token.mint != *mint_info.key || token.amount != 1 {
+        return Err(AppError::InvalidTokenAccount.into());
+    }
+    if !stake_info.data_is_empty() {
+        return Err(AppError::AlreadyInitialized.into());
+    }
+
+    let bump = assert_stake(program_id, mint_info, stake_info)?;
+    let stake_seeds = [
+        program_id.as_ref(),
+        mint_info.key.as_ref(),
+        "stake".as_bytes(),
+        &[bump],
+    ];
+    create_or_allocate_account_raw(
+        *program_id,
+        stake_info,
+        rent_info,
+        system_info,
+        signer_info,
+        StakeData::LEN,
+        &stake_seeds,
+    )?;
+
+    //delegate and freeze in place
+    spl_token_approve(
+        token_program_info,
+        token_account,
+        stake_info,
+        signer_info,
+        1,
+    )?;
+    let freeze = FreezeDelegatedAccount {
+        delegate: *stake_info.key,
+        token_account: *token_account.key,
+        edition: *edition_info.key,
+        mint: *mint_info.key,
+        token_program: *token_program_info.key,
+    };
+    invoke_signed(
+        &freeze.instruction(),
+        &[
+            stake_info.clone(),
+            token_account.clone(),
+            edition_info.clone(),
+            mint_info.clone(),
+            token_program_info.clone(),
+            metadata_program_info.clone(),
+        ],
+        &[&stake_seeds],
+    )?;
+
+    let now_ts = now_timestamp();
+    let stake_data = StakeData {
+        collection: collection_data.collection_mint,
+        mint: *mint_info.key,
+        owner: *signer_info.key,
+        staked_at: now_ts,
+        last_claim_ts: now_ts,
+    };
+    stake_data.serialize(&mut *stake_info.try_borrow_mut_data()?)?;
+    pool_data.staked += 1;
+    pool_data.serialize(&mut *pool_info.try_borrow_mut_data()?)?;
+
+    AppEvent::Staked {
+        collection: stake_data.collection,
+        mint: stake_data.mint,
+        owner: stake_data.owner,
+    }
+    .emit();
+
+    Ok(())
+}
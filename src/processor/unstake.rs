@@ -0,0 +1,111 @@
+use borsh::BorshSerialize;
+use mpl_token_metadata::{accounts::MasterEdition, instructions::ThawDelegatedAccount};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program::invoke_signed,
+    pubkey::Pubkey,
+};
+
+use crate::{error::AppError, event::AppEvent, processor::pay_rewards, state::*, utils::*};
+
+/// Unstake an item, paying its outstanding rewards, thawing it and revoking
+/// the delegate. Rewards the pool balance can't cover are forfeited. The
+/// stake record rent goes back to the owner.
+pub fn process_unstake(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let signer_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    let token_account = next_account_info(account_info_iter)?;
+    let edition_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let stake_info = next_account_info(account_info_iter)?;
+    let reward_mint = next_account_info(account_info_iter)?;
+    let mint_vault = next_account_info(account_info_iter)?;
+    let mint_auth = next_account_info(account_info_iter)?;
+    let reward_account = next_account_info(account_info_iter)?;
+    let metadata_program_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+    let reward_token_program_info = next_account_info(account_info_iter)?;
+
+    assert_signer(signer_info)?;
+    assert_eq_pubkey(
+        metadata_program_info,
+        &mpl_token_metadata::programs::MPL_TOKEN_METADATA_ID,
+    )?;
+    assert_token_program(token_program_info)?;
+    assert_owned_by(pool_info, program_id)?;
+    assert_owned_by(stake_info, program_id)?;
+    assert_eq_pubkey(edition_info, &MasterEdition::find_pda(mint_info.key).0)?;
+    let bump = assert_stake(program_id, mint_info, stake_info)?;
+
+    let mut stake_data = StakeData::from_account_info(stake_info)?;
+    if stake_data.owner != *signer_info.key {
+        return Err(AppError::InvalidOwner.into());
+    }
+    assert_pool(program_id, &stake_data.collection, pool_info)?;
+    let mut pool_data = PoolData::from_account_info(pool_info)?;
+
+    let amount = pay_rewards(
+        program_id,
+        &mut pool_data,
+        &mut stake_data,
+        reward_mint,
+        mint_vault,
+        mint_auth,
+        reward_account,
+        reward_token_program_info,
+    )?;
+    let forfeited = now_timestamp()
+        .saturating_sub(stake_data.last_claim_ts)
+        .saturating_mul(pool_data.reward_rate);
+
+    //thaw and revoke
+    let stake_seeds = [
+        program_id.as_ref(),
+        mint_info.key.as_ref(),
+        "stake".as_bytes(),
+        &[bump],
+    ];
+    let thaw = ThawDelegatedAccount {
+        delegate: *stake_info.key,
+        token_account: *token_account.key,
+        edition: *edition_info.key,
+        mint: *mint_info.key,
+        token_program: *token_program_info.key,
+    };
+    invoke_signed(
+        &thaw.instruction(),
+        &[
+            stake_info.clone(),
+            token_account.clone(),
+            edition_info.clone(),
+            mint_info.clone(),
+            token_program_info.clone(),
+            metadata_program_info.clone(),
+        ],
+        &[&stake_seeds],
+    )?;
+    spl_token_revoke(token_program_info, token_account, signer_info)?;
+
+    pool_data.staked -= 1;
+    pool_data.serialize(&mut *pool_info.try_borrow_mut_data()?)?;
+    close_account(stake_info, signer_info)?;
+
+    AppEvent::RewardsClaimed {
+        collection: stake_data.collection,
+        mint: stake_data.mint,
+        owner: stake_data.owner,
+        amount,
+    }
+    .emit();
+    AppEvent::Unstaked {
+        collection: stake_data.collection,
+        mint: stake_data.mint,
+        owner: stake_data.owner,
+        forfeited,
+    }
+    .emit();
+
+    Ok(())
+}
@@ -0,0 +1,82 @@
+use borsh::BorshSerialize;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    pubkey::Pubkey,
+};
+
+use crate::{error::AppError, event::AppEvent, state::*, utils::*};
+
+/// Change single fields of a collection, the counters and sale options set by
+/// `AddCollection` stay as they are.
+pub fn process_update_collection(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: UpdateCollectionArgs,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let signer_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    let collection_mint = next_account_info(account_info_iter)?;
+    let collection_info = next_account_info(account_info_iter)?;
+
+    assert_signer(signer_info)?;
+    assert_config(program_id, config_info)?;
+    assert_owned_by(config_info, program_id)?;
+    assert_owned_by(collection_info, program_id)?;
+    assert_collection(program_id, collection_mint, collection_info)?;
+
+    //check authority
+    let config_data = ConfigureData::from_account_info(config_info)?;
+    let mut collection_data = CollectionData::from_account_info(collection_info)?;
+    if config_data.authority != *signer_info.key && collection_data.admin != *signer_info.key {
+        return Err(AppError::InvalidAuthority.into());
+    }
+
+    if let Some(name) = &args.name {
+        collection_data.name = name.clone();
+    }
+    if let Some(symbol) = &args.symbol {
+        collection_data.symbol = symbol.clone();
+    }
+    if let Some(uri) = &args.uri {
+        collection_data.uri = uri.clone();
+    }
+    if let Some(fee) = args.fee {
+        collection_data.fee = fee;
+    }
+    if let Some(creators) = &args.creators {
+        assert_creators(creators, &collection_data.pda_creator)?;
+        collection_data.creators = creators.clone();
+    }
+    if let Some(admin) = args.admin {
+        collection_data.admin = admin;
+    }
+    if let Some(supply_cap) = args.supply_cap {
+        // minted and still reserved items must fit under the cap
+        let committed = collection_data
+            .max_supply
+            .checked_add(collection_data.reserve_left())
+            .ok_or(AppError::CheckedCalculateFailed)?;
+        if supply_cap == 0 || supply_cap > MAX_COLLECTION_SUPPLY || supply_cap < committed {
+            return Err(AppError::InvalidSupplyCap.into());
+        }
+        collection_data.supply_cap = supply_cap;
+    }
+    assert_item_metadata(
+        &collection_data.name,
+        &collection_data.symbol,
+        &collection_data.uri,
+        collection_data.fee,
+    )?;
+    collection_data.serialize(&mut *collection_info.try_borrow_mut_data()?)?;
+
+    AppEvent::CollectionUpdated {
+        collection: *collection_mint.key,
+        authority: *signer_info.key,
+        changes: args,
+    }
+    .emit();
+
+    Ok(())
+}
@@ -0,0 +1,178 @@
+use mpl_token_metadata::{
+    accounts::{MasterEdition, Metadata},
+    instructions::{
+        UpdateMetadataAccountV2, UpdateMetadataAccountV2InstructionArgs, UpdateV1,
+        UpdateV1InstructionArgs,
+    },
+    types::{
+        CollectionDetailsToggle, CollectionToggle, Data, DataV2, RuleSetToggle, TokenStandard,
+        UsesToggle,
+    },
+    MAX_URI_LENGTH,
+};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar,
+};
+
+use crate::{error::AppError, event::AppEvent, state::*, utils::*};
+
+/// Update an item's metadata as its update authority, the pda creator. Handing
+/// update authority to another key takes the item out of the factory for
+/// good, `GraduateCollection` does the same for the collection. Programmable
+/// items are updated with the metadata program's `Update` and take the item
+/// mint, its edition, the system program and the instructions sysvar after
+/// the fixed accounts.
+pub fn process_update_item(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: UpdateItemArgs,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let signer_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    let collection_mint = next_account_info(account_info_iter)?;
+    let collection_info = next_account_info(account_info_iter)?;
+    let pda_creator_info = next_account_info(account_info_iter)?;
+    let metadata_info = next_account_info(account_info_iter)?;
+    let metadata_program_info = next_account_info(account_info_iter)?;
+
+    assert_signer(signer_info)?;
+    assert_config(program_id, config_info)?;
+    assert_owned_by(config_info, program_id)?;
+    assert_owned_by(collection_info, program_id)?;
+    assert_collection(program_id, collection_mint, collection_info)?;
+    assert_eq_pubkey(metadata_program_info, &mpl_token_metadata::ID)?;
+    let pda_bump = assert_pda_creator(program_id, collection_mint, pda_creator_info)?;
+
+    //check authority
+    let config_data = ConfigureData::from_account_info(config_info)?;
+    let collection_data = CollectionData::from_account_info(collection_info)?;
+    if config_data.authority != *signer_info.key && collection_data.admin != *signer_info.key {
+        return Err(AppError::InvalidAuthority.into());
+    }
+
+    assert_owned_by(metadata_info, &mpl_token_metadata::ID)?;
+    let metadata = Metadata::safe_deserialize(&metadata_info.data.borrow())
+        .map_err(|_| ProgramError::InvalidAccountData)?;
+    if Metadata::find_pda(&metadata.mint).0 != *metadata_info.key {
+        return Err(AppError::InvalidDerivedKey.into());
+    }
+    match &metadata.collection {
+        Some(collection)
+            if collection.verified
+                && collection.key == *collection_mint.key
+                && metadata.update_authority == *pda_creator_info.key => {}
+        _ => return Err(AppError::NotCollectionItem.into()),
+    }
+    if let Some(uri) = &args.uri {
+        if uri.len() > MAX_URI_LENGTH {
+            return Err(AppError::UriTooLong.into());
+        }
+    }
+
+    let pda_seed = [
+        program_id.as_ref(),
+        collection_mint.key.as_ref(),
+        "pda_creator".as_bytes(),
+        &[pda_bump],
+    ];
+    if metadata.token_standard == Some(TokenStandard::ProgrammableNonFungible) {
+        // programmable items only take the metadata program's `Update`
+        let mint_info = next_account_info(account_info_iter)?;
+        let edition_info = next_account_info(account_info_iter)?;
+        let system_info = next_account_info(account_info_iter)?;
+        let sysvar_instructions = next_account_info(account_info_iter)?;
+        assert_eq_pubkey(mint_info, &metadata.mint)?;
+        assert_eq_pubkey(edition_info, &MasterEdition::find_pda(&metadata.mint).0)?;
+        assert_system_program(system_info)?;
+        assert_eq_pubkey(sysvar_instructions, &sysvar::instructions::id())?;
+
+        let data = args.uri.as_ref().map(|uri| Data {
+            name: metadata.name.trim_end_matches('\0').to_string(),
+            symbol: metadata.symbol.trim_end_matches('\0').to_string(),
+            uri: uri.clone(),
+            seller_fee_basis_points: metadata.seller_fee_basis_points,
+            creators: metadata.creators.clone(),
+        });
+        let update = UpdateV1 {
+            authority: *pda_creator_info.key,
+            delegate_record: None,
+            token: None,
+            mint: *mint_info.key,
+            metadata: *metadata_info.key,
+            edition: Some(*edition_info.key),
+            payer: *signer_info.key,
+            system_program: *system_info.key,
+            sysvar_instructions: *sysvar_instructions.key,
+            authorization_rules_program: None,
+            authorization_rules: None,
+        };
+        invoke_signed(
+            &update.instruction(UpdateV1InstructionArgs {
+                new_update_authority: args.update_authority,
+                data,
+                primary_sale_happened: args.primary_sale_happened,
+                is_mutable: None,
+                collection: CollectionToggle::None,
+                collection_details: CollectionDetailsToggle::None,
+                uses: UsesToggle::None,
+                rule_set: RuleSetToggle::None,
+                authorization_data: None,
+            }),
+            &[
+                pda_creator_info.clone(),
+                mint_info.clone(),
+                metadata_info.clone(),
+                edition_info.clone(),
+                signer_info.clone(),
+                system_info.clone(),
+                sysvar_instructions.clone(),
+                metadata_program_info.clone(),
+            ],
+            &[&pda_seed],
+        )?;
+    } else {
+        let data = args.uri.as_ref().map(|uri| DataV2 {
+            name: metadata.name.trim_end_matches('\0').to_string(),
+            symbol: metadata.symbol.trim_end_matches('\0').to_string(),
+            uri: uri.clone(),
+            seller_fee_basis_points: metadata.seller_fee_basis_points,
+            creators: metadata.creators.clone(),
+            collection: metadata.collection.clone(),
+            uses: metadata.uses.clone(),
+        });
+        invoke_signed(
+            &UpdateMetadataAccountV2 {
+                metadata: *metadata_info.key,
+                update_authority: *pda_creator_info.key,
+            }
+            .instruction(UpdateMetadataAccountV2InstructionArgs {
+                data,
+                new_update_authority: args.update_authority,
+                primary_sale_happened: args.primary_sale_happened,
+                is_mutable: None,
+            }),
+            &[
+                metadata_info.clone(),
+                pda_creator_info.clone(),
+                metadata_program_info.clone(),
+            ],
+            &[&pda_seed],
+        )?;
+    }
+
+    AppEvent::ItemUpdated {
+        collection: *collection_mint.key,
+        mint: metadata.mint,
+        authority: *signer_info.key,
+        changes: args,
+    }
+    .emit();
+
+    Ok(())
+}
@@ -1,17 +1,21 @@
 use borsh::{BorshDeserialize, BorshSerialize};
-use solana_program::{
-    account_info::AccountInfo,
-    borsh0_10::try_from_slice_unchecked,
-    program_error::ProgramError,
-    pubkey::Pubkey,
+use mpl_token_metadata::{
+    types::Creator, MAX_CREATOR_LIMIT, MAX_NAME_LENGTH, MAX_SYMBOL_LENGTH, MAX_URI_LENGTH,
 };
-use mpl_token_metadata::types::Creator;
+use solana_program::{account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey};
+use spl_token_2022::extension::ExtensionType;
+
+use crate::utils::try_from_slice_unchecked;
 
 #[repr(C)]
 #[derive(BorshSerialize, BorshDeserialize, Clone, Debug, Default, PartialEq)]
 pub struct ConfigureArgs {
     /// Contract admin
     pub authority: Pubkey,
+    /// marketplace fee in basis points of every sale after royalties, 0 for none
+    pub platform_fee: u16,
+    /// account receiving the marketplace fee
+    pub fee_recipient: Pubkey,
 }
 
 #[repr(C)]
@@ -19,10 +23,16 @@ pub struct ConfigureArgs {
 pub struct ConfigureData {
     /// Contract admin
     pub authority: Pubkey,
+    /// marketplace fee in basis points of every sale after royalties, 0 for none
+    pub platform_fee: u16,
+    /// account receiving the marketplace fee
+    pub fee_recipient: Pubkey,
 }
 
 impl ConfigureData {
-    pub const LEN: usize = 32;
+    pub const LEN: usize = 32 + 2 + 32;
+    /// config written before marketplace fees, the authority alone
+    pub const LEN_V0: usize = 32;
 
     pub fn from_account_info(a: &AccountInfo) -> Result<ConfigureData, ProgramError> {
         if a.data_len() != Self::LEN {
@@ -30,6 +40,26 @@ impl ConfigureData {
         }
         try_from_slice_unchecked(&a.data.borrow_mut()).map_err(|_| ProgramError::InvalidAccountData)
     }
+
+    /// Decode a config with or without the marketplace fee settings, an old
+    /// config charges no fee.
+    pub fn from_any_layout(a: &AccountInfo) -> Result<ConfigureData, ProgramError> {
+        if a.data_len() != Self::LEN_V0 {
+            return Self::from_account_info(a);
+        }
+        Self::from_v0(&a.data.borrow())
+    }
+
+    /// Decode the data of a config written before marketplace fees.
+    pub fn from_v0(data: &[u8]) -> Result<ConfigureData, ProgramError> {
+        let authority: Pubkey =
+            try_from_slice_unchecked(data).map_err(|_| ProgramError::InvalidAccountData)?;
+        Ok(ConfigureData {
+            authority,
+            platform_fee: 0,
+            fee_recipient: authority,
+        })
+    }
 }
 
 #[repr(C)]
@@ -45,13 +75,16 @@ pub struct CreateCollectionArgs {
     pub uri: String,
 }
 
-
 pub const MAX_CREATOR_LEN: usize = 32 + 1 + 1;
 
+/// Most creators a collection lists, every item also credits its pda creator
+/// within the metadata program's creator limit.
+pub const MAX_COLLECTION_CREATORS: usize = MAX_CREATOR_LIMIT - 1;
+
 #[repr(C)]
 #[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq)]
 pub struct AddCollectionArgs {
-    pub max_supply : u64,
+    pub max_supply: u64,
     pub collection_mint: Pubkey,
     pub admin: Pubkey,
     pub pda_creator: Pubkey,
@@ -66,13 +99,79 @@ pub struct AddCollectionArgs {
     /// default uri
     pub uri: String,
     pub ts: u64,
-
+    /// spl-token or spl-token-2022, owner of every mint in the collection
+    pub token_program: Pubkey,
+    /// mint ProgrammableNonFungible items so royalties are enforced on transfer
+    pub programmable: bool,
+    /// token-auth-rules rule set applied to programmable items
+    pub rule_set: Option<Pubkey>,
+    /// key that must co-sign every mint, e.g. a captcha or allowlist service
+    pub gatekeeper: Option<Pubkey>,
+    /// lamports charged instead of failing a mint before its phase, with a
+    /// used gate item or without the required tokens
+    pub bot_tax: Option<u64>,
+    /// items held back from the sale for `AdminMint`
+    pub reserve: u64,
+    /// reserved items minted so far
+    pub reserve_minted: u64,
+    /// most items the collection mints, `max_supply` counts the minted ones
+    pub supply_cap: u64,
+    /// items of the collection listed for sale
+    pub listings: u64,
+    /// auctions of the collection not settled yet
+    pub auctions: u64,
+    /// account layout, `LAYOUT_VERSION` once written by this program version
+    pub version: u8,
 }
 
 pub type CollectionData = AddCollectionArgs;
 
+/// Layout of collection, promotion and token accounts written by this
+/// program version. Accounts created before layouts were versioned are
+/// decoded with `from_any_layout` and resized when next written.
+pub const LAYOUT_VERSION: u8 = 1;
+
+/// Collection layout before versioning.
+#[derive(BorshDeserialize)]
+struct CollectionDataV0 {
+    max_supply: u64,
+    collection_mint: Pubkey,
+    admin: Pubkey,
+    pda_creator: Pubkey,
+    creators: Vec<Creator>,
+    fee: u16,
+    name: String,
+    symbol: String,
+    uri: String,
+    ts: u64,
+}
+
+impl CollectionDataV0 {
+    const LEN: usize = 8 + 32 + 32 + 32 + 34 * 4 + 1 + 4 + 32 + 10 + 200;
+}
+
 impl CollectionData {
-    pub const LEN: usize = 8 + 32 + 32 + 32 + 34 * 4 + 1 + 4 + 32 + 10 + 200;
+    pub const LEN: usize = 8
+        + 32 * 3
+        + (4 + MAX_CREATOR_LEN * MAX_COLLECTION_CREATORS)
+        + 2
+        + (4 + MAX_NAME_LENGTH)
+        + (4 + MAX_SYMBOL_LENGTH)
+        + (4 + MAX_URI_LENGTH)
+        + 8
+        + 32
+        + 1
+        + 33
+        + 33
+        + 9
+        + 8
+        + 8
+        + 8
+        + 8
+        + 8
+        + 1;
+    /// length of the unversioned layout
+    pub const LEN_V0: usize = CollectionDataV0::LEN;
 
     pub fn from_account_info(a: &AccountInfo) -> Result<CollectionData, ProgramError> {
         if a.data_len() != Self::LEN {
@@ -81,6 +180,55 @@ impl CollectionData {
         try_from_slice_unchecked(&a.data.borrow_mut()).map_err(|_| ProgramError::InvalidAccountData)
     }
 
+    /// Decode a collection in the current or the unversioned layout, the
+    /// fields added since take their defaults.
+    pub fn from_any_layout(a: &AccountInfo) -> Result<CollectionData, ProgramError> {
+        if a.data_len() != Self::LEN_V0 {
+            return Self::from_account_info(a);
+        }
+        Self::from_v0(&a.data.borrow())
+    }
+
+    /// Decode the data of an account in the unversioned layout.
+    pub fn from_v0(data: &[u8]) -> Result<CollectionData, ProgramError> {
+        let old: CollectionDataV0 =
+            try_from_slice_unchecked(data).map_err(|_| ProgramError::InvalidAccountData)?;
+        Ok(CollectionData {
+            max_supply: old.max_supply,
+            collection_mint: old.collection_mint,
+            admin: old.admin,
+            pda_creator: old.pda_creator,
+            creators: old.creators,
+            fee: old.fee,
+            name: old.name,
+            symbol: old.symbol,
+            uri: old.uri,
+            ts: old.ts,
+            token_program: spl_token::id(),
+            programmable: false,
+            rule_set: None,
+            gatekeeper: None,
+            bot_tax: None,
+            reserve: 0,
+            reserve_minted: 0,
+            supply_cap: MAX_COLLECTION_SUPPLY.max(old.max_supply),
+            listings: 0,
+            auctions: 0,
+            version: LAYOUT_VERSION,
+        })
+    }
+
+    /// Reserved items not minted yet, the sale can't mint into them.
+    pub fn reserve_left(&self) -> u64 {
+        self.reserve.saturating_sub(self.reserve_minted)
+    }
+
+    /// Items the sale, auctions and recipes can still mint, the reserve left
+    /// is held back for `AdminMint`.
+    pub fn sale_left(&self) -> u64 {
+        self.supply_cap
+            .saturating_sub(self.max_supply.saturating_add(self.reserve_left()))
+    }
 }
 
 #[repr(C)]
@@ -90,13 +238,60 @@ pub struct AddPromotionArgs {
     pub public_start_ts: u64,
     pub collection: Pubkey,
     pub char_addr: Pubkey,
+    /// hold mint proceeds in the collection treasury until the sale is
+    /// finalized, instead of paying `char_addr` directly
+    pub escrow: bool,
+    /// price decays from the sale start, `sale_price` is ignored when set
+    pub dutch: Option<DutchAuction>,
+    /// holders-only phase before `public_start_ts`
+    pub gate: Option<TokenGate>,
+    /// fungible token the buyer must hold, or burn instead of paying
+    pub token: Option<TokenRequirement>,
+    /// account layout, `LAYOUT_VERSION` once written by this program version
+    pub version: u8,
 }
 
 pub type PromotionData = AddPromotionArgs;
 
+/// Promotion layout before versioning.
+#[derive(BorshDeserialize)]
+struct PromotionDataV0 {
+    sale_price: u64,
+    public_start_ts: u64,
+    collection: Pubkey,
+    char_addr: Pubkey,
+}
+
+impl PromotionDataV0 {
+    const LEN: usize = 8 + 8 + 32 * 2;
+}
+
 impl PromotionData {
     // pub const LEN: usize = 8 * 9 + 4 + 32 * 3 + 32 * 100 + 4;
-    pub const LEN: usize = 8 + 8 + 32 * 2;
+    /// length of the unversioned layout
+    pub const LEN_V0: usize = PromotionDataV0::LEN;
+    pub const LEN: usize = 8
+        + 8
+        + 32 * 2
+        + 1
+        + (1 + DutchAuction::LEN)
+        + (1 + TokenGate::LEN)
+        + (1 + TokenRequirement::LEN)
+        + 1;
+
+    /// Price of a mint at `now_ts`, after the sale opened.
+    pub fn current_price(&self, now_ts: u64) -> u64 {
+        match &self.dutch {
+            Some(dutch) => {
+                let drops = now_ts.saturating_sub(self.public_start_ts) / dutch.interval;
+                dutch
+                    .start_price
+                    .saturating_sub(dutch.step.saturating_mul(drops))
+                    .max(dutch.floor_price)
+            }
+            None => self.sale_price,
+        }
+    }
 
     pub fn from_account_info(a: &AccountInfo) -> Result<PromotionData, ProgramError> {
         if a.data_len() != Self::LEN {
@@ -104,32 +299,223 @@ impl PromotionData {
         }
         try_from_slice_unchecked(&a.data.borrow_mut()).map_err(|_| ProgramError::InvalidAccountData)
     }
+
+    /// Decode a promotion in the current or the unversioned layout, the
+    /// fields added since take their defaults.
+    pub fn from_any_layout(a: &AccountInfo) -> Result<PromotionData, ProgramError> {
+        if a.data_len() != Self::LEN_V0 {
+            return Self::from_account_info(a);
+        }
+        Self::from_v0(&a.data.borrow())
+    }
+
+    /// Decode the data of an account in the unversioned layout.
+    pub fn from_v0(data: &[u8]) -> Result<PromotionData, ProgramError> {
+        let old: PromotionDataV0 =
+            try_from_slice_unchecked(data).map_err(|_| ProgramError::InvalidAccountData)?;
+        Ok(PromotionData {
+            sale_price: old.sale_price,
+            public_start_ts: old.public_start_ts,
+            collection: old.collection,
+            char_addr: old.char_addr,
+            escrow: false,
+            dutch: None,
+            gate: None,
+            token: None,
+            version: LAYOUT_VERSION,
+        })
+    }
 }
 
 #[repr(C)]
 #[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq)]
-pub struct CreateTokenArgs {
+pub struct DutchAuction {
+    pub start_price: u64,
+    /// the price never drops below this
+    pub floor_price: u64,
+    /// seconds between price drops
+    pub interval: u64,
+    /// lamports taken off the price at every drop
+    pub step: u64,
+    /// escrowed sales only: keep what buyers paid above the clearing price
+    /// in the treasury for them to claim back
+    pub rebate: bool,
+}
+
+impl DutchAuction {
+    pub const LEN: usize = 8 * 4 + 1;
+}
+
+/// A mint phase reserved to holders of an item verified into `collection`,
+/// from `start_ts` until the public sale opens. Each item of the gate
+/// collection mints once per phase.
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct TokenGate {
+    /// collection mint of the items granting access
+    pub collection: Pubkey,
+    /// phase number, gate items used in an earlier phase can mint again
+    pub phase: u64,
+    pub start_ts: u64,
+    /// discounted price, the promotion price when unset
+    pub price: Option<u64>,
+}
+
+impl TokenGate {
+    pub const LEN: usize = 32 + 8 + 8 + 9;
+}
+
+/// Fungible token condition of a promotion, `amount` in base units.
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq)]
+pub enum TokenRequirement {
+    /// the buyer must hold at least `amount`, the mint price is unchanged
+    Hold { mint: Pubkey, amount: u64 },
+    /// the buyer burns `amount` of a token created by `CreateToken` as the
+    /// mint price, no lamports are charged
+    Burn { mint: Pubkey, amount: u64 },
+}
+
+impl TokenRequirement {
+    pub const LEN: usize = 1 + 32 + 8;
+
+    pub fn mint(&self) -> &Pubkey {
+        match self {
+            TokenRequirement::Hold { mint, .. } | TokenRequirement::Burn { mint, .. } => mint,
+        }
+    }
+
+    pub fn amount(&self) -> u64 {
+        match self {
+            TokenRequirement::Hold { amount, .. } | TokenRequirement::Burn { amount, .. } => {
+                *amount
+            }
+        }
+    }
+}
+
+/// Last phase a gate item minted in.
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct GateUseData {
+    pub collection: Pubkey,
+    pub gate_mint: Pubkey,
+    pub phase: u64,
+}
+
+impl GateUseData {
+    pub const LEN: usize = 32 * 2 + 8;
+
+    pub fn from_account_info(a: &AccountInfo) -> Result<GateUseData, ProgramError> {
+        if a.data_len() != Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        try_from_slice_unchecked(&a.data.borrow_mut()).map_err(|_| ProgramError::InvalidAccountData)
+    }
+}
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct CreateEditionDropArgs {
+    /// number of prints allowed, None for unlimited
+    pub max_supply: Option<u64>,
+    /// master edition name
     pub name: String,
+    /// master edition symbol
     pub symbol: String,
+    /// master edition uri
     pub uri: String,
-    pub decimals: u8
 }
 
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct EditionDropData {
+    /// collection the master edition is verified into
+    pub collection: Pubkey,
+    pub master_mint: Pubkey,
+    /// number of prints allowed, None for unlimited
+    pub max_supply: Option<u64>,
+    /// prints sold so far, the next print is edition `minted + 1`
+    pub minted: u64,
+}
+
+impl EditionDropData {
+    pub const LEN: usize = 32 + 32 + 9 + 8;
+
+    pub fn from_account_info(a: &AccountInfo) -> Result<EditionDropData, ProgramError> {
+        if a.data_len() != Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        try_from_slice_unchecked(&a.data.borrow_mut()).map_err(|_| ProgramError::InvalidAccountData)
+    }
+}
+
+/// Token-2022 mint extensions, only valid when the token program is spl-token-2022
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq)]
+pub enum TokenExtension {
+    /// point the mint at its metaplex metadata account
+    MetadataPointer,
+    /// withhold a fee on every transfer, claimable by the mint authority
+    TransferFee { basis_points: u16, maximum_fee: u64 },
+    /// soulbound token, can be minted and burned but never transferred
+    NonTransferable,
+}
+
+impl TokenExtension {
+    pub fn extension_type(&self) -> ExtensionType {
+        match self {
+            TokenExtension::MetadataPointer => ExtensionType::MetadataPointer,
+            TokenExtension::TransferFee { .. } => ExtensionType::TransferFeeConfig,
+            TokenExtension::NonTransferable => ExtensionType::NonTransferable,
+        }
+    }
+}
 
 #[repr(C)]
 #[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq)]
-pub struct  TokenData {
+pub struct CreateTokenArgs {
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    pub decimals: u8,
+    pub extensions: Vec<TokenExtension>,
+}
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct TokenData {
     pub name: String,
     pub symbol: String,
     pub decimals: u8,
     pub mint: Pubkey,
     pub creator: Pubkey,
     pub supply: u64,
+    pub token_program: Pubkey,
+    /// account layout, `LAYOUT_VERSION` once written by this program version
+    pub version: u8,
+}
+
+/// Token layout before versioning.
+#[derive(BorshDeserialize)]
+struct TokenDataV0 {
+    name: String,
+    symbol: String,
+    decimals: u8,
+    mint: Pubkey,
+    creator: Pubkey,
+    supply: u64,
+}
+
+impl TokenDataV0 {
+    const LEN: usize = 32 + 10 + 1 + 32 + 32 + 8;
 }
 
 impl TokenData {
     // pub const LEN: usize = 8 * 9 + 4 + 32 * 3 + 32 * 100 + 4;
-    pub const LEN: usize = 32 + 10 + 1 + 32 + 32 + 8;
+    /// length of the unversioned layout
+    pub const LEN_V0: usize = TokenDataV0::LEN;
+    pub const LEN: usize = 32 + 10 + 1 + 32 + 32 + 8 + 32 + 1;
 
     pub fn from_account_info(a: &AccountInfo) -> Result<TokenData, ProgramError> {
         if a.data_len() != Self::LEN {
@@ -137,15 +523,38 @@ impl TokenData {
         }
         try_from_slice_unchecked(&a.data.borrow_mut()).map_err(|_| ProgramError::InvalidAccountData)
     }
-}
 
+    /// Decode a token in the current or the unversioned layout, the fields
+    /// added since take their defaults.
+    pub fn from_any_layout(a: &AccountInfo) -> Result<TokenData, ProgramError> {
+        if a.data_len() != Self::LEN_V0 {
+            return Self::from_account_info(a);
+        }
+        Self::from_v0(&a.data.borrow())
+    }
 
+    /// Decode the data of an account in the unversioned layout.
+    pub fn from_v0(data: &[u8]) -> Result<TokenData, ProgramError> {
+        let old: TokenDataV0 =
+            try_from_slice_unchecked(data).map_err(|_| ProgramError::InvalidAccountData)?;
+        Ok(TokenData {
+            name: old.name,
+            symbol: old.symbol,
+            decimals: old.decimals,
+            mint: old.mint,
+            creator: old.creator,
+            supply: old.supply,
+            token_program: spl_token::id(),
+            version: LAYOUT_VERSION,
+        })
+    }
+}
 
 #[repr(C)]
 #[derive(BorshSerialize, BorshDeserialize, Clone, Debug, Default, PartialEq)]
 pub struct UserData {
     pub minted: u16,
-    pub shots: u16
+    pub shots: u16,
 }
 
 impl UserData {
@@ -159,3 +568,388 @@ impl UserData {
     }
 }
 
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, PartialEq)]
+pub enum SaleStatus {
+    /// mints are accepted and their proceeds held
+    Open,
+    /// proceeds were paid out, no more mints
+    Finalized,
+    /// buyers can refund their items, no more mints
+    Cancelled,
+}
+
+/// Escrow of a collection's mint proceeds, the account itself holds the
+/// lamports on top of its rent.
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct TreasuryData {
+    pub collection: Pubkey,
+    pub status: SaleStatus,
+    /// proceeds currently held, after finalizing only the unclaimed rebates
+    pub total: u64,
+    /// items sold through the escrow
+    pub sold: u64,
+    /// price of the last sale, the lowest one for dutch auctions
+    pub clearing_price: u64,
+    /// set when finalizing kept the rebates in escrow
+    pub rebate: bool,
+}
+
+impl TreasuryData {
+    pub const LEN: usize = 32 + 1 + 8 * 3 + 1;
+
+    pub fn from_account_info(a: &AccountInfo) -> Result<TreasuryData, ProgramError> {
+        if a.data_len() != Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        try_from_slice_unchecked(&a.data.borrow_mut()).map_err(|_| ProgramError::InvalidAccountData)
+    }
+}
+
+/// Proof of an escrowed purchase, closed when the buyer is refunded.
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct ReceiptData {
+    pub collection: Pubkey,
+    pub mint: Pubkey,
+    pub buyer: Pubkey,
+    pub price: u64,
+}
+
+impl ReceiptData {
+    pub const LEN: usize = 32 * 3 + 8;
+
+    pub fn from_account_info(a: &AccountInfo) -> Result<ReceiptData, ProgramError> {
+        if a.data_len() != Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        try_from_slice_unchecked(&a.data.borrow_mut()).map_err(|_| ProgramError::InvalidAccountData)
+    }
+}
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct CreateAuctionArgs {
+    /// lot number, unique within the collection
+    pub id: u64,
+    /// item name
+    pub name: String,
+    /// item uri
+    pub uri: String,
+    /// lowest accepted bid
+    pub reserve_price: u64,
+    pub start_ts: u64,
+    pub end_ts: u64,
+    /// a bid placed closer than this many seconds to the end pushes the end
+    /// back to this many seconds from the bid
+    pub extension: u64,
+    /// how much a bid must beat the highest one by
+    pub min_increment: u64,
+    /// account receiving the winning bid
+    pub char_addr: Pubkey,
+}
+
+/// English auction of a single collection item, the account itself holds the
+/// highest bid on top of its rent.
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct AuctionData {
+    pub collection: Pubkey,
+    pub id: u64,
+    pub name: String,
+    pub uri: String,
+    pub reserve_price: u64,
+    pub start_ts: u64,
+    pub end_ts: u64,
+    pub extension: u64,
+    pub min_increment: u64,
+    pub char_addr: Pubkey,
+    pub highest_bidder: Option<Pubkey>,
+    pub highest_bid: u64,
+    /// signer of `CreateAuction`, gets the rent back on settlement
+    pub creator: Pubkey,
+}
+
+impl AuctionData {
+    pub const LEN: usize = 32 + 8 + 4 + 32 + 4 + 200 + 8 * 5 + 32 + 33 + 8 + 32;
+
+    pub fn from_account_info(a: &AccountInfo) -> Result<AuctionData, ProgramError> {
+        if a.data_len() != Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        try_from_slice_unchecked(&a.data.borrow_mut()).map_err(|_| ProgramError::InvalidAccountData)
+    }
+}
+
+/// Most items a single `MintMany` may mint. Each classic item costs about the
+/// compute of one `Mint`, and each adds four accounts and a mint signer, so
+/// three items fill both the compute budget and a legacy transaction.
+pub const MAX_MINT_COUNT: u8 = 3;
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct MintManyArgs {
+    /// items to mint, 1 to `MAX_MINT_COUNT`
+    pub count: u8,
+}
+
+/// Hard cap on the supply of every collection.
+pub const MAX_COLLECTION_SUPPLY: u64 = 10000;
+
+/// Collection fields to change, `None` keeps the current value. Metadata
+/// changes apply to items minted afterwards.
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct UpdateCollectionArgs {
+    pub name: Option<String>,
+    pub symbol: Option<String>,
+    pub uri: Option<String>,
+    pub fee: Option<u16>,
+    pub creators: Option<Vec<Creator>>,
+    pub admin: Option<Pubkey>,
+    /// new supply cap, not below the items minted and reserved
+    pub supply_cap: Option<u64>,
+}
+
+/// Item metadata changes, `None` keeps the current value.
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct UpdateItemArgs {
+    pub uri: Option<String>,
+    /// the metadata program only lets this flip to `true`
+    pub primary_sale_happened: Option<bool>,
+    /// new update authority, the factory can't update the item afterwards
+    pub update_authority: Option<Pubkey>,
+}
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct AdminMintArgs {
+    /// wallet receiving the item in its associated token account
+    pub recipient: Pubkey,
+}
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct PlaceBidArgs {
+    /// lamports bid, escrowed until outbid or settled
+    pub amount: u64,
+}
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct ListArgs {
+    /// asking price in lamports, royalties and platform fee included
+    pub price: u64,
+}
+
+/// A collection item for sale, the item itself sits in the listing vault.
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct ListingData {
+    pub seller: Pubkey,
+    pub mint: Pubkey,
+    pub collection: Pubkey,
+    pub price: u64,
+}
+
+impl ListingData {
+    pub const LEN: usize = 32 * 3 + 8;
+
+    pub fn from_account_info(a: &AccountInfo) -> Result<ListingData, ProgramError> {
+        if a.data_len() != Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        try_from_slice_unchecked(&a.data.borrow_mut()).map_err(|_| ProgramError::InvalidAccountData)
+    }
+}
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct BuyArgs {
+    /// price the buyer agreed to, guards against a relist at a higher price
+    pub price: u64,
+}
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct OfferArgs {
+    /// offered price in lamports, royalties and platform fee included
+    pub price: u64,
+}
+
+/// An offer on any item of a collection, the offered lamports sit in the
+/// offer account on top of its rent.
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct OfferData {
+    pub collection: Pubkey,
+    pub bidder: Pubkey,
+    pub price: u64,
+}
+
+impl OfferData {
+    pub const LEN: usize = 32 * 2 + 8;
+
+    pub fn from_account_info(a: &AccountInfo) -> Result<OfferData, ProgramError> {
+        if a.data_len() != Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        try_from_slice_unchecked(&a.data.borrow_mut()).map_err(|_| ProgramError::InvalidAccountData)
+    }
+}
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct AcceptOfferArgs {
+    /// price the seller agreed to, guards against a lowered offer
+    pub price: u64,
+}
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct CreatePoolArgs {
+    /// reward in base units of the reward token, per staked item per second
+    pub reward_rate: u64,
+}
+
+/// A staking pool of a collection, paying rewards from the `mint_vault` of a
+/// token created by `CreateToken`.
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct PoolData {
+    pub collection: Pubkey,
+    pub reward_mint: Pubkey,
+    pub reward_rate: u64,
+    /// items currently staked
+    pub staked: u64,
+    /// rewards funded and not claimed yet, claims are capped by it
+    pub balance: u64,
+}
+
+impl PoolData {
+    pub const LEN: usize = 32 * 2 + 8 * 3;
+
+    pub fn from_account_info(a: &AccountInfo) -> Result<PoolData, ProgramError> {
+        if a.data_len() != Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        try_from_slice_unchecked(&a.data.borrow_mut()).map_err(|_| ProgramError::InvalidAccountData)
+    }
+
+    /// Seconds of rewards payable to a stake last claimed at `last_claim_ts`,
+    /// and their amount. Seconds the balance can't cover stay unpaid.
+    pub fn payable(&self, last_claim_ts: u64, now_ts: u64) -> (u64, u64) {
+        let elapsed = now_ts.saturating_sub(last_claim_ts);
+        let seconds = elapsed.min(self.balance / self.reward_rate);
+        (seconds, seconds * self.reward_rate)
+    }
+}
+
+/// A staked item, frozen in its owner's token account until unstaked.
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct StakeData {
+    pub collection: Pubkey,
+    pub mint: Pubkey,
+    pub owner: Pubkey,
+    pub staked_at: u64,
+    /// rewards accrue from here
+    pub last_claim_ts: u64,
+}
+
+impl StakeData {
+    pub const LEN: usize = 32 * 3 + 8 * 2;
+
+    pub fn from_account_info(a: &AccountInfo) -> Result<StakeData, ProgramError> {
+        if a.data_len() != Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        try_from_slice_unchecked(&a.data.borrow_mut()).map_err(|_| ProgramError::InvalidAccountData)
+    }
+}
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct FundPoolArgs {
+    pub amount: u64,
+}
+
+/// Most items a single redemption may burn.
+pub const MAX_RECIPE_INPUTS: usize = 4;
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct RecipeInput {
+    /// collection mint the burned items must be verified into
+    pub collection: Pubkey,
+    pub quantity: u8,
+}
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq)]
+pub enum RecipeOutput {
+    /// mint an item of this collection to the redeemer
+    Collection(Pubkey),
+    /// record the redemption on chain, e.g. for a physical item
+    Receipt,
+}
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct CreateRecipeArgs {
+    pub id: u64,
+    pub inputs: Vec<RecipeInput>,
+    pub output: RecipeOutput,
+}
+
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct RecipeData {
+    pub id: u64,
+    pub inputs: Vec<RecipeInput>,
+    pub output: RecipeOutput,
+    /// redemptions so far, the next receipt is number `redeemed`
+    pub redeemed: u64,
+}
+
+impl RecipeData {
+    pub const LEN: usize = 8 + 4 + 33 * MAX_RECIPE_INPUTS + 33 + 8;
+
+    pub fn from_account_info(a: &AccountInfo) -> Result<RecipeData, ProgramError> {
+        if a.data_len() != Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        try_from_slice_unchecked(&a.data.borrow_mut()).map_err(|_| ProgramError::InvalidAccountData)
+    }
+
+    /// Number of items one redemption burns.
+    pub fn burns(&self) -> usize {
+        self.inputs
+            .iter()
+            .map(|input| input.quantity as usize)
+            .sum()
+    }
+}
+
+/// Proof of a redemption with a `Receipt` output.
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct RedemptionData {
+    pub recipe: Pubkey,
+    pub index: u64,
+    pub redeemer: Pubkey,
+    pub ts: u64,
+}
+
+impl RedemptionData {
+    pub const LEN: usize = 32 + 8 + 32 + 8;
+
+    pub fn from_account_info(a: &AccountInfo) -> Result<RedemptionData, ProgramError> {
+        if a.data_len() != Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        try_from_slice_unchecked(&a.data.borrow_mut()).map_err(|_| ProgramError::InvalidAccountData)
+    }
+}
@@ -7,12 +7,28 @@ use solana_program::{
     program_error::ProgramError,
     program_pack::Pack,
     pubkey::Pubkey,
-    system_instruction,
-    sysvar::{clock::Clock, rent::Rent, Sysvar},
+    system_instruction, system_program,
+    sysvar::{self, clock::Clock, rent::Rent, Sysvar},
+};
+use spl_token_2022::{
+    extension::{
+        metadata_pointer, transfer_fee, BaseStateWithExtensions, ExtensionType, StateWithExtensions,
+    },
+    state::{Account, Mint},
 };
 use std::io::Error;
 
-use crate::error::AppError;
+use crate::{
+    error::AppError,
+    state::{TokenExtension, MAX_COLLECTION_CREATORS},
+};
+use mpl_token_metadata::{
+    accounts::Metadata, types::Creator, MAX_NAME_LENGTH, MAX_SYMBOL_LENGTH, MAX_URI_LENGTH,
+};
+
+/// mpl-token-auth-rules program, evaluates the rule sets of programmable NFTs
+pub const MPL_TOKEN_AUTH_RULES_ID: Pubkey =
+    solana_program::pubkey!("auth9SigNpDKz4sJJ1DfCTuZrZNSAgh9sFD3rboVmgg");
 
 pub fn now_timestamp() -> u64 {
     Clock::get().unwrap().unix_timestamp as u64
@@ -26,25 +42,77 @@ pub fn assert_eq_pubkey(account_info: &AccountInfo, account: &Pubkey) -> Program
     }
 }
 
-pub fn assert_eq_pubkey_0(account_info: &AccountInfo, account: &Pubkey) -> ProgramResult {
-    if account_info.key != account {
-        Err(AppError::InvalidEqPubkey0.into())
+pub fn assert_rent_sysvar(account_info: &AccountInfo) -> ProgramResult {
+    if *account_info.key != sysvar::rent::id() {
+        Err(AppError::InvalidRentSysvar.into())
     } else {
         Ok(())
     }
 }
 
-pub fn assert_eq_pubkey_1(account_info: &AccountInfo, account: &Pubkey) -> ProgramResult {
-    if account_info.key != account {
-        Err(AppError::InvalidEqPubkey1.into())
+pub fn assert_system_program(account_info: &AccountInfo) -> ProgramResult {
+    if *account_info.key != system_program::id() {
+        Err(AppError::InvalidSystemProgram.into())
     } else {
         Ok(())
     }
 }
 
-pub fn assert_eq_pubkey_2(account_info: &AccountInfo, account: &Pubkey) -> ProgramResult {
-    if account_info.key != account {
-        Err(AppError::InvalidEqPubkey2.into())
+pub fn assert_charge_account(account_info: &AccountInfo, char_addr: &Pubkey) -> ProgramResult {
+    if account_info.key != char_addr {
+        Err(AppError::InvalidChargeAccount.into())
+    } else {
+        Ok(())
+    }
+}
+
+/// Collection creators share all royalties, the pda creator prepended to
+/// every item takes none and must not be listed again.
+pub fn assert_creators(creators: &[Creator], pda_creator: &Pubkey) -> ProgramResult {
+    if creators.len() > MAX_COLLECTION_CREATORS {
+        return Err(AppError::TooManyCreators.into());
+    }
+    for (i, creator) in creators.iter().enumerate() {
+        if creator.address == *pda_creator
+            || creators[..i]
+                .iter()
+                .any(|other| other.address == creator.address)
+        {
+            return Err(AppError::DuplicateCreator.into());
+        }
+    }
+    if creators
+        .iter()
+        .map(|creator| creator.share as u16)
+        .sum::<u16>()
+        != 100
+    {
+        return Err(AppError::InvalidCreatorShares.into());
+    }
+    Ok(())
+}
+
+/// Item metadata of a collection must fit the metadata program's limits.
+pub fn assert_item_metadata(name: &str, symbol: &str, uri: &str, fee: u16) -> ProgramResult {
+    if name.len() > MAX_NAME_LENGTH {
+        return Err(AppError::NameTooLong.into());
+    }
+    if symbol.len() > MAX_SYMBOL_LENGTH {
+        return Err(AppError::SymbolTooLong.into());
+    }
+    if uri.len() > MAX_URI_LENGTH {
+        return Err(AppError::UriTooLong.into());
+    }
+    if fee > 10000 {
+        return Err(AppError::InvalidFee.into());
+    }
+    Ok(())
+}
+
+pub fn assert_token_program(token_program_info: &AccountInfo) -> ProgramResult {
+    if *token_program_info.key != spl_token::id() && *token_program_info.key != spl_token_2022::id()
+    {
+        Err(AppError::InvalidTokenProgram.into())
     } else {
         Ok(())
     }
@@ -63,7 +131,7 @@ pub fn assert_derivation(
     account: &AccountInfo,
     path: &[&[u8]],
 ) -> Result<u8, ProgramError> {
-    let (key, bump) = Pubkey::find_program_address(&path, program_id);
+    let (key, bump) = Pubkey::find_program_address(path, program_id);
     if key != *account.key {
         return Err(AppError::InvalidDerivedKey.into());
     }
@@ -72,17 +140,29 @@ pub fn assert_derivation(
 
 pub fn assert_config(program_id: &Pubkey, account: &AccountInfo) -> Result<u8, ProgramError> {
     let path = &[program_id.as_ref(), "config".as_bytes()];
-    assert_derivation(&program_id, &account, path)
+    assert_derivation(program_id, account, path)
 }
 
-pub fn assert_token_info(program_id: &Pubkey,new_mint: &Pubkey, account: &AccountInfo) -> Result<u8, ProgramError> {
-    let path = &[program_id.as_ref(), new_mint.as_ref(), "token_info".as_bytes()];
-    assert_derivation(&program_id, &account, path)
+pub fn assert_token_info(
+    program_id: &Pubkey,
+    new_mint: &Pubkey,
+    account: &AccountInfo,
+) -> Result<u8, ProgramError> {
+    let path = &[
+        program_id.as_ref(),
+        new_mint.as_ref(),
+        "token_info".as_bytes(),
+    ];
+    assert_derivation(program_id, account, path)
 }
 
-pub fn assert_user_info(program_id: &Pubkey,user: &Pubkey, account: &AccountInfo) -> Result<u8, ProgramError> {
+pub fn assert_user_info(
+    program_id: &Pubkey,
+    user: &Pubkey,
+    account: &AccountInfo,
+) -> Result<u8, ProgramError> {
     let path = &[program_id.as_ref(), user.as_ref(), "user_info".as_bytes()];
-    assert_derivation(&program_id, &account, path)
+    assert_derivation(program_id, account, path)
 }
 
 pub fn assert_signer(account_info: &AccountInfo) -> ProgramResult {
@@ -103,7 +183,7 @@ pub fn assert_pda_creator(
         collection_mint.key.as_ref(),
         "pda_creator".as_bytes(),
     ];
-    assert_derivation(&program_id, &pda_creator_info, path)
+    assert_derivation(program_id, pda_creator_info, path)
 }
 
 pub fn assert_collection(
@@ -116,7 +196,215 @@ pub fn assert_collection(
         collection_mint.key.as_ref(),
         "collection".as_bytes(),
     ];
-    assert_derivation(&program_id, &collection_info, path)
+    assert_derivation(program_id, collection_info, path)
+}
+
+pub fn assert_treasury(
+    program_id: &Pubkey,
+    collection_mint: &AccountInfo,
+    treasury_info: &AccountInfo,
+) -> Result<u8, ProgramError> {
+    let path = &[
+        program_id.as_ref(),
+        collection_mint.key.as_ref(),
+        "treasury".as_bytes(),
+    ];
+    assert_derivation(program_id, treasury_info, path)
+}
+
+pub fn assert_auction(
+    program_id: &Pubkey,
+    collection_mint: &AccountInfo,
+    id: u64,
+    auction_info: &AccountInfo,
+) -> Result<u8, ProgramError> {
+    let path = &[
+        program_id.as_ref(),
+        collection_mint.key.as_ref(),
+        &id.to_le_bytes(),
+        "auction".as_bytes(),
+    ];
+    assert_derivation(program_id, auction_info, path)
+}
+
+pub fn assert_listing(
+    program_id: &Pubkey,
+    mint: &AccountInfo,
+    listing_info: &AccountInfo,
+) -> Result<u8, ProgramError> {
+    let path = &[program_id.as_ref(), mint.key.as_ref(), "listing".as_bytes()];
+    assert_derivation(program_id, listing_info, path)
+}
+
+pub fn assert_offer(
+    program_id: &Pubkey,
+    collection_mint: &Pubkey,
+    bidder: &Pubkey,
+    offer_info: &AccountInfo,
+) -> Result<u8, ProgramError> {
+    let path = &[
+        program_id.as_ref(),
+        collection_mint.as_ref(),
+        bidder.as_ref(),
+        "offer".as_bytes(),
+    ];
+    assert_derivation(program_id, offer_info, path)
+}
+
+pub fn assert_pool(
+    program_id: &Pubkey,
+    collection_mint: &Pubkey,
+    pool_info: &AccountInfo,
+) -> Result<u8, ProgramError> {
+    let path = &[
+        program_id.as_ref(),
+        collection_mint.as_ref(),
+        "pool".as_bytes(),
+    ];
+    assert_derivation(program_id, pool_info, path)
+}
+
+pub fn assert_stake(
+    program_id: &Pubkey,
+    mint: &AccountInfo,
+    stake_info: &AccountInfo,
+) -> Result<u8, ProgramError> {
+    let path = &[program_id.as_ref(), mint.key.as_ref(), "stake".as_bytes()];
+    assert_derivation(program_id, stake_info, path)
+}
+
+pub fn assert_recipe(
+    program_id: &Pubkey,
+    id: u64,
+    recipe_info: &AccountInfo,
+) -> Result<u8, ProgramError> {
+    let path = &[program_id.as_ref(), &id.to_le_bytes(), "recipe".as_bytes()];
+    assert_derivation(program_id, recipe_info, path)
+}
+
+pub fn assert_redemption(
+    program_id: &Pubkey,
+    recipe_info: &AccountInfo,
+    index: u64,
+    redemption_info: &AccountInfo,
+) -> Result<u8, ProgramError> {
+    let path = &[
+        program_id.as_ref(),
+        recipe_info.key.as_ref(),
+        &index.to_le_bytes(),
+        "redemption".as_bytes(),
+    ];
+    assert_derivation(program_id, redemption_info, path)
+}
+
+pub fn assert_gate_use(
+    program_id: &Pubkey,
+    collection_mint: &AccountInfo,
+    gate_mint: &AccountInfo,
+    gate_use_info: &AccountInfo,
+) -> Result<u8, ProgramError> {
+    let path = &[
+        program_id.as_ref(),
+        collection_mint.key.as_ref(),
+        gate_mint.key.as_ref(),
+        "gate".as_bytes(),
+    ];
+    assert_derivation(program_id, gate_use_info, path)
+}
+
+pub fn assert_listing_vault(
+    program_id: &Pubkey,
+    mint: &AccountInfo,
+    vault_info: &AccountInfo,
+) -> Result<u8, ProgramError> {
+    let path = &[
+        program_id.as_ref(),
+        mint.key.as_ref(),
+        "listing_vault".as_bytes(),
+    ];
+    assert_derivation(program_id, vault_info, path)
+}
+
+/// Check `metadata_info` is the metadata of `mint` and that the item is
+/// verified into `collection_mint`, which need not be a factory collection.
+pub fn assert_verified_item(
+    mint: &AccountInfo,
+    metadata_info: &AccountInfo,
+    collection_mint: &Pubkey,
+) -> Result<Metadata, ProgramError> {
+    assert_owned_by(metadata_info, &mpl_token_metadata::ID)?;
+    if Metadata::find_pda(mint.key).0 != *metadata_info.key {
+        return Err(AppError::InvalidDerivedKey.into());
+    }
+    let metadata = Metadata::safe_deserialize(&metadata_info.data.borrow())
+        .map_err(|_| ProgramError::InvalidAccountData)?;
+    match &metadata.collection {
+        Some(collection) if collection.verified && collection.key == *collection_mint => {
+            Ok(metadata)
+        }
+        _ => Err(AppError::NotCollectionItem.into()),
+    }
+}
+
+/// Check `metadata_info` is the metadata of `mint` and that the item is
+/// verified into the collection whose record is `collection_info`.
+pub fn assert_collection_item(
+    program_id: &Pubkey,
+    mint: &AccountInfo,
+    metadata_info: &AccountInfo,
+    collection_info: &AccountInfo,
+) -> Result<Metadata, ProgramError> {
+    assert_owned_by(metadata_info, &mpl_token_metadata::ID)?;
+    assert_owned_by(collection_info, program_id)?;
+    if Metadata::find_pda(mint.key).0 != *metadata_info.key {
+        return Err(AppError::InvalidDerivedKey.into());
+    }
+    let metadata = Metadata::safe_deserialize(&metadata_info.data.borrow())
+        .map_err(|_| ProgramError::InvalidAccountData)?;
+    match &metadata.collection {
+        Some(collection) if collection.verified => {
+            let (key, _) = Pubkey::find_program_address(
+                &[
+                    program_id.as_ref(),
+                    collection.key.as_ref(),
+                    "collection".as_bytes(),
+                ],
+                program_id,
+            );
+            if key != *collection_info.key {
+                return Err(AppError::NotCollectionItem.into());
+            }
+        }
+        _ => return Err(AppError::NotCollectionItem.into()),
+    }
+    Ok(metadata)
+}
+
+/// `bps` basis points of `amount`, rounded down.
+pub fn bps_of(amount: u64, bps: u16) -> u64 {
+    (amount as u128 * bps as u128 / 10000) as u64
+}
+
+pub fn assert_receipt(
+    program_id: &Pubkey,
+    mint: &AccountInfo,
+    receipt_info: &AccountInfo,
+) -> Result<u8, ProgramError> {
+    let path = &[program_id.as_ref(), mint.key.as_ref(), "receipt".as_bytes()];
+    assert_derivation(program_id, receipt_info, path)
+}
+
+pub fn assert_edition_drop(
+    program_id: &Pubkey,
+    master_mint: &AccountInfo,
+    edition_drop_info: &AccountInfo,
+) -> Result<u8, ProgramError> {
+    let path = &[
+        program_id.as_ref(),
+        master_mint.key.as_ref(),
+        "edition_drop".as_bytes(),
+    ];
+    assert_derivation(program_id, edition_drop_info, path)
 }
 
 pub fn assert_mint_vault(
@@ -129,7 +417,7 @@ pub fn assert_mint_vault(
         token.key.as_ref(),
         "mint_vault".as_bytes(),
     ];
-    assert_derivation(&program_id, &token_vault, path)
+    assert_derivation(program_id, token_vault, path)
 }
 
 pub fn assert_mint_authority(
@@ -142,7 +430,7 @@ pub fn assert_mint_authority(
         token.key.as_ref(),
         "mint_auth".as_bytes(),
     ];
-    assert_derivation(&program_id, &authority_info, path)
+    assert_derivation(program_id, authority_info, path)
 }
 
 pub struct TokenTransferParams<'a: 'b, 'b> {
@@ -164,22 +452,87 @@ pub struct TokenTransferParams<'a: 'b, 'b> {
 pub fn spl_token_transfer<'a>(
     token_program: AccountInfo<'a>,
     source: AccountInfo<'a>,
+    mint: AccountInfo<'a>,
     destination: AccountInfo<'a>,
     authority: AccountInfo<'a>,
     amount: u64,
+    decimals: u8,
     signer_seeds: &[&[u8]],
 ) -> Result<(), ProgramError> {
     invoke_signed(
-        &spl_token::instruction::transfer(
+        &spl_token_2022::instruction::transfer_checked(
             token_program.key,
             source.key,
+            mint.key,
             destination.key,
             authority.key,
             &[],
             amount,
+            decimals,
         )?,
-        &[source, destination, authority, token_program],
-        &[&signer_seeds],
+        &[source, mint, destination, authority, token_program],
+        &[signer_seeds],
+    )
+}
+
+pub fn spl_token_approve<'a>(
+    token_program: &AccountInfo<'a>,
+    source: &AccountInfo<'a>,
+    delegate: &AccountInfo<'a>,
+    owner: &AccountInfo<'a>,
+    amount: u64,
+) -> ProgramResult {
+    invoke(
+        &spl_token_2022::instruction::approve(
+            token_program.key,
+            source.key,
+            delegate.key,
+            owner.key,
+            &[],
+            amount,
+        )?,
+        &[
+            source.clone(),
+            delegate.clone(),
+            owner.clone(),
+            token_program.clone(),
+        ],
+    )
+}
+
+pub fn spl_token_revoke<'a>(
+    token_program: &AccountInfo<'a>,
+    source: &AccountInfo<'a>,
+    owner: &AccountInfo<'a>,
+) -> ProgramResult {
+    invoke(
+        &spl_token_2022::instruction::revoke(token_program.key, source.key, owner.key, &[])?,
+        &[source.clone(), owner.clone(), token_program.clone()],
+    )
+}
+
+pub fn spl_token_close_account<'a>(
+    token_program: &AccountInfo<'a>,
+    account: &AccountInfo<'a>,
+    destination: &AccountInfo<'a>,
+    authority: &AccountInfo<'a>,
+    signer_seeds: &[&[u8]],
+) -> ProgramResult {
+    invoke_signed(
+        &spl_token_2022::instruction::close_account(
+            token_program.key,
+            account.key,
+            destination.key,
+            authority.key,
+            &[],
+        )?,
+        &[
+            account.clone(),
+            destination.clone(),
+            authority.clone(),
+            token_program.clone(),
+        ],
+        &[signer_seeds],
     )
 }
 
@@ -202,7 +555,7 @@ pub fn create_or_allocate_account_raw<'a>(
     if required_lamports > 0 {
         msg!("Transfer {} lamports to the new account", required_lamports);
         invoke(
-            &system_instruction::transfer(&payer_info.key, new_account_info.key, required_lamports),
+            &system_instruction::transfer(payer_info.key, new_account_info.key, required_lamports),
             &[
                 payer_info.clone(),
                 new_account_info.clone(),
@@ -215,20 +568,78 @@ pub fn create_or_allocate_account_raw<'a>(
     invoke_signed(
         &system_instruction::allocate(new_account_info.key, size.try_into().unwrap()),
         &[new_account_info.clone(), system_program_info.clone()],
-        &[&signer_seeds],
+        &[signer_seeds],
     )?;
 
     msg!("Assign the account to the owning program");
     invoke_signed(
         &system_instruction::assign(new_account_info.key, &program_id),
         &[new_account_info.clone(), system_program_info.clone()],
-        &[&signer_seeds],
+        &[signer_seeds],
     )?;
     msg!("Completed assignation!");
 
     Ok(())
 }
 
+/// Resize a program owned account written in an older layout to `size`, the
+/// payer tops up the rent of the added bytes.
+pub fn resize_account<'a>(
+    account: &AccountInfo<'a>,
+    rent_sysvar_info: &AccountInfo<'a>,
+    system_program_info: &AccountInfo<'a>,
+    payer_info: &AccountInfo<'a>,
+    size: usize,
+) -> ProgramResult {
+    let rent = &Rent::from_account_info(rent_sysvar_info)?;
+    let required_lamports = rent
+        .minimum_balance(size)
+        .saturating_sub(account.lamports());
+    if required_lamports > 0 {
+        invoke(
+            &system_instruction::transfer(payer_info.key, account.key, required_lamports),
+            &[
+                payer_info.clone(),
+                account.clone(),
+                system_program_info.clone(),
+            ],
+        )?;
+    }
+    msg!("Resize the account to {} bytes", size);
+    account.realloc(size, true)
+}
+
+/// Move lamports out of an account owned by this program, which can debit
+/// it directly without a system program transfer.
+pub fn transfer_lamports(from: &AccountInfo, to: &AccountInfo, amount: u64) -> ProgramResult {
+    let from_lamports = from
+        .lamports()
+        .checked_sub(amount)
+        .ok_or(AppError::CheckedCalculateFailed)?;
+    let to_lamports = to
+        .lamports()
+        .checked_add(amount)
+        .ok_or(AppError::CheckedCalculateFailed)?;
+    **from.try_borrow_mut_lamports()? = from_lamports;
+    **to.try_borrow_mut_lamports()? = to_lamports;
+    Ok(())
+}
+
+/// Close a program owned account: move its lamports to `recipient`, wipe the
+/// data and hand it back to the system program, so topping it up again later
+/// in the same transaction can't revive the old state.
+pub fn close_account(account: &AccountInfo, recipient: &AccountInfo) -> ProgramResult {
+    if account.key == recipient.key {
+        return Err(AppError::InvalidEqPubkey.into());
+    }
+    transfer_lamports(account, recipient, account.lamports())?;
+
+    account.data.borrow_mut().fill(0);
+    account.realloc(0, false)?;
+    account.assign(&system_program::id());
+    Ok(())
+}
+
 #[inline(always)]
 pub fn spl_token_create_account<'a>(
     token_program: &AccountInfo<'a>,
@@ -240,8 +651,8 @@ pub fn spl_token_create_account<'a>(
     initialize_account_seeds: &[&[u8]], // when account is not a pda, is null
     rent_info: &AccountInfo<'a>,
 ) -> ProgramResult {
-    let size = spl_token::state::Account::LEN;
-    let rent = &Rent::from_account_info(&rent_info)?;
+    let size = token_account_len(token_program, mint_info)?;
+    let rent = &Rent::from_account_info(rent_info)?;
     let required_lamports = rent.minimum_balance(size);
 
     msg!("spl_token_create_account create");
@@ -259,7 +670,7 @@ pub fn spl_token_create_account<'a>(
 
     msg!("spl_token_create_account initialize");
     invoke_signed(
-        &spl_token::instruction::initialize_account(
+        &spl_token_2022::instruction::initialize_account(
             token_program.key,
             new_account.key,
             mint_info.key,
@@ -279,6 +690,44 @@ pub fn spl_token_create_account<'a>(
     Ok(())
 }
 
+/// Unpack a token account owned by either token program.
+pub fn unpack_token_account(account_info: &AccountInfo) -> Result<Account, ProgramError> {
+    assert_token_program_owner(account_info)?;
+    let data = account_info.try_borrow_data()?;
+    Ok(StateWithExtensions::<Account>::unpack(&data)?.base)
+}
+
+/// Unpack a mint owned by either token program.
+pub fn unpack_mint(mint_info: &AccountInfo) -> Result<Mint, ProgramError> {
+    assert_token_program_owner(mint_info)?;
+    let data = mint_info.try_borrow_data()?;
+    Ok(StateWithExtensions::<Mint>::unpack(&data)?.base)
+}
+
+fn assert_token_program_owner(account_info: &AccountInfo) -> ProgramResult {
+    if *account_info.owner != spl_token::id() && *account_info.owner != spl_token_2022::id() {
+        Err(AppError::InvalidOwner.into())
+    } else {
+        Ok(())
+    }
+}
+
+/// Size of a token account for `mint`, including the extensions token-2022
+/// requires on accounts of that mint.
+pub fn token_account_len(
+    token_program: &AccountInfo,
+    mint_info: &AccountInfo,
+) -> Result<usize, ProgramError> {
+    if *token_program.key == spl_token::id() {
+        return Ok(Account::LEN);
+    }
+    let mint_data = mint_info.try_borrow_data()?;
+    let mint = StateWithExtensions::<Mint>::unpack(&mint_data)?;
+    let account_extensions =
+        ExtensionType::get_required_init_account_extensions(&mint.get_extension_types()?);
+    ExtensionType::try_calculate_account_len::<Account>(&account_extensions)
+}
+
 pub fn try_from_slice_unchecked<T: BorshDeserialize>(data: &[u8]) -> Result<T, Error> {
     let mut data_mut = data;
     let result = T::deserialize(&mut data_mut)?;
@@ -288,20 +737,24 @@ pub fn try_from_slice_unchecked<T: BorshDeserialize>(data: &[u8]) -> Result<T, E
 pub fn spl_token_transfer_invoke<'a>(
     token_program: AccountInfo<'a>,
     source: AccountInfo<'a>,
+    mint: AccountInfo<'a>,
     destination: AccountInfo<'a>,
     authority: AccountInfo<'a>,
     amount: u64,
+    decimals: u8,
 ) -> Result<(), ProgramError> {
     invoke(
-        &spl_token::instruction::transfer(
+        &spl_token_2022::instruction::transfer_checked(
             token_program.key,
             source.key,
+            mint.key,
             destination.key,
             authority.key,
             &[],
             amount,
+            decimals,
         )?,
-        &[source, destination, authority, token_program],
+        &[source, mint, destination, authority, token_program],
     )
 }
 
@@ -315,9 +768,13 @@ pub fn spl_token_create_mint<'a>(
     initialize_mint_seeds: &[&[u8]], // when account is not a pda, is null
     rent_info: &AccountInfo<'a>,
     decimals: u8,
+    extensions: &[TokenExtension],
+    metadata: &Pubkey,
 ) -> Result<(), ProgramError> {
-    let size = spl_token::state::Account::LEN;
-    let rent = &Rent::from_account_info(&rent_info)?;
+    let extension_types: Vec<ExtensionType> =
+        extensions.iter().map(|e| e.extension_type()).collect();
+    let size = ExtensionType::try_calculate_account_len::<Mint>(&extension_types)?;
+    let rent = &Rent::from_account_info(rent_info)?;
     let required_lamports = rent.minimum_balance(size);
 
     msg!("spl_token_create_token create");
@@ -333,9 +790,42 @@ pub fn spl_token_create_mint<'a>(
         &[create_account_seeds],
     )?;
 
+    for extension in extensions.iter() {
+        msg!(
+            "spl_token_initialize extension {:?}",
+            extension.extension_type()
+        );
+        let ix = match extension {
+            TokenExtension::MetadataPointer => metadata_pointer::instruction::initialize(
+                token_program.key,
+                new_mint.key,
+                Some(*authority.key),
+                Some(*metadata),
+            )?,
+            TokenExtension::TransferFee {
+                basis_points,
+                maximum_fee,
+            } => transfer_fee::instruction::initialize_transfer_fee_config(
+                token_program.key,
+                new_mint.key,
+                Some(authority.key),
+                Some(authority.key),
+                *basis_points,
+                *maximum_fee,
+            )?,
+            TokenExtension::NonTransferable => {
+                spl_token_2022::instruction::initialize_non_transferable_mint(
+                    token_program.key,
+                    new_mint.key,
+                )?
+            }
+        };
+        invoke(&ix, &[new_mint.clone(), token_program.clone()])?;
+    }
+
     msg!("spl_token_initialize mint");
     invoke_signed(
-        &spl_token::instruction::initialize_mint(
+        &spl_token_2022::instruction::initialize_mint(
             token_program.key,
             new_mint.key,
             authority.key,
@@ -365,7 +855,7 @@ pub fn spl_token_mint_to<'a>(
 ) -> Result<(), ProgramError> {
     msg!("spl_token_mint_to mint");
     invoke_signed(
-        &spl_token::instruction::mint_to(
+        &spl_token_2022::instruction::mint_to(
             token_program.key,
             new_mint.key,
             token_account.key,
@@ -399,7 +889,7 @@ pub fn spl_token_burn<'a>(
 ) -> Result<(), ProgramError> {
     msg!("spl_token_mint_to mint");
     invoke_signed(
-        &spl_token::instruction::burn(
+        &spl_token_2022::instruction::burn(
             token_program.key,
             token_account.key,
             new_mint.key,
@@ -419,4 +909,4 @@ pub fn spl_token_burn<'a>(
 
     msg!("spl_token_burn success");
     Ok(())
-}
\ No newline at end of file
+}
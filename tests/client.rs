@@ -0,0 +1,435 @@
+#![cfg(feature = "client")]
+
+use borsh::BorshSerialize;
+use mpl_token_metadata::types::Creator;
+use nft_factory::{client::*, state::*};
+use solana_program::pubkey::Pubkey;
+
+fn fixture<T: BorshSerialize>(state: &T, len: usize) -> Vec<u8> {
+    let mut data = vec![0u8; len];
+    state.serialize(&mut &mut data[..]).unwrap();
+    data
+}
+
+fn collection(collection_mint: Pubkey) -> CollectionData {
+    CollectionData {
+        max_supply: 12,
+        collection_mint,
+        admin: Pubkey::new_unique(),
+        pda_creator: pda_creator_address(&nft_factory::id(), &collection_mint),
+        creators: vec![Creator {
+            address: Pubkey::new_unique(),
+            verified: false,
+            share: 100,
+        }],
+        fee: 500,
+        name: "Gen 1".to_string(),
+        symbol: "GEN".to_string(),
+        uri: "https://example.com/gen1.json".to_string(),
+        ts: 1_700_000_000,
+        token_program: spl_token::id(),
+        programmable: false,
+        rule_set: None,
+        gatekeeper: Some(Pubkey::new_unique()),
+        bot_tax: Some(10_000_000),
+        reserve: 50,
+        reserve_minted: 2,
+        supply_cap: 1_000,
+        listings: 3,
+        auctions: 1,
+        version: LAYOUT_VERSION,
+    }
+}
+
+#[test]
+fn classifies_every_account_type() {
+    let program_id = nft_factory::id();
+    let collection_mint = Pubkey::new_unique();
+    let wallet = Pubkey::new_unique();
+    let collection_data = collection(collection_mint);
+    let config_data = ConfigureData {
+        authority: Pubkey::new_unique(),
+        platform_fee: 250,
+        fee_recipient: Pubkey::new_unique(),
+    };
+    let token_data = TokenData {
+        name: "Gold".to_string(),
+        symbol: "GLD".to_string(),
+        decimals: 6,
+        mint: Pubkey::new_unique(),
+        creator: Pubkey::new_unique(),
+        supply: 1_000_000,
+        token_program: spl_token::id(),
+        version: LAYOUT_VERSION,
+    };
+    let treasury_data = TreasuryData {
+        collection: collection_mint,
+        status: SaleStatus::Open,
+        total: 5_000_000_000,
+        sold: 5,
+        clearing_price: 1_000_000_000,
+        rebate: false,
+    };
+    let auction_data = AuctionData {
+        collection: collection_mint,
+        id: 4,
+        name: "One of one".to_string(),
+        uri: "https://example.com/1.json".to_string(),
+        reserve_price: 1_000_000_000,
+        start_ts: 1_700_000_000,
+        end_ts: 1_700_086_400,
+        extension: 300,
+        min_increment: 100_000_000,
+        char_addr: Pubkey::new_unique(),
+        highest_bidder: Some(Pubkey::new_unique()),
+        highest_bid: 1_500_000_000,
+        creator: Pubkey::new_unique(),
+    };
+    let redemption_data = RedemptionData {
+        recipe: Pubkey::new_unique(),
+        index: 9,
+        redeemer: Pubkey::new_unique(),
+        ts: 1_700_000_000,
+    };
+    let promotion_data = PromotionData {
+        sale_price: 1_000_000_000,
+        public_start_ts: 1_700_000_000,
+        collection: collection_mint,
+        char_addr: Pubkey::new_unique(),
+        escrow: true,
+        dutch: Some(DutchAuction {
+            start_price: 2_000_000_000,
+            floor_price: 500_000_000,
+            interval: 600,
+            step: 100_000_000,
+            rebate: true,
+        }),
+        gate: Some(TokenGate {
+            collection: Pubkey::new_unique(),
+            phase: 1,
+            start_ts: 1_699_990_000,
+            price: Some(500_000_000),
+        }),
+        token: Some(TokenRequirement::Hold {
+            mint: Pubkey::new_unique(),
+            amount: 1_000,
+        }),
+        version: LAYOUT_VERSION,
+    };
+    let drop_data = EditionDropData {
+        collection: Pubkey::new_unique(),
+        master_mint: Pubkey::new_unique(),
+        max_supply: Some(100),
+        minted: 7,
+    };
+    let user_data = UserData {
+        minted: 2,
+        shots: 3,
+    };
+    let receipt_data = ReceiptData {
+        collection: collection_mint,
+        mint: Pubkey::new_unique(),
+        buyer: Pubkey::new_unique(),
+        price: 1_000_000_000,
+    };
+    let listing_data = ListingData {
+        seller: Pubkey::new_unique(),
+        mint: Pubkey::new_unique(),
+        collection: collection_mint,
+        price: 2_000_000_000,
+    };
+    let offer_data = OfferData {
+        collection: collection_mint,
+        bidder: Pubkey::new_unique(),
+        price: 3_000_000_000,
+    };
+    let pool_data = PoolData {
+        collection: collection_mint,
+        reward_mint: Pubkey::new_unique(),
+        reward_rate: 10,
+        staked: 1,
+        balance: 1_000_000,
+    };
+    let stake_data = StakeData {
+        collection: collection_mint,
+        mint: Pubkey::new_unique(),
+        owner: Pubkey::new_unique(),
+        staked_at: 1_700_000_000,
+        last_claim_ts: 1_700_000_600,
+    };
+    let recipe_data = RecipeData {
+        id: 7,
+        inputs: vec![RecipeInput {
+            collection: collection_mint,
+            quantity: 2,
+        }],
+        output: RecipeOutput::Collection(Pubkey::new_unique()),
+        redeemed: 3,
+    };
+    let gate_use_data = GateUseData {
+        collection: collection_mint,
+        gate_mint: Pubkey::new_unique(),
+        phase: 1,
+    };
+
+    let cases = vec![
+        (
+            config_address(&program_id),
+            fixture(&config_data, ConfigureData::LEN),
+            ProgramAccount::Configure(config_data),
+        ),
+        (
+            collection_address(&program_id, &collection_mint),
+            fixture(&collection_data, CollectionData::LEN),
+            ProgramAccount::Collection(collection_data),
+        ),
+        (
+            promotion_address(&program_id, &collection_mint),
+            fixture(&promotion_data, PromotionData::LEN),
+            ProgramAccount::Promotion(promotion_data),
+        ),
+        (
+            edition_drop_address(&program_id, &drop_data.master_mint),
+            fixture(&drop_data, EditionDropData::LEN),
+            ProgramAccount::EditionDrop(drop_data),
+        ),
+        (
+            token_info_address(&program_id, &token_data.mint),
+            fixture(&token_data, TokenData::LEN),
+            ProgramAccount::Token(token_data),
+        ),
+        (
+            treasury_address(&program_id, &collection_mint),
+            fixture(&treasury_data, TreasuryData::LEN),
+            ProgramAccount::Treasury(treasury_data),
+        ),
+        (
+            auction_address(&program_id, &collection_mint, auction_data.id),
+            fixture(&auction_data, AuctionData::LEN),
+            ProgramAccount::Auction(auction_data),
+        ),
+        (
+            user_address(&program_id, &wallet),
+            fixture(&user_data, UserData::LEN),
+            ProgramAccount::User(user_data),
+        ),
+        (
+            receipt_address(&program_id, &receipt_data.mint),
+            fixture(&receipt_data, ReceiptData::LEN),
+            ProgramAccount::Receipt(receipt_data),
+        ),
+        (
+            listing_address(&program_id, &listing_data.mint),
+            fixture(&listing_data, ListingData::LEN),
+            ProgramAccount::Listing(listing_data),
+        ),
+        (
+            offer_address(&program_id, &collection_mint, &offer_data.bidder),
+            fixture(&offer_data, OfferData::LEN),
+            ProgramAccount::Offer(offer_data),
+        ),
+        (
+            pool_address(&program_id, &collection_mint),
+            fixture(&pool_data, PoolData::LEN),
+            ProgramAccount::Pool(pool_data),
+        ),
+        (
+            stake_address(&program_id, &stake_data.mint),
+            fixture(&stake_data, StakeData::LEN),
+            ProgramAccount::Stake(stake_data),
+        ),
+        (
+            recipe_address(&program_id, recipe_data.id),
+            fixture(&recipe_data, RecipeData::LEN),
+            ProgramAccount::Recipe(recipe_data),
+        ),
+        (
+            redemption_address(&program_id, &redemption_data.recipe, redemption_data.index),
+            fixture(&redemption_data, RedemptionData::LEN),
+            ProgramAccount::Redemption(redemption_data),
+        ),
+        (
+            gate_use_address(&program_id, &collection_mint, &gate_use_data.gate_mint),
+            fixture(&gate_use_data, GateUseData::LEN),
+            ProgramAccount::GateUse(gate_use_data),
+        ),
+    ];
+    for (address, data, expected) in cases {
+        let kind = expected.kind();
+        assert_eq!(
+            classify(&program_id, &address, &data, &[wallet]),
+            Some(expected),
+            "{}",
+            kind
+        );
+    }
+}
+
+#[test]
+fn classifies_unmigrated_layouts() {
+    let program_id = nft_factory::id();
+    let collection_mint = Pubkey::new_unique();
+    let authority = Pubkey::new_unique();
+    let current = collection(collection_mint);
+    let old_collection = (
+        current.max_supply,
+        collection_mint,
+        current.admin,
+        current.pda_creator,
+        current.creators.clone(),
+        current.fee,
+        current.name.clone(),
+        current.symbol.clone(),
+        current.uri.clone(),
+        current.ts,
+    );
+    let charge = Pubkey::new_unique();
+    let token_mint = Pubkey::new_unique();
+    let creator = Pubkey::new_unique();
+    let old_token = (
+        "Gold".to_string(),
+        "GLD".to_string(),
+        6u8,
+        token_mint,
+        creator,
+        100u64,
+    );
+
+    let cases = vec![
+        (
+            config_address(&program_id),
+            fixture(&authority, ConfigureData::LEN_V0),
+            ProgramAccount::Configure(ConfigureData {
+                authority,
+                platform_fee: 0,
+                fee_recipient: authority,
+            }),
+        ),
+        (
+            collection_address(&program_id, &collection_mint),
+            fixture(&old_collection, CollectionData::LEN_V0),
+            ProgramAccount::Collection(CollectionData {
+                gatekeeper: None,
+                bot_tax: None,
+                reserve: 0,
+                reserve_minted: 0,
+                supply_cap: MAX_COLLECTION_SUPPLY,
+                listings: 0,
+                auctions: 0,
+                ..current.clone()
+            }),
+        ),
+        (
+            promotion_address(&program_id, &collection_mint),
+            fixture(
+                &(7u64, 1_700_000_000u64, collection_mint, charge),
+                PromotionData::LEN_V0,
+            ),
+            ProgramAccount::Promotion(PromotionData {
+                sale_price: 7,
+                public_start_ts: 1_700_000_000,
+                collection: collection_mint,
+                char_addr: charge,
+                escrow: false,
+                dutch: None,
+                gate: None,
+                token: None,
+                version: LAYOUT_VERSION,
+            }),
+        ),
+        (
+            token_info_address(&program_id, &token_mint),
+            fixture(&old_token, TokenData::LEN_V0),
+            ProgramAccount::Token(TokenData {
+                name: "Gold".to_string(),
+                symbol: "GLD".to_string(),
+                decimals: 6,
+                mint: token_mint,
+                creator,
+                supply: 100,
+                token_program: spl_token::id(),
+                version: LAYOUT_VERSION,
+            }),
+        ),
+    ];
+    for (address, data, expected) in cases {
+        let kind = expected.kind();
+        assert_eq!(
+            classify(&program_id, &address, &data, &[]),
+            Some(expected),
+            "{}",
+            kind
+        );
+    }
+}
+
+#[test]
+fn rejects_account_at_wrong_address() {
+    let program_id = nft_factory::id();
+    let data = fixture(&collection(Pubkey::new_unique()), CollectionData::LEN);
+    assert_eq!(
+        classify(&program_id, &Pubkey::new_unique(), &data, &[]),
+        None
+    );
+    assert_eq!(
+        classify(&program_id, &Pubkey::new_unique(), &[0u8; 7], &[]),
+        None
+    );
+
+    let wallet = Pubkey::new_unique();
+    let data = fixture(
+        &UserData {
+            minted: 1,
+            shots: 0,
+        },
+        UserData::LEN,
+    );
+    assert_eq!(
+        classify(&program_id, &Pubkey::new_unique(), &data, &[wallet]),
+        None
+    );
+}
+
+#[test]
+fn exports_json_lines() {
+    let program_id = nft_factory::id();
+    let collection_mint = Pubkey::new_unique();
+    let wallet = Pubkey::new_unique();
+    let user = UserData {
+        minted: 2,
+        shots: 3,
+    };
+    let accounts = vec![
+        (
+            collection_address(&program_id, &collection_mint),
+            fixture(&collection(collection_mint), CollectionData::LEN),
+        ),
+        (
+            user_address(&program_id, &wallet),
+            fixture(&user, UserData::LEN),
+        ),
+        (
+            user_address(&program_id, &Pubkey::new_unique()),
+            fixture(&user, UserData::LEN),
+        ),
+        (Pubkey::new_unique(), vec![1, 2, 3]),
+    ];
+
+    let mut out = Vec::new();
+    let written = export_json_lines(&program_id, &accounts, &[wallet], &mut out).unwrap();
+    assert_eq!(written, 2);
+
+    let lines: Vec<serde_json::Value> = String::from_utf8(out)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(lines[0]["type"], "collection");
+    assert_eq!(
+        lines[0]["data"]["collection_mint"],
+        collection_mint.to_string()
+    );
+    assert_eq!(lines[0]["data"]["minted"], 12);
+    assert_eq!(lines[1]["type"], "user");
+    assert_eq!(lines[1]["data"]["shots"], 3);
+}
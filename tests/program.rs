@@ -0,0 +1,586 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use borsh::BorshSerialize;
+use mpl_token_metadata::{types::Creator, MAX_NAME_LENGTH, MAX_SYMBOL_LENGTH, MAX_URI_LENGTH};
+use nft_factory::{
+    error::AppError,
+    event::{AppEvent, EVENT_TAG},
+    processor::{process_create_recipe, process_mint, SaleSplit},
+    state::*,
+    utils::*,
+};
+use solana_program::{
+    account_info::AccountInfo,
+    clock::Clock,
+    program_error::ProgramError,
+    program_pack::Pack,
+    program_stubs::{set_syscall_stubs, SyscallStubs},
+    pubkey::Pubkey,
+    system_program, sysvar,
+};
+use spl_token::state::{Account, AccountState};
+use std::sync::Once;
+
+const NOW: i64 = 1_700_000_000;
+
+struct Stubs;
+
+impl SyscallStubs for Stubs {
+    fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+        let clock = Clock {
+            unix_timestamp: NOW,
+            ..Clock::default()
+        };
+        unsafe { *(var_addr as *mut Clock) = clock };
+        0
+    }
+}
+
+fn stub_clock() {
+    static STUBS: Once = Once::new();
+    STUBS.call_once(|| {
+        set_syscall_stubs(Box::new(Stubs));
+    });
+}
+
+/// Backing storage of an `AccountInfo` handed to a processor.
+struct TestAccount {
+    key: Pubkey,
+    owner: Pubkey,
+    lamports: u64,
+    data: Vec<u8>,
+    is_signer: bool,
+}
+
+impl TestAccount {
+    fn new(key: Pubkey) -> TestAccount {
+        TestAccount {
+            key,
+            owner: system_program::id(),
+            lamports: 0,
+            data: vec![],
+            is_signer: false,
+        }
+    }
+
+    fn owned<T: BorshSerialize>(key: Pubkey, owner: Pubkey, state: &T, len: usize) -> TestAccount {
+        let mut data = vec![0u8; len];
+        state.serialize(&mut &mut data[..]).unwrap();
+        TestAccount {
+            key,
+            owner,
+            lamports: 1_000_000,
+            data,
+            is_signer: false,
+        }
+    }
+
+    fn signer(key: Pubkey) -> TestAccount {
+        TestAccount {
+            is_signer: true,
+            lamports: 1_000_000_000,
+            ..TestAccount::new(key)
+        }
+    }
+}
+
+fn infos(accounts: &mut [TestAccount]) -> Vec<AccountInfo<'_>> {
+    accounts
+        .iter_mut()
+        .map(|account| {
+            AccountInfo::new(
+                &account.key,
+                account.is_signer,
+                true,
+                &mut account.lamports,
+                &mut account.data,
+                &account.owner,
+                false,
+                0,
+            )
+        })
+        .collect()
+}
+
+fn creator(share: u8) -> Creator {
+    Creator {
+        address: Pubkey::new_unique(),
+        verified: false,
+        share,
+    }
+}
+
+fn promotion(dutch: Option<DutchAuction>) -> PromotionData {
+    PromotionData {
+        sale_price: 1_000,
+        public_start_ts: 100,
+        collection: Pubkey::new_unique(),
+        char_addr: Pubkey::new_unique(),
+        escrow: false,
+        dutch,
+        gate: None,
+        token: None,
+        version: LAYOUT_VERSION,
+    }
+}
+
+fn collection(creators: Vec<Creator>, fee: u16) -> CollectionData {
+    CollectionData {
+        max_supply: 0,
+        collection_mint: Pubkey::new_unique(),
+        admin: Pubkey::new_unique(),
+        pda_creator: Pubkey::new_unique(),
+        creators,
+        fee,
+        name: "Gen 1".to_string(),
+        symbol: "GEN".to_string(),
+        uri: "https://example.com/gen1.json".to_string(),
+        ts: 0,
+        token_program: spl_token::id(),
+        programmable: false,
+        rule_set: None,
+        gatekeeper: None,
+        bot_tax: None,
+        reserve: 0,
+        reserve_minted: 0,
+        supply_cap: 100,
+        listings: 0,
+        auctions: 0,
+        version: LAYOUT_VERSION,
+    }
+}
+
+fn err(error: AppError) -> Result<(), ProgramError> {
+    Err(error.into())
+}
+
+#[test]
+fn creators_must_be_unique_and_share_everything() {
+    let pda_creator = Pubkey::new_unique();
+    assert_eq!(
+        assert_creators(&[creator(60), creator(40)], &pda_creator),
+        Ok(())
+    );
+
+    let too_many: Vec<Creator> = (0..=MAX_COLLECTION_CREATORS).map(|_| creator(1)).collect();
+    assert_eq!(
+        assert_creators(&too_many, &pda_creator),
+        err(AppError::TooManyCreators)
+    );
+
+    let first = creator(50);
+    let duplicate = [first.clone(), first];
+    assert_eq!(
+        assert_creators(&duplicate, &pda_creator),
+        err(AppError::DuplicateCreator)
+    );
+
+    let mut listed = creator(50);
+    listed.address = pda_creator;
+    assert_eq!(
+        assert_creators(&[creator(50), listed], &pda_creator),
+        err(AppError::DuplicateCreator)
+    );
+
+    assert_eq!(
+        assert_creators(&[creator(60), creator(30)], &pda_creator),
+        err(AppError::InvalidCreatorShares)
+    );
+    assert_eq!(
+        assert_creators(&[creator(100), creator(100)], &pda_creator),
+        err(AppError::InvalidCreatorShares)
+    );
+}
+
+#[test]
+fn item_metadata_fits_metadata_limits() {
+    let name = "a".repeat(MAX_NAME_LENGTH);
+    let symbol = "a".repeat(MAX_SYMBOL_LENGTH);
+    let uri = "a".repeat(MAX_URI_LENGTH);
+    assert_eq!(assert_item_metadata(&name, &symbol, &uri, 10000), Ok(()));
+
+    let long = "a".repeat(MAX_URI_LENGTH + 1);
+    assert_eq!(
+        assert_item_metadata(&long, &symbol, &uri, 0),
+        err(AppError::NameTooLong)
+    );
+    assert_eq!(
+        assert_item_metadata(&name, &long, &uri, 0),
+        err(AppError::SymbolTooLong)
+    );
+    assert_eq!(
+        assert_item_metadata(&name, &symbol, &long, 0),
+        err(AppError::UriTooLong)
+    );
+    assert_eq!(
+        assert_item_metadata(&name, &symbol, &uri, 10001),
+        err(AppError::InvalidFee)
+    );
+}
+
+#[test]
+fn fixed_price_does_not_move() {
+    let promotion = promotion(None);
+    assert_eq!(promotion.current_price(0), 1_000);
+    assert_eq!(promotion.current_price(u64::MAX), 1_000);
+}
+
+#[test]
+fn dutch_price_drops_to_the_floor() {
+    let promotion = promotion(Some(DutchAuction {
+        start_price: 1_000,
+        floor_price: 300,
+        interval: 60,
+        step: 200,
+        rebate: false,
+    }));
+    // before the sale and within the first interval
+    assert_eq!(promotion.current_price(0), 1_000);
+    assert_eq!(promotion.current_price(159), 1_000);
+    assert_eq!(promotion.current_price(160), 800);
+    assert_eq!(promotion.current_price(220), 600);
+    assert_eq!(promotion.current_price(280), 400);
+    assert_eq!(promotion.current_price(340), 300);
+    assert_eq!(promotion.current_price(u64::MAX), 300);
+}
+
+#[test]
+fn bps_rounds_down_without_overflow() {
+    assert_eq!(bps_of(10_000, 250), 250);
+    assert_eq!(bps_of(999, 100), 9);
+    assert_eq!(bps_of(1_000, 0), 0);
+    assert_eq!(bps_of(1_000, 10000), 1_000);
+    assert_eq!(bps_of(u64::MAX, 10000), u64::MAX);
+    assert_eq!(bps_of(u64::MAX, 5000), u64::MAX / 2);
+}
+
+#[test]
+fn events_round_trip_through_logs() {
+    let event = AppEvent::PoolClosed {
+        collection: Pubkey::new_unique(),
+        authority: Pubkey::new_unique(),
+        withdrawn: 42,
+    };
+    let data = event.try_to_vec().unwrap();
+    assert_eq!(AppEvent::decode(&[&EVENT_TAG, &data]), Some(event.clone()));
+    assert_eq!(AppEvent::decode(&[b"othertag", &data]), None);
+    assert_eq!(AppEvent::decode(&[&EVENT_TAG]), None);
+
+    let line = format!(
+        "Program data: {} {}",
+        STANDARD.encode(EVENT_TAG),
+        STANDARD.encode(&data)
+    );
+    assert_eq!(AppEvent::from_log(&line), Some(event.clone()));
+    assert_eq!(
+        AppEvent::from_log("Program log: Instruction: ClosePool"),
+        None
+    );
+    assert_eq!(AppEvent::from_log("Program data: not-base64!"), None);
+
+    let program = nft_factory::id().to_string();
+    let other = Pubkey::new_unique().to_string();
+    let logs = vec![
+        format!("Program {} invoke [1]", program),
+        "Program log: Instruction: ClosePool".to_string(),
+        line.clone(),
+        // an event logged by a program invoked from ours is not ours
+        format!("Program {} invoke [2]", other),
+        line.clone(),
+        format!("Program {} success", other),
+        format!("Program {} consumed 5000 of 200000 compute units", program),
+        format!("Program {} success", program),
+        // nor is one logged by another instruction of the transaction
+        format!("Program {} invoke [1]", other),
+        line.clone(),
+        format!("Program {} failed: custom program error: 0x1", other),
+        line,
+    ];
+    assert_eq!(AppEvent::parse_logs(&logs), vec![event]);
+}
+
+#[test]
+fn errors_decode_from_their_code() {
+    assert_eq!(AppError::from_code(0xfa01), Some(AppError::InvalidSigner));
+    assert_eq!(AppError::from_code(0xfa3b), Some(AppError::CollectionInUse));
+    assert_eq!(
+        AppError::from_code(AppError::PoolHasStakes as u32),
+        Some(AppError::PoolHasStakes)
+    );
+    assert_eq!(AppError::from_code(0), None);
+    assert_eq!(AppError::from_code(0xfa00), None);
+
+    let error: ProgramError = AppError::SaleActive.into();
+    assert_eq!(
+        AppError::from_program_error(&error),
+        Some(AppError::SaleActive)
+    );
+    assert_eq!(
+        AppError::from_program_error(&ProgramError::InvalidAccountData),
+        None
+    );
+}
+
+#[test]
+fn offer_price_splits_into_royalties_fee_and_proceeds() {
+    let creators = vec![creator(70), creator(30)];
+    let collection_data = collection(creators.clone(), 500);
+    let split = SaleSplit::new(&collection_data, 1_000_000, 250).unwrap();
+    assert_eq!(
+        split.royalties,
+        vec![(creators[0].address, 35_000), (creators[1].address, 15_000)]
+    );
+    assert_eq!(split.total_royalties(), 50_000);
+    // the platform fee comes out of what the royalties leave
+    assert_eq!(split.platform_fee, 23_750);
+    assert_eq!(split.proceeds, 926_250);
+
+    // shares round down, the dust stays with the seller
+    let split = SaleSplit::new(&collection(vec![creator(33), creator(67)], 10000), 10, 0).unwrap();
+    assert_eq!(split.total_royalties(), 9);
+    assert_eq!(split.proceeds, 1);
+
+    // full royalties still sell, with nothing left for the platform or seller
+    let creators = vec![creator(100)];
+    let split = SaleSplit::new(&collection(creators.clone(), 10000), 10_000, 250).unwrap();
+    assert_eq!(split.royalties, vec![(creators[0].address, 10_000)]);
+    assert_eq!(split.platform_fee, 0);
+    assert_eq!(split.proceeds, 0);
+}
+
+#[test]
+fn rewards_accrue_up_to_the_pool_balance() {
+    let mut pool = PoolData {
+        collection: Pubkey::new_unique(),
+        reward_mint: Pubkey::new_unique(),
+        reward_rate: 10,
+        staked: 1,
+        balance: 1_000,
+    };
+    assert_eq!(pool.payable(100, 100), (0, 0));
+    assert_eq!(pool.payable(100, 160), (60, 600));
+    assert_eq!(pool.payable(200, 100), (0, 0));
+    // only the seconds the balance covers are paid, the rest stays claimable
+    assert_eq!(pool.payable(100, 1_000), (100, 1_000));
+
+    pool.balance = 1_005;
+    assert_eq!(pool.payable(0, 1_000), (100, 1_000));
+    pool.balance = 9;
+    assert_eq!(pool.payable(0, 1_000), (0, 0));
+}
+
+#[test]
+fn recipe_burns_every_input() {
+    let input = |quantity| RecipeInput {
+        collection: Pubkey::new_unique(),
+        quantity,
+    };
+    let mut recipe = RecipeData {
+        id: 1,
+        inputs: vec![],
+        output: RecipeOutput::Receipt,
+        redeemed: 0,
+    };
+    assert_eq!(recipe.burns(), 0);
+    recipe.inputs = vec![input(2), input(1), input(3)];
+    assert_eq!(recipe.burns(), 6);
+    recipe.inputs = vec![input(u8::MAX), input(u8::MAX)];
+    assert_eq!(recipe.burns(), 510);
+}
+
+fn token_account(owner: Pubkey, mint: Pubkey, amount: u64) -> TestAccount {
+    let mut data = vec![0u8; Account::LEN];
+    Account {
+        mint,
+        owner,
+        amount,
+        state: AccountState::Initialized,
+        ..Account::default()
+    }
+    .pack_into_slice(&mut data);
+    TestAccount {
+        owner: spl_token::id(),
+        lamports: 1_000_000,
+        data,
+        ..TestAccount::new(Pubkey::new_unique())
+    }
+}
+
+/// Accounts of a single mint from a sale requiring `requirement`, up to and
+/// including the token requirement accounts. The buyer holds `held` tokens.
+fn mint_accounts(requirement: TokenRequirement, held: u64) -> Vec<TestAccount> {
+    let program_id = nft_factory::id();
+    let buyer = Pubkey::new_unique();
+    let collection_mint = Pubkey::new_unique();
+    let collection_info = Pubkey::find_program_address(
+        &[
+            program_id.as_ref(),
+            collection_mint.as_ref(),
+            "collection".as_bytes(),
+        ],
+        &program_id,
+    )
+    .0;
+    let promotion_info = Pubkey::find_program_address(
+        &[program_id.as_ref(), collection_info.as_ref()],
+        &program_id,
+    )
+    .0;
+    let charge = Pubkey::new_unique();
+    let token_mint = *requirement.mint();
+
+    let mut collection_data = collection(vec![creator(100)], 500);
+    collection_data.collection_mint = collection_mint;
+    let promotion_data = PromotionData {
+        sale_price: 0,
+        public_start_ts: 0,
+        collection: collection_mint,
+        char_addr: charge,
+        token: Some(requirement),
+        ..promotion(None)
+    };
+
+    let mut accounts = vec![TestAccount::signer(buyer)];
+    // pda creator, item mint, token account, metadata, edition, collection
+    // mint, collection metadata, collection edition, authority record
+    accounts.extend((0..9).map(|_| TestAccount::new(Pubkey::new_unique())));
+    accounts[6].key = collection_mint;
+    accounts.push(TestAccount::owned(
+        promotion_info,
+        program_id,
+        &promotion_data,
+        PromotionData::LEN,
+    ));
+    accounts.push(TestAccount::owned(
+        collection_info,
+        program_id,
+        &collection_data,
+        CollectionData::LEN,
+    ));
+    accounts.push(TestAccount::new(charge));
+    accounts.push(TestAccount::new(Pubkey::new_unique()));
+    accounts.push(TestAccount::new(mpl_token_metadata::ID));
+    accounts.push(TestAccount::new(spl_token::id()));
+    accounts.push(TestAccount::new(sysvar::rent::id()));
+    accounts.push(TestAccount::new(system_program::id()));
+    accounts.push(TestAccount {
+        owner: spl_token::id(),
+        ..TestAccount::new(token_mint)
+    });
+    accounts.push(token_account(buyer, token_mint, held));
+    accounts.push(TestAccount::new(spl_token::id()));
+    accounts
+}
+
+#[test]
+fn token_phase_rejects_mint_without_the_token() {
+    stub_clock();
+    let program_id = nft_factory::id();
+    for requirement in [
+        TokenRequirement::Hold {
+            mint: Pubkey::new_unique(),
+            amount: 5,
+        },
+        TokenRequirement::Burn {
+            mint: Pubkey::new_unique(),
+            amount: 5,
+        },
+    ] {
+        let mut accounts = mint_accounts(requirement.clone(), 0);
+        assert_eq!(
+            process_mint(&program_id, &infos(&mut accounts)),
+            err(AppError::InsufficientTokens)
+        );
+
+        let mut accounts = mint_accounts(requirement, 4);
+        assert_eq!(
+            process_mint(&program_id, &infos(&mut accounts)),
+            err(AppError::InsufficientTokens)
+        );
+    }
+}
+
+#[test]
+fn mint_rejects_a_promotion_of_another_collection() {
+    stub_clock();
+    let program_id = nft_factory::id();
+    let requirement = TokenRequirement::Hold {
+        mint: Pubkey::new_unique(),
+        amount: 5,
+    };
+    // a promotion without the requirement, made by the buyer
+    let mut accounts = mint_accounts(requirement, 0);
+    let mut decoy: PromotionData = try_from_slice_unchecked(&accounts[10].data).unwrap();
+    decoy.token = None;
+    accounts[10] = TestAccount::owned(Pubkey::new_unique(), program_id, &decoy, PromotionData::LEN);
+    assert_eq!(
+        process_mint(&program_id, &infos(&mut accounts)),
+        err(AppError::InvalidDerivedKey)
+    );
+}
+
+#[test]
+fn recipe_rejects_inputs_redeem_cannot_burn() {
+    let program_id = nft_factory::id();
+    let authority = Pubkey::new_unique();
+    let config_info =
+        Pubkey::find_program_address(&[program_id.as_ref(), b"config"], &program_id).0;
+    let config_data = ConfigureData {
+        authority,
+        platform_fee: 0,
+        fee_recipient: authority,
+    };
+    let classic = collection(vec![creator(100)], 500);
+    let programmable = CollectionData {
+        programmable: true,
+        ..collection(vec![creator(100)], 500)
+    };
+    let token_2022 = CollectionData {
+        token_program: spl_token_2022::id(),
+        ..collection(vec![creator(100)], 500)
+    };
+
+    for (input, error) in [
+        (programmable, AppError::ProgrammableUnsupported),
+        (token_2022, AppError::InvalidTokenProgram),
+    ] {
+        let args = CreateRecipeArgs {
+            id: 1,
+            inputs: [&classic, &input]
+                .iter()
+                .map(|collection_data| RecipeInput {
+                    collection: collection_data.collection_mint,
+                    quantity: 1,
+                })
+                .collect(),
+            output: RecipeOutput::Receipt,
+        };
+        let mut accounts = vec![
+            TestAccount::signer(authority),
+            TestAccount::owned(config_info, program_id, &config_data, ConfigureData::LEN),
+            TestAccount::new(Pubkey::new_unique()),
+            TestAccount::new(sysvar::rent::id()),
+            TestAccount::new(system_program::id()),
+        ];
+        for collection_data in [&classic, &input] {
+            let collection_info = Pubkey::find_program_address(
+                &[
+                    program_id.as_ref(),
+                    collection_data.collection_mint.as_ref(),
+                    b"collection",
+                ],
+                &program_id,
+            )
+            .0;
+            accounts.push(TestAccount::owned(
+                collection_info,
+                program_id,
+                collection_data,
+                CollectionData::LEN,
+            ));
+        }
+        assert_eq!(
+            process_create_recipe(&program_id, &infos(&mut accounts), args),
+            err(error)
+        );
+    }
+}